@@ -1,39 +1,185 @@
+use crate::ai::injection::UntrustedFence;
 use crate::ai::provider::{AiProvider, ProviderHealth, ScoringContext, SubagentType, SummaryContext};
 use crate::ai::schema::{
     controversiality_json_schema, subagent_review_json_schema, summary_json_schema,
     ControversialityResponse, SubagentReviewResponse, SummaryResponse,
 };
-use crate::config::{AiConfig, AiProviderType};
+use crate::config::{AiConfig, AiProviderType, READ_ONLY_TOOLS};
 use crate::diff::FileDiff;
 use crate::error::{CraiError, CraiResult};
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::process::Command;
+use uuid::Uuid;
+
+/// Environment variables the CLI needs to locate its config and credentials
+const BASE_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "LANG",
+    "LC_ALL",
+    "TERM",
+    "TMPDIR",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_CACHE_HOME",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "SYSTEMROOT",
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_BASE_URL",
+    "ANTHROPIC_AUTH_TOKEN",
+    "CLAUDE_CONFIG_DIR",
+    "CLAUDE_CODE_USE_BEDROCK",
+    "CLAUDE_CODE_USE_VERTEX",
+    "HTTPS_PROXY",
+    "HTTP_PROXY",
+    "NO_PROXY",
+];
 
 pub struct ClaudeProvider {
     cli_path: String,
     model: Option<String>,
     timeout: Duration,
     max_retries: u32,
+    allowed_tools: Vec<String>,
+    /// Directories the allowed tools are confined to
+    read_roots: Vec<PathBuf>,
+    working_dir: WorkingDir,
+    env_passthrough: Vec<String>,
+}
+
+/// Where the CLI subprocess runs
+enum WorkingDir {
+    /// A configured or repository directory that crai does not own
+    Fixed(PathBuf),
+    /// An empty scratch directory created by crai and removed on drop
+    Scratch(PathBuf),
+}
+
+impl WorkingDir {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Fixed(p) | Self::Scratch(p) => p,
+        }
+    }
+}
+
+impl Drop for WorkingDir {
+    fn drop(&mut self) {
+        if let Self::Scratch(path) = self {
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
 }
 
 impl ClaudeProvider {
-    pub fn new(config: &AiConfig) -> CraiResult<Self> {
+    pub fn new(config: &AiConfig, repo_path: &Path) -> CraiResult<Self> {
         let cli_path = config
             .custom_cli_path
             .as_ref()
             .map(|p| p.to_string_lossy().into_owned())
             .unwrap_or_else(|| "claude".to_string());
 
+        let sandbox = &config.sandbox;
+
+        let mut allowed_tools: Vec<String> = Vec::new();
+        for tool in &sandbox.allowed_tools {
+            if READ_ONLY_TOOLS.contains(&tool.as_str()) {
+                allowed_tools.push(tool.clone());
+            } else {
+                tracing::warn!("Ignoring non read-only tool '{}' in ai.sandbox.allowed_tools", tool);
+            }
+        }
+        if sandbox.allow_repository_read {
+            for tool in ["Read", "Grep", "Glob"] {
+                if !allowed_tools.iter().any(|t| t == tool) {
+                    allowed_tools.push(tool.to_string());
+                }
+            }
+        }
+
+        let working_dir = match (&sandbox.working_directory, sandbox.allow_repository_read) {
+            (Some(dir), _) => WorkingDir::Fixed(dir.clone()),
+            (None, true) => WorkingDir::Fixed(repo_path.to_path_buf()),
+            (None, false) => {
+                let dir = std::env::temp_dir().join(format!("crai-sandbox-{}", Uuid::new_v4().simple()));
+                std::fs::create_dir_all(&dir)?;
+                WorkingDir::Scratch(dir)
+            }
+        };
+
+        // Tools may only look inside the working directory, and the repository when reading
+        // it was allowed, never elsewhere on the reviewer's machine (e.g. ~/.ssh)
+        let mut read_roots = vec![canonical(working_dir.path())];
+        if sandbox.allow_repository_read {
+            let repo = canonical(repo_path);
+            if !read_roots.contains(&repo) {
+                read_roots.push(repo);
+            }
+        }
+
         Ok(Self {
             cli_path,
             model: config.model.clone(),
             timeout: Duration::from_secs(config.timeout_seconds),
             max_retries: config.max_retries,
+            allowed_tools,
+            read_roots,
+            working_dir,
+            env_passthrough: sandbox.env_passthrough.clone(),
         })
     }
 
+    /// Build a CLI command that runs in the sandbox directory with a scrubbed environment
+    fn sandboxed_command(&self) -> Command {
+        let mut cmd = Command::new(&self.cli_path);
+        cmd.current_dir(self.working_dir.path()).env_clear();
+
+        let passthrough = BASE_ENV_VARS
+            .iter()
+            .copied()
+            .chain(self.env_passthrough.iter().map(String::as_str));
+        for var in passthrough {
+            if let Some(value) = std::env::var_os(var) {
+                cmd.env(var, value);
+            }
+        }
+
+        cmd
+    }
+
+    /// Tool permission flags: grant the allowed read-only tools under the read roots only.
+    /// `dontAsk` denies every tool that no rule allows, including ones added to the CLI
+    /// after this list was written.
+    fn tool_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--strict-mcp-config".to_string(),
+            "--permission-mode".to_string(),
+            "dontAsk".to_string(),
+        ];
+        let rules: Vec<String> = self
+            .allowed_tools
+            .iter()
+            .flat_map(|tool| self.read_roots.iter().map(move |root| scoped_rule(tool, root)))
+            .collect();
+        if !rules.is_empty() {
+            args.push("--allowedTools".to_string());
+            args.push(rules.join(","));
+        }
+        args
+    }
+
+    /// Read-only tools the agent has been granted
+    pub fn allowed_tools(&self) -> &[String] {
+        &self.allowed_tools
+    }
+
     async fn execute_with_schema<T: serde::de::DeserializeOwned>(
         &self,
         prompt: &str,
@@ -45,8 +191,9 @@ impl ClaudeProvider {
 
         let mut attempt = 0;
         loop {
-            let mut cmd = Command::new(&self.cli_path);
+            let mut cmd = self.sandboxed_command();
             cmd.args(["-p", prompt, "--output-format", "json", "--json-schema", &schema_str]);
+            cmd.args(self.tool_args());
 
             if let Some(model) = &self.model {
                 cmd.args(["--model", model]);
@@ -100,6 +247,17 @@ impl ClaudeProvider {
     }
 }
 
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Permission rule granting `tool` on everything under `root`. The CLI reads a leading `//`
+/// as an absolute path; a single `/` would be relative to its settings file.
+fn scoped_rule(tool: &str, root: &Path) -> String {
+    let root = root.to_string_lossy().replace('\\', "/");
+    format!("{}(//{}/**)", tool, root.trim_matches('/'))
+}
+
 #[async_trait]
 impl AiProvider for ClaudeProvider {
     fn provider_type(&self) -> AiProviderType {
@@ -113,31 +271,44 @@ impl AiProvider for ClaudeProvider {
         language: &str,
         context: &ScoringContext,
     ) -> CraiResult<ControversialityResponse> {
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_diff = fence.wrap(diff_text);
+        let fenced_path = fence.wrap(file_path);
         let mut prompt = format!(
             r#"Analyze this code diff and score its controversiality.
 
-## Diff Content
-```{language}
-{diff_text}
-```
+{preamble}
+
+## Diff Content ({language})
+{fenced_diff}
 
 ## Context
-- File: {file_path}
 - Language: {language}
+- File:
+{fenced_path}
 
 Score from 0.0 (trivial, auto-approvable) to 1.0 (critical, needs deep review).
 Consider: security implications, correctness risks, architectural impact, and maintainability."#
         );
 
         if let Some(ref pr_desc) = context.pr_description {
-            prompt.push_str(&format!("\n\n## PR Description\n{}", pr_desc));
+            prompt.push_str(&format!("\n\n## PR Description\n{}", fence.wrap(pr_desc)));
         }
 
         if !context.commit_messages.is_empty() {
             prompt.push_str("\n\n## Related Commits\n");
-            for msg in &context.commit_messages {
-                prompt.push_str(&format!("- {}\n", msg));
-            }
+            prompt.push_str(&fence.wrap(&context.commit_messages.join("\n")));
+        }
+
+        if !context.authors.is_empty() {
+            prompt.push_str("\n\n## Authors\n");
+            prompt.push_str(&fence.wrap(&context.authors.join(", ")));
+        }
+
+        if let Some(focus) = context.review_focus {
+            prompt.push_str("\n\n## Review Focus\n");
+            prompt.push_str(focus);
         }
 
         self.execute_with_schema(&prompt, controversiality_json_schema(), None)
@@ -157,16 +328,20 @@ Consider: security implications, correctness risks, architectural impact, and ma
             .collect::<Vec<_>>()
             .join("\n");
 
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_diff = fence.wrap(diff_text);
+        let fenced_files = fence.wrap(&files_list);
         let prompt = format!(
             r#"Review these code changes from a {} perspective.
 
+{preamble}
+
 ## Files Changed
-{files_list}
+{fenced_files}
 
 ## Diff Content
-```
-{diff_text}
-```
+{fenced_diff}
 
 {}"#,
             subagent.name(),
@@ -185,31 +360,37 @@ Consider: security implications, correctness risks, architectural impact, and ma
         let files_summary = files
             .iter()
             .map(|f| {
+                if !f.content_kind.is_text() {
+                    return format!("- {} ({})", f.path.display(), f.content_kind.describe());
+                }
                 let total_changes: usize = f.chunks.iter().map(|c| c.changes()).sum();
                 format!("- {} ({} changes)", f.path.display(), total_changes)
             })
             .collect::<Vec<_>>()
             .join("\n");
 
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_files = fence.wrap(&files_summary);
         let mut prompt = format!(
             r#"Generate a summary of these code changes for a code review.
 
+{preamble}
+
 ## Files Changed ({} files)
-{files_summary}
+{fenced_files}
 
 Provide a high-level overview, identify key changes, and assess overall risk."#,
             files.len()
         );
 
         if let Some(ref pr_desc) = context.pr_description {
-            prompt.push_str(&format!("\n\n## PR Description\n{}", pr_desc));
+            prompt.push_str(&format!("\n\n## PR Description\n{}", fence.wrap(pr_desc)));
         }
 
         if !context.commit_messages.is_empty() {
             prompt.push_str("\n\n## Commit Messages\n");
-            for msg in &context.commit_messages {
-                prompt.push_str(&format!("- {}\n", msg));
-            }
+            prompt.push_str(&fence.wrap(&context.commit_messages.join("\n")));
         }
 
         self.execute_with_schema(&prompt, summary_json_schema(), None)
@@ -219,7 +400,8 @@ Provide a high-level overview, identify key changes, and assess overall risk."#,
     async fn health_check(&self) -> CraiResult<ProviderHealth> {
         let start = std::time::Instant::now();
 
-        let output = Command::new(&self.cli_path)
+        let output = self
+            .sandboxed_command()
             .args(["--version"])
             .output()
             .await
@@ -1,3 +1,4 @@
+use crate::ai::injection::UntrustedFence;
 use crate::ai::provider::{AiProvider, ProviderHealth, ScoringContext, SubagentType, SummaryContext};
 use crate::ai::schema::{ControversialityResponse, SubagentReviewResponse, SummaryResponse};
 use crate::config::{AiConfig, AiProviderType};
@@ -9,6 +10,8 @@ use std::process::Stdio;
 use std::time::Duration;
 use tokio::process::Command;
 
+/// Runs `kiro-cli chat`. Unlike the claude provider this is not sandboxed: `ai.sandbox` is
+/// not applied, and kiro-cli's own default tool permissions let the agent read any file.
 pub struct KiroProvider {
     cli_path: String,
     model: Option<String>,
@@ -154,31 +157,44 @@ impl AiProvider for KiroProvider {
         language: &str,
         context: &ScoringContext,
     ) -> CraiResult<ControversialityResponse> {
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_diff = fence.wrap(diff_text);
+        let fenced_path = fence.wrap(file_path);
         let mut prompt = format!(
             r#"Analyze this code diff and score its controversiality.
 
-## Diff Content
-```{language}
-{diff_text}
-```
+{preamble}
+
+## Diff Content ({language})
+{fenced_diff}
 
 ## Context
-- File: {file_path}
 - Language: {language}
+- File:
+{fenced_path}
 
 Score from 0.0 (trivial, auto-approvable) to 1.0 (critical, needs deep review).
 Consider: security implications, correctness risks, architectural impact, and maintainability."#
         );
 
         if let Some(ref pr_desc) = context.pr_description {
-            prompt.push_str(&format!("\n\n## PR Description\n{}", pr_desc));
+            prompt.push_str(&format!("\n\n## PR Description\n{}", fence.wrap(pr_desc)));
         }
 
         if !context.commit_messages.is_empty() {
             prompt.push_str("\n\n## Related Commits\n");
-            for msg in &context.commit_messages {
-                prompt.push_str(&format!("- {}\n", msg));
-            }
+            prompt.push_str(&fence.wrap(&context.commit_messages.join("\n")));
+        }
+
+        if !context.authors.is_empty() {
+            prompt.push_str("\n\n## Authors\n");
+            prompt.push_str(&fence.wrap(&context.authors.join(", ")));
+        }
+
+        if let Some(focus) = context.review_focus {
+            prompt.push_str("\n\n## Review Focus\n");
+            prompt.push_str(focus);
         }
 
         let json_hint = r#"{"score": 0.5, "classification": "routine", "reasoning": "Brief explanation", "concerns": [{"category": "correctness", "description": "Issue description", "severity": "low"}], "review_depth": "glance"}
@@ -206,16 +222,20 @@ If there are no concerns, use an empty array: "concerns": []"#;
             .collect::<Vec<_>>()
             .join("\n");
 
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_diff = fence.wrap(diff_text);
+        let fenced_files = fence.wrap(&files_list);
         let prompt = format!(
             r#"You are a {} specialist. Review these code changes.
 
+{preamble}
+
 ## Files Changed
-{files_list}
+{fenced_files}
 
 ## Diff Content
-```
-{diff_text}
-```
+{fenced_diff}
 
 {}
 
@@ -245,31 +265,37 @@ If no findings, use empty array: "findings": []"#;
         let files_summary = files
             .iter()
             .map(|f| {
+                if !f.content_kind.is_text() {
+                    return format!("- {} ({})", f.path.display(), f.content_kind.describe());
+                }
                 let total_changes: usize = f.chunks.iter().map(|c| c.changes()).sum();
                 format!("- {} ({} changes)", f.path.display(), total_changes)
             })
             .collect::<Vec<_>>()
             .join("\n");
 
+        let fence = UntrustedFence::new();
+        let preamble = fence.preamble();
+        let fenced_files = fence.wrap(&files_summary);
         let mut prompt = format!(
             r#"Generate a summary of these code changes for a code review.
 
+{preamble}
+
 ## Files Changed ({} files)
-{files_summary}
+{fenced_files}
 
 Provide a high-level overview, identify key changes, and assess overall risk."#,
             files.len()
         );
 
         if let Some(ref pr_desc) = context.pr_description {
-            prompt.push_str(&format!("\n\n## PR Description\n{}", pr_desc));
+            prompt.push_str(&format!("\n\n## PR Description\n{}", fence.wrap(pr_desc)));
         }
 
         if !context.commit_messages.is_empty() {
             prompt.push_str("\n\n## Commit Messages\n");
-            for msg in &context.commit_messages {
-                prompt.push_str(&format!("- {}\n", msg));
-            }
+            prompt.push_str(&fence.wrap(&context.commit_messages.join("\n")));
         }
 
         let json_hint = r#"{"overview": "High-level summary of changes", "key_changes": [{"description": "What changed", "affected_files": ["file.rs"], "impact_level": "low"}], "risk_assessment": {"overall_risk": "low", "factors": [{"factor": "Risk factor description", "contribution": 0.3}]}}
@@ -1,4 +1,5 @@
 pub mod claude;
+pub mod injection;
 pub mod kiro;
 pub mod provider;
 pub mod schema;
@@ -1,8 +1,10 @@
 use crate::ai::schema::{ControversialityResponse, SubagentReviewResponse, SummaryResponse};
 use crate::config::{AiConfig, AiProviderType};
+use crate::diff::category::ChangeCategory;
 use crate::diff::FileDiff;
 use crate::error::{CraiError, CraiResult};
 use async_trait::async_trait;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -96,11 +98,53 @@ const USABILITY_PROMPT: &str = r#"You are a usability-focused code reviewer. Ana
 - Configuration complexity
 Focus only on usability and developer experience findings."#;
 
+/// Scoring guidance for chunks whose category policy is "review"; `None` for source code
+pub fn category_prompt(category: ChangeCategory) -> Option<&'static str> {
+    match category {
+        ChangeCategory::Source => None,
+        ChangeCategory::Test => Some(TEST_PROMPT),
+        ChangeCategory::Docs => Some(DOCS_PROMPT),
+        ChangeCategory::Config => Some(CONFIG_PROMPT),
+    }
+}
+
+const TEST_PROMPT: &str = r#"This chunk is test code. Judge it as a test rather than as production code:
+- Does it assert the behaviour it claims to, or could it pass with the code under test broken?
+- Assertions that were removed, weakened or skipped (ignored tests, loosened tolerances)
+- Flakiness: timing, ordering, shared state, network or filesystem dependence
+- Missing edge cases for the change it accompanies
+Score routine additions of well-formed tests low; score weakened or disabled tests high."#;
+
+const DOCS_PROMPT: &str = r#"This chunk is documentation. Judge it as documentation:
+- Statements that are now wrong or contradict the code
+- Changed or removed guidance users rely on (install steps, commands, configuration keys)
+- Security-relevant advice (credentials, permissions, exposed endpoints)
+Score typo fixes and rewording low."#;
+
+const CONFIG_PROMPT: &str = r#"This chunk is configuration. Judge it as configuration:
+- Changed defaults, limits, timeouts and feature flags, and what they affect at runtime
+- Permissions, secrets, credentials and network exposure
+- CI and build changes that skip checks, pin or unpin versions, or change what gets deployed
+- Environment-specific values leaking into shared configuration
+Score cosmetic reordering low."#;
+
+/// Scoring guidance for the synthetic "Dependencies" entry
+pub const DEPENDENCIES_PROMPT: &str = r#"This chunk lists dependency changes read from manifests and lockfiles, one dependency per line: removed lines are the old version, added lines the new one, with "[major]" and "[new registry ...]" flags. Judge the supply-chain risk:
+- New dependencies, especially from git, tarball or non-default registry sources
+- Major version bumps that may change behaviour or APIs
+- Downgrades, and versions pinned to a branch instead of a tag or revision
+- Unfamiliar or typosquat-looking package names
+Score routine patch and minor bumps from the default registry low."#;
+
 #[derive(Debug, Clone, Default)]
 pub struct ScoringContext {
     pub pr_description: Option<String>,
     pub commit_messages: Vec<String>,
+    /// Authors of the commits, when known
+    pub authors: Vec<String>,
     pub surrounding_code: Option<String>,
+    /// Guidance for test, docs or configuration chunks, from `category_prompt`
+    pub review_focus: Option<&'static str>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -122,10 +166,11 @@ pub struct ProviderHealth {
 pub struct AiProviderFactory;
 
 impl AiProviderFactory {
-    pub fn create(config: &AiConfig) -> CraiResult<Arc<dyn AiProvider>> {
+    /// Create the configured provider for reviewing the repository at `repo_path`
+    pub fn create(config: &AiConfig, repo_path: &Path) -> CraiResult<Arc<dyn AiProvider>> {
         match config.provider {
             AiProviderType::Claude => {
-                Ok(Arc::new(crate::ai::claude::ClaudeProvider::new(config)?))
+                Ok(Arc::new(crate::ai::claude::ClaudeProvider::new(config, repo_path)?))
             }
             AiProviderType::Kiro => {
                 Ok(Arc::new(crate::ai::kiro::KiroProvider::new(config)?))
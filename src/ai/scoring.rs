@@ -1,6 +1,11 @@
-use crate::ai::provider::{AiProvider, ScoringContext};
+use crate::ai::injection::{
+    apply_injection_findings, check_consistency, detect_injection, IntegrityFlag,
+};
+use crate::ai::provider::{category_prompt, AiProvider, ScoringContext, DEPENDENCIES_PROMPT};
 use crate::ai::schema::ControversialityResponse;
-use crate::diff::chunk::{ChunkId, DiffChunk, FileDiff, LineKind};
+use crate::config::CategoryPolicy;
+use crate::diff::chunk::{ChunkId, DiffChunk, DiffResult, LineKind};
+use crate::diff::deps::DEPENDENCIES_PATH;
 use crate::diff::filter::{ChunkFilter, FilterReason, FilterResult, FilterStats};
 use crate::error::CraiResult;
 use futures::stream::{self, StreamExt};
@@ -28,13 +33,14 @@ impl ScoringOrchestrator {
     /// Score all chunks in the diff result
     pub async fn score_all<F>(
         &self,
-        files: &[FileDiff],
+        diff_result: &DiffResult,
         context: &ScoringContext,
         mut progress_callback: F,
     ) -> CraiResult<ScoringResult>
     where
         F: FnMut(ScoringUpdate) + Send,
     {
+        let files = &diff_result.files;
         let mut all_scores = Vec::new();
         let mut chunks_to_score = Vec::new();
         let mut stats = FilterStats::default();
@@ -46,9 +52,24 @@ impl ScoringOrchestrator {
                 stats.total_chunks += 1;
                 stats.total_lines += line_count;
 
-                let filter_result = self.filter.filter_chunk(chunk, file);
-
-                if filter_result.is_filtered {
+                // Hunks already reviewed at the --since revision aren't scored again
+                let moved = diff_result
+                    .move_of(chunk.id)
+                    .and_then(|moved| self.filter.filter_moved(chunk, moved));
+                let filter_result = match &diff_result.interdiff {
+                    Some(interdiff) if interdiff.is_unchanged(chunk.id) => FilterResult {
+                        is_filtered: true,
+                        reason: Some(FilterReason::Unchanged),
+                        can_override: true,
+                    },
+                    _ => moved.unwrap_or_else(|| self.filter.filter_chunk(chunk, file)),
+                };
+
+                // Scanned before filtering, and over the whole hunk, so text aimed at the
+                // reviewer can't hide in a hunk that would be skipped, like a comment-only one
+                let injections = detect_injection(&chunk_to_diff_text(chunk));
+
+                if filter_result.is_filtered && injections.is_empty() {
                     if let Some(reason) = filter_result.reason {
                         stats.add_filtered(reason, line_count);
                     }
@@ -58,9 +79,11 @@ impl ScoringOrchestrator {
                         chunk_id: chunk.id,
                         response: None,
                         filter_result: Some(filter_result),
+                        integrity_flags: Vec::new(),
+                        unweighted_score: None,
                     });
                 } else {
-                    chunks_to_score.push((file_idx, chunk_idx, file, chunk));
+                    chunks_to_score.push((file_idx, chunk_idx, file, chunk, injections));
                 }
             }
         }
@@ -71,12 +94,22 @@ impl ScoringOrchestrator {
         // Second pass: AI scoring for non-filtered chunks
         // Process results as they stream in for real-time feedback
         let mut score_stream = stream::iter(chunks_to_score)
-            .map(|(file_idx, chunk_idx, file, chunk)| {
+            .map(|(file_idx, chunk_idx, file, chunk, injections)| {
                 let provider = Arc::clone(&self.provider);
-                let ctx = context.clone();
+                let mut ctx = context.clone();
+                let category = self.filter.category(chunk, file);
+                if file.path.as_os_str() == DEPENDENCIES_PATH {
+                    ctx.review_focus = Some(DEPENDENCIES_PROMPT);
+                } else if self.filter.policy(category) == Some(CategoryPolicy::Review) {
+                    ctx.review_focus = category_prompt(category);
+                }
                 let file_path = file.path.to_string_lossy().to_string();
+                // Code moved with edits is scored on the edits alone
+                let residual = diff_result
+                    .move_of(chunk.id)
+                    .and_then(|moved| self.filter.moved_residual(chunk, moved));
                 async move {
-                    let diff_text = chunk_to_diff_text(chunk);
+                    let diff_text = chunk_to_diff_text(residual.unwrap_or(chunk));
                     let language = file
                         .language
                         .map(|l| l.name())
@@ -91,13 +124,45 @@ impl ScoringOrchestrator {
                         )
                         .await;
 
-                    (file_idx, chunk_idx, chunk.id, file_path, response)
+                    let mut integrity_flags = Vec::new();
+                    let mut unweighted_score = None;
+                    let response = response.map(|mut resp| {
+                        // Judge the model's own answer before we adjust it
+                        if let Some(detail) = check_consistency(&resp) {
+                            integrity_flags.push(IntegrityFlag::ScoreMismatch(detail));
+                        }
+
+                        // Category demotion and per-path weights apply before the injection
+                        // floor, so neither can pull a suspected injection below it
+                        let demoted = self.filter.demote_score(resp.score, category);
+                        let weighted = self.filter.weigh_score(demoted, file);
+                        if weighted != resp.score {
+                            unweighted_score = Some(resp.score);
+                            resp.score = weighted;
+                        }
+
+                        if !injections.is_empty() {
+                            apply_injection_findings(&mut resp, &injections);
+                        }
+                        resp
+                    });
+                    // Flagged whatever the provider returned, so a chunk that makes scoring
+                    // fail is still marked
+                    if !injections.is_empty() {
+                        integrity_flags.push(IntegrityFlag::InjectionSuspected {
+                            lines: injections.len(),
+                        });
+                    }
+
+                    (file_idx, chunk_idx, chunk.id, file_path, response, integrity_flags, unweighted_score)
                 }
             })
             .buffer_unordered(self.concurrent_requests);
 
         // Process each result as it completes
-        while let Some((file_idx, chunk_idx, chunk_id, file_path, response)) = score_stream.next().await {
+        while let Some((file_idx, chunk_idx, chunk_id, file_path, response, integrity_flags, unweighted_score)) =
+            score_stream.next().await
+        {
             completed += 1;
 
             let (finding, chunk_score) = match response {
@@ -132,6 +197,8 @@ impl ScoringOrchestrator {
                         } else {
                             None
                         },
+                        integrity_flags,
+                        unweighted_score,
                     };
 
                     (Some(finding), score)
@@ -144,6 +211,8 @@ impl ScoringOrchestrator {
                         chunk_id,
                         response: None,
                         filter_result: None,
+                        integrity_flags,
+                        unweighted_score: None,
                     };
                     (None, score)
                 }
@@ -186,6 +255,10 @@ pub struct ChunkScore {
     pub chunk_id: ChunkId,
     pub response: Option<ControversialityResponse>,
     pub filter_result: Option<FilterResult>,
+    /// Prompt-injection and consistency problems found for this chunk
+    pub integrity_flags: Vec<IntegrityFlag>,
+    /// The AI's score before category demotion and `filters.path_weights` adjusted it
+    pub unweighted_score: Option<f64>,
 }
 
 impl ChunkScore {
@@ -283,6 +356,14 @@ pub struct ScoringFinding {
 fn chunk_to_diff_text(chunk: &DiffChunk) -> String {
     let mut lines = Vec::new();
 
+    // Name the enclosing definition the way git's function context does
+    if let Some(symbol) = &chunk.symbol {
+        lines.push(format!(
+            "@@ -{},{} +{},{} @@ {}",
+            chunk.old_range.start, chunk.old_range.count, chunk.new_range.start, chunk.new_range.count, symbol
+        ));
+    }
+
     for line in &chunk.lines {
         let prefix = match line.kind {
             LineKind::Context => ' ',
@@ -0,0 +1,492 @@
+use crate::config::validate::{unknown_keys, validate, ConfigIssue};
+use crate::config::{default_config_path, Config, DiffMode};
+use crate::error::{CraiError, CraiResult};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Repository config locations, relative to the repository root; the first one found is used
+pub const REPOSITORY_CONFIG_LOCATIONS: [&str; 2] = [".crai/crai.toml", "crai.toml"];
+
+/// Prefix of environment variables that override config keys. Nested keys are separated by
+/// a double underscore: `CRAI_FILTERS__CONTROVERSIALITY_THRESHOLD=0.5`.
+pub const ENV_PREFIX: &str = "CRAI_";
+
+/// Keys a profile may not set: profiles are chosen after the repository and the profile
+/// itself are known
+const PROFILE_EXCLUDED_KEYS: [&str; 3] = ["profiles", "general.default_profiles", "general.repository_path"];
+
+/// Keys a repository config may not set: they choose what runs on the reviewer's machine
+/// and what the AI agent may touch, so they stay under the reviewer's control
+const USER_ONLY_KEYS: [&str; 5] = [
+    "general.repository_path",
+    "general.cache_directory",
+    "ai.custom_cli_path",
+    "ai.sandbox",
+    "diff.difft_path",
+];
+
+/// Where an effective config value was set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Default,
+    User(PathBuf),
+    Repository(PathBuf),
+    /// Profile name
+    Profile(String),
+    /// Environment variable name
+    Environment(String),
+    /// Command-line flag
+    Cli(String),
+}
+
+impl std::fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::User(path) => write!(f, "user ({})", path.display()),
+            Self::Repository(path) => write!(f, "repository ({})", path.display()),
+            Self::Profile(name) => write!(f, "profile {}", name),
+            Self::Environment(var) => write!(f, "env {}", var),
+            Self::Cli(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// The effective config, with the origin of every value set by a layer above the defaults
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: Config,
+    /// Origin by dotted key path; keys missing here have their default value
+    pub origins: BTreeMap<String, ConfigOrigin>,
+    /// Config files that were read, in the order they were applied
+    pub files: Vec<ConfigOrigin>,
+    /// Profile applied over the config files, if any
+    pub profile: Option<String>,
+    /// Unknown keys and invalid values, in the order they were found
+    pub issues: Vec<ConfigIssue>,
+    /// Merged values, as written by the layers
+    values: Table,
+}
+
+impl LoadedConfig {
+    /// Origin of a dotted key path, or of the nearest parent a layer set as a whole
+    pub fn origin_of(&self, key: &str) -> &ConfigOrigin {
+        let mut key = key;
+        loop {
+            if let Some(origin) = self.origins.get(key) {
+                return origin;
+            }
+            match key.rfind(['.', '[']) {
+                Some(end) => key = &key[..end],
+                None => return &ConfigOrigin::Default,
+            }
+        }
+    }
+
+    /// Every effective value as `(key path, value, origin)`, in key order. Arrays and
+    /// inline tables are single entries.
+    pub fn entries(&self) -> Vec<(String, Value, &ConfigOrigin)> {
+        let mut entries = Vec::new();
+        flatten(&self.values, "", &mut |key, value| {
+            entries.push((key.to_string(), value.clone(), self.origin_of(key)));
+        });
+        entries
+    }
+}
+
+/// Builds the effective config from, in increasing precedence: defaults, the user config,
+/// the repository's `.crai/crai.toml` or `crai.toml`, the selected profile, `CRAI_*`
+/// environment variables and command-line flags. Tables are merged key by key; any other
+/// value replaces the one below.
+#[derive(Debug, Default)]
+pub struct ConfigLoader {
+    user_path: Option<PathBuf>,
+    repository: Option<PathBuf>,
+    profile: Option<String>,
+    mode: DiffMode,
+    env: Vec<(String, String)>,
+    cli: Vec<(String, Value, String)>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// User config file; a missing file is skipped
+    pub fn with_user_file(mut self, path: Option<PathBuf>) -> Self {
+        self.user_path = path;
+        self
+    }
+
+    /// Repository to look for a repository config in. Without it, `general.repository_path`
+    /// from the user config or environment is used.
+    pub fn with_repository(mut self, repo: Option<PathBuf>) -> Self {
+        self.repository = repo;
+        self
+    }
+
+    /// Profile to apply; without it, the one `general.default_profiles` names for the mode
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// What is being reviewed, to pick the default profile
+    pub fn with_mode(mut self, mode: DiffMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Environment variables, e.g. `std::env::vars()`; only `CRAI_*` ones are used
+    pub fn with_env(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX) && name.contains("__"))
+            .collect();
+        self
+    }
+
+    /// A value set by a command-line flag
+    pub fn with_cli_override(mut self, key: &str, value: impl Into<Value>, flag: &str) -> Self {
+        self.cli.push((key.to_string(), value.into(), flag.to_string()));
+        self
+    }
+
+    pub fn load(self) -> CraiResult<LoadedConfig> {
+        let defaults = Value::try_from(Config::default())
+            .map_err(|e| CraiError::Serialization(e.to_string()))?;
+        let defaults = match defaults {
+            Value::Table(table) => table,
+            _ => Table::new(),
+        };
+
+        let mut merged = defaults.clone();
+        let mut origins = BTreeMap::new();
+        let mut files = Vec::new();
+        let mut issues = Vec::new();
+        let mut check_keys = |layer: &Table, origin: &ConfigOrigin| {
+            issues.extend(unknown_keys(layer, &defaults).into_iter().map(|issue| ConfigIssue {
+                origin: origin.clone(),
+                ..issue
+            }));
+        };
+
+        if let Some(path) = self.user_path.as_ref().filter(|p| p.exists()) {
+            let origin = ConfigOrigin::User(path.clone());
+            let layer = read_layer(path)?;
+            check_keys(&layer, &origin);
+            merge(&mut merged, layer, "", &origin, &mut origins);
+            files.push(origin);
+        }
+
+        let env = env_layer(&self.env, &defaults)?;
+        // A value as the environment leaves it, before the layers that depend on it are read
+        let lookup = |merged: &Table, key: &str| {
+            let mut lookup = merged.clone();
+            for (_, key, value) in &env {
+                set_path(&mut lookup, key, value.clone());
+            }
+            get_path(&lookup, key).and_then(|v| v.as_str()).map(str::to_string)
+        };
+
+        // The repository is the --repo flag, or else the user's or environment's
+        // repository_path, which the repository config itself can't move
+        let repository = self.repository.clone().unwrap_or_else(|| {
+            lookup(&merged, "general.repository_path")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("."))
+        });
+
+        if let Some(path) = find_repository_config(&repository) {
+            let origin = ConfigOrigin::Repository(path.clone());
+            let mut layer = read_layer(&path)?;
+            let profiles: Vec<String> = match layer.get("profiles") {
+                Some(Value::Table(profiles)) => profiles.keys().map(|name| format!("profiles.{}.", name)).collect(),
+                _ => Vec::new(),
+            };
+            for prefix in std::iter::once(String::new()).chain(profiles) {
+                for key in USER_ONLY_KEYS {
+                    let key = format!("{}{}", prefix, key);
+                    if remove_path(&mut layer, &key) {
+                        tracing::warn!("{}: ignoring {}, which only the user config may set", path.display(), key);
+                    }
+                }
+            }
+            check_keys(&layer, &origin);
+            merge(&mut merged, layer, "", &origin, &mut origins);
+            files.push(origin);
+        }
+
+        let profile = match &self.profile {
+            Some(name) => Some(name.clone()),
+            None => lookup(&merged, &format!("general.default_profiles.{}", self.mode.name())),
+        };
+        let profile_table = profile
+            .as_ref()
+            .and_then(|name| get_path(&merged, "profiles")?.get(name)?.as_table().cloned());
+        if let Some(name) = self.profile.as_ref().filter(|_| profile_table.is_none()) {
+            let available: Vec<&String> = merged
+                .get("profiles")
+                .and_then(Value::as_table)
+                .map(|profiles| profiles.keys().collect())
+                .unwrap_or_default();
+            if available.is_empty() {
+                return Err(CraiError::Config(format!(
+                    "unknown profile '{}': no [profiles.<name>] tables are configured",
+                    name
+                )));
+            }
+            let available: Vec<&str> = available.iter().map(|s| s.as_str()).collect();
+            return Err(CraiError::Config(format!(
+                "unknown profile '{}'; available: {}",
+                name,
+                available.join(", ")
+            )));
+        }
+        // A default profile that doesn't exist is reported by validation
+        let profile = profile.filter(|_| profile_table.is_some());
+        let files_only = merged.clone();
+        if let (Some(name), Some(mut table)) = (&profile, profile_table) {
+            for key in PROFILE_EXCLUDED_KEYS {
+                if remove_path(&mut table, key) {
+                    tracing::warn!("profile {}: ignoring {}, which a profile can't set", name, key);
+                }
+            }
+            merge(&mut merged, table, "", &ConfigOrigin::Profile(name.clone()), &mut origins);
+        }
+
+        for (var, key, value) in &env {
+            let mut layer = Table::new();
+            set_path(&mut layer, key, value.clone());
+            check_keys(&layer, &ConfigOrigin::Environment(var.clone()));
+        }
+
+        let mut overrides: Vec<(String, Value, ConfigOrigin)> = env
+            .into_iter()
+            .map(|(var, key, value)| (key, value, ConfigOrigin::Environment(var)))
+            .collect();
+        if self.repository.is_some() {
+            let value = Value::String(repository.to_string_lossy().into_owned());
+            overrides.push(("general.repository_path".to_string(), value, ConfigOrigin::Cli("--repo".to_string())));
+        }
+        overrides.extend(
+            self.cli
+                .into_iter()
+                .map(|(key, value, flag)| (key, value, ConfigOrigin::Cli(flag))),
+        );
+
+        for (key, value, origin) in overrides {
+            set_path(&mut merged, &key, value);
+            origins.retain(|k, _| !is_within(k, &key));
+            origins.insert(key, origin);
+        }
+
+        let config = Value::Table(merged.clone())
+            .try_into::<Config>()
+            .map_err(|e| CraiError::Config(format!("invalid value from environment or flags: {}", e)))?;
+
+        let mut loaded = LoadedConfig {
+            config,
+            origins,
+            files,
+            profile,
+            issues,
+            values: merged,
+        };
+        let invalid: Vec<ConfigIssue> = validate(&loaded.config)
+            .into_iter()
+            .map(|issue| ConfigIssue {
+                origin: loaded.origin_of(&issue.key).clone(),
+                ..issue
+            })
+            .collect();
+        loaded.issues.extend(invalid);
+
+        // Other profiles are checked as they would apply over the config files
+        for (name, table) in &loaded.config.profiles {
+            if Some(name) == loaded.profile.as_ref() {
+                continue;
+            }
+            let issues = check_profile(&files_only, name, table)
+                .into_iter()
+                .map(|issue| ConfigIssue {
+                    origin: loaded.origin_of(&issue.key).clone(),
+                    ..issue
+                })
+                .collect::<Vec<_>>();
+            loaded.issues.extend(issues);
+        }
+        Ok(loaded)
+    }
+}
+
+/// Issues in a profile that isn't applied, keyed under `profiles.<name>`
+fn check_profile(base: &Table, name: &str, profile: &Table) -> Vec<ConfigIssue> {
+    let prefix = format!("profiles.{}", name);
+    let mut profile = profile.clone();
+    for key in PROFILE_EXCLUDED_KEYS {
+        remove_path(&mut profile, key);
+    }
+    let mut keys = Vec::new();
+    flatten(&profile, "", &mut |key, value| {
+        if !value.is_table() {
+            keys.push(key.to_string());
+        }
+    });
+
+    let mut merged = base.clone();
+    merge(&mut merged, profile, "", &ConfigOrigin::Profile(name.to_string()), &mut BTreeMap::new());
+    match Value::Table(merged).try_into::<Config>() {
+        Ok(config) => validate(&config)
+            .into_iter()
+            .filter(|issue| keys.iter().any(|key| is_within(&issue.key, key)))
+            .map(|issue| ConfigIssue {
+                key: format!("{}.{}", prefix, issue.key),
+                ..issue
+            })
+            .collect(),
+        Err(e) => vec![ConfigIssue::error(prefix, e.to_string().trim())],
+    }
+}
+
+/// The repository config for `repo`, searching up to the repository root
+pub fn find_repository_config(repo: &Path) -> Option<PathBuf> {
+    let repo = repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf());
+    for dir in repo.ancestors() {
+        for location in REPOSITORY_CONFIG_LOCATIONS {
+            let path = dir.join(location);
+            if path.is_file() && Some(&path) != default_config_path().as_ref() {
+                return Some(path);
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Read a config file as a table, checking it against the schema so errors name the file
+fn read_layer(path: &Path) -> CraiResult<Table> {
+    let content = std::fs::read_to_string(path)?;
+    let parse_error = |source| CraiError::ConfigParse {
+        path: path.to_path_buf(),
+        source,
+    };
+    toml::from_str::<Config>(&content).map_err(parse_error)?;
+    toml::from_str::<Table>(&content).map_err(parse_error)
+}
+
+/// Merge `layer` into `base`, recording `origin` for every value it sets
+fn merge(base: &mut Table, layer: Table, prefix: &str, origin: &ConfigOrigin, origins: &mut BTreeMap<String, ConfigOrigin>) {
+    for (key, value) in layer {
+        let path = join_key(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(layer)) => merge(base, layer, &path, origin, origins),
+            (_, value) => {
+                origins.retain(|k, _| !is_within(k, &path));
+                origins.insert(path, origin.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Set a dotted key path, creating tables on the way
+fn set_path(table: &mut Table, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let entry = table
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Table::new());
+            }
+            if let Value::Table(inner) = entry {
+                set_path(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Remove a dotted key path; returns whether it was present
+fn remove_path(table: &mut Table, key: &str) -> bool {
+    match key.split_once('.') {
+        Some((head, rest)) => table
+            .get_mut(head)
+            .and_then(|v| v.as_table_mut())
+            .is_some_and(|t| remove_path(t, rest)),
+        None => table.remove(key).is_some(),
+    }
+}
+
+fn get_path<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    match key.split_once('.') {
+        Some((head, rest)) => get_path(table.get(head)?.as_table()?, rest),
+        None => table.get(key),
+    }
+}
+
+/// `CRAI_*` variables as `(variable, key path, value)`. Values are read with the type of
+/// the default at that key, so strings need no quoting.
+fn env_layer(vars: &[(String, String)], defaults: &Table) -> CraiResult<Vec<(String, String, Value)>> {
+    let mut layer = Vec::new();
+    for (var, raw) in vars {
+        let key = var[ENV_PREFIX.len()..].to_lowercase().replace("__", ".");
+        let invalid = |expected: &str| CraiError::Config(format!("{}: expected {}, got '{}'", var, expected, raw));
+        let value = match get_path(defaults, &key) {
+            Some(Value::String(_)) => Value::String(raw.clone()),
+            Some(Value::Boolean(_)) => Value::Boolean(raw.parse().map_err(|_| invalid("true or false"))?),
+            Some(Value::Integer(_)) => Value::Integer(raw.parse().map_err(|_| invalid("an integer"))?),
+            Some(Value::Float(_)) => Value::Float(raw.parse().map_err(|_| invalid("a number"))?),
+            // Arrays take TOML syntax, or a comma-separated list of strings
+            Some(Value::Array(_)) => parse_literal(raw).filter(Value::is_array).unwrap_or_else(|| {
+                Value::Array(
+                    raw.split(',')
+                        .map(|s| Value::String(s.trim().to_string()))
+                        .filter(|s| s.as_str() != Some(""))
+                        .collect(),
+                )
+            }),
+            _ => parse_literal(raw).unwrap_or_else(|| Value::String(raw.clone())),
+        };
+        layer.push((var.clone(), key, value));
+    }
+    Ok(layer)
+}
+
+/// Parse a TOML value such as `0.5`, `true` or `["a", "b"]`
+fn parse_literal(raw: &str) -> Option<Value> {
+    toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut t| t.remove("value"))
+}
+
+/// Call `visit` with the dotted path of every non-table value
+fn flatten(table: &Table, prefix: &str, visit: &mut dyn FnMut(&str, &Value)) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            Value::Table(table) if !table.is_empty() => flatten(table, &path, visit),
+            value => visit(&path, value),
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Whether `key` is `path` or lies beneath it
+fn is_within(key: &str, path: &str) -> bool {
+    key == path || key.strip_prefix(path).is_some_and(|rest| rest.starts_with(['.', '[']))
+}
@@ -1,6 +1,10 @@
+mod layers;
 mod schema;
+mod validate;
 
+pub use layers::{find_repository_config, ConfigLoader, ConfigOrigin, LoadedConfig, ENV_PREFIX, REPOSITORY_CONFIG_LOCATIONS};
 pub use schema::*;
+pub use validate::{validate, ConfigIssue, IssueSeverity};
 
 use crate::error::{CraiError, CraiResult};
 use std::path::{Path, PathBuf};
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -11,6 +11,9 @@ pub struct Config {
     pub filters: FilterConfig,
     pub tui: TuiConfig,
     pub subagents: SubagentConfig,
+    /// Named sets of overrides, each a partial config: `[profiles.quick.ai]`. One is applied
+    /// over the config files when selected with `--profile` or by `general.default_profiles`.
+    pub profiles: BTreeMap<String, toml::Table>,
 }
 
 impl Default for Config {
@@ -22,6 +25,7 @@ impl Default for Config {
             filters: FilterConfig::default(),
             tui: TuiConfig::default(),
             subagents: SubagentConfig::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
@@ -33,6 +37,8 @@ pub struct GeneralConfig {
     pub default_base_branch: String,
     pub cache_directory: PathBuf,
     pub log_level: LogLevel,
+    /// Profile to use for each kind of review when `--profile` isn't given
+    pub default_profiles: DefaultProfiles,
 }
 
 impl Default for GeneralConfig {
@@ -44,10 +50,72 @@ impl Default for GeneralConfig {
                 .unwrap_or_else(|| PathBuf::from(".cache"))
                 .join("crai"),
             log_level: LogLevel::Info,
+            default_profiles: DefaultProfiles::default(),
         }
     }
 }
 
+/// What is being reviewed, as selected by the command-line flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffMode {
+    #[default]
+    Unstaged,
+    Staged,
+    /// `--base`/`--compare`, including `--since`
+    Branches,
+    Commit,
+    Range,
+    Patch,
+}
+
+impl DiffMode {
+    pub const ALL: [DiffMode; 6] = [
+        Self::Unstaged,
+        Self::Staged,
+        Self::Branches,
+        Self::Commit,
+        Self::Range,
+        Self::Patch,
+    ];
+
+    /// Key in `general.default_profiles`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unstaged => "unstaged",
+            Self::Staged => "staged",
+            Self::Branches => "branches",
+            Self::Commit => "commit",
+            Self::Range => "range",
+            Self::Patch => "patch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DefaultProfiles {
+    pub unstaged: Option<String>,
+    pub staged: Option<String>,
+    pub branches: Option<String>,
+    pub commit: Option<String>,
+    pub range: Option<String>,
+    pub patch: Option<String>,
+}
+
+impl DefaultProfiles {
+    pub fn for_mode(&self, mode: DiffMode) -> Option<&str> {
+        let profile = match mode {
+            DiffMode::Unstaged => &self.unstaged,
+            DiffMode::Staged => &self.staged,
+            DiffMode::Branches => &self.branches,
+            DiffMode::Commit => &self.commit,
+            DiffMode::Range => &self.range,
+            DiffMode::Patch => &self.patch,
+        };
+        profile.as_deref()
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
@@ -68,6 +136,7 @@ pub struct AiConfig {
     pub max_retries: u32,
     pub concurrent_requests: usize,
     pub custom_cli_path: Option<PathBuf>,
+    pub sandbox: AiSandboxConfig,
 }
 
 impl Default for AiConfig {
@@ -79,10 +148,29 @@ impl Default for AiConfig {
             max_retries: 3,
             concurrent_requests: 4,
             custom_cli_path: None,
+            sandbox: AiSandboxConfig::default(),
         }
     }
 }
 
+/// Tools the review agent may be granted; all of them only read from disk
+pub const READ_ONLY_TOOLS: &[&str] = &["Read", "Grep", "Glob", "LS"];
+
+/// Restrictions applied to agentic AI CLIs while they review untrusted code
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AiSandboxConfig {
+    /// Tools the agent may use. Only `READ_ONLY_TOOLS` are honoured; anything else is dropped.
+    pub allowed_tools: Vec<String>,
+    /// Directory the CLI runs in. When unset, an empty temporary directory is used
+    /// (or the repository itself if `allow_repository_read` is enabled).
+    pub working_directory: Option<PathBuf>,
+    /// Let the agent read files in the repository under review to gather its own context
+    pub allow_repository_read: bool,
+    /// Extra environment variables passed through to the CLI. All others are scrubbed.
+    pub env_passthrough: Vec<String>,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum AiProviderType {
@@ -96,43 +184,98 @@ pub enum AiProviderType {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DiffConfig {
+    /// Which tool produces the chunks under review
+    pub backend: DiffBackend,
+    /// How the repository is read: the `git` binary or in-process gitoxide
+    pub vcs: VcsBackend,
     pub difft_path: PathBuf,
     pub context_lines: u32,
     pub ignore_whitespace: bool,
     pub ignore_comments: bool,
     pub max_file_size_bytes: u64,
+    /// Include untracked, non-ignored files as added files when reviewing unstaged changes
+    pub include_untracked: bool,
+    /// Compare branches from their merge base (`base...compare`), like a pull request,
+    /// instead of directly (`base..compare`)
+    pub merge_base: bool,
+    /// Label chunks with their enclosing definition and split chunks that span several
+    /// definitions. Needs the `syntax` build feature; has no effect without it.
+    pub symbols: bool,
+    /// Compare Cargo, npm and Go manifests and lockfiles and list the dependency changes
+    /// as a "Dependencies" entry
+    pub dependencies: bool,
 }
 
 impl Default for DiffConfig {
     fn default() -> Self {
         Self {
+            backend: DiffBackend::Git,
+            vcs: VcsBackend::Cli,
             difft_path: PathBuf::from("difft"),
             context_lines: 3,
             ignore_whitespace: false,
             ignore_comments: false,
             max_file_size_bytes: 1_000_000,
+            include_untracked: true,
+            merge_base: true,
+            symbols: true,
+            dependencies: true,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffBackend {
+    /// Line-based hunks from `git diff`
+    #[default]
+    Git,
+    /// Syntax-aware hunks from difftastic, falling back to git when difft is missing
+    Difftastic,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VcsBackend {
+    /// Run the `git` binary
+    #[default]
+    Cli,
+    /// Read the repository in-process with gitoxide; needs no `git` on PATH
+    Gitoxide,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct FilterConfig {
     pub auto_filter_whitespace: bool,
+    /// Filter hunks whose tokens are unchanged once line breaks and indentation are
+    /// ignored, such as rustfmt or prettier reflows
+    pub auto_filter_formatting: bool,
+    /// Extra normalizations for formatting detection, keyed by language name
+    pub formatting_normalizations: HashMap<String, FormattingNormalization>,
     pub auto_filter_imports: bool,
     pub auto_filter_renames: bool,
+    /// Filter code moved within or between files; edits made during the move are still scored
+    pub auto_filter_moved: bool,
     pub auto_filter_generated: bool,
     pub generated_file_patterns: Vec<String>,
     pub import_patterns: HashMap<String, Vec<String>>,
     pub controversiality_threshold: f64,
+    /// Score adjustments by path or owner, applied to AI scores before the threshold
+    pub path_weights: Vec<PathWeight>,
+    /// How test, documentation and configuration changes are handled
+    pub categories: CategoryPolicies,
 }
 
 impl Default for FilterConfig {
     fn default() -> Self {
         Self {
             auto_filter_whitespace: true,
+            auto_filter_formatting: true,
+            formatting_normalizations: default_formatting_normalizations(),
             auto_filter_imports: true,
             auto_filter_renames: true,
+            auto_filter_moved: true,
             auto_filter_generated: true,
             generated_file_patterns: vec![
                 r".*\.lock$".to_string(),
@@ -145,6 +288,8 @@ impl Default for FilterConfig {
             ],
             import_patterns: default_import_patterns(),
             controversiality_threshold: 0.3,
+            path_weights: Vec::new(),
+            categories: CategoryPolicies::default(),
         }
     }
 }
@@ -189,6 +334,85 @@ fn default_import_patterns() -> HashMap<String, Vec<String>> {
     patterns
 }
 
+/// Raises or lowers the scores of chunks in matching files. When several entries match a
+/// file the last one applies, as in CODEOWNERS.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct PathWeight {
+    /// CODEOWNERS-style glob, e.g. `src/auth/**` or `*.sql`
+    pub pattern: Option<String>,
+    /// Owner as written in CODEOWNERS, e.g. `@org/security`
+    pub owner: Option<String>,
+    /// Factor the AI score is multiplied by; the result is capped at 1.0
+    pub multiplier: Option<f64>,
+    /// Lowest score a chunk in a matching file can have
+    pub floor: Option<f64>,
+}
+
+/// What happens to chunks in test code, documentation and configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CategoryPolicies {
+    pub test: CategoryPolicy,
+    pub docs: CategoryPolicy,
+    pub config: CategoryPolicy,
+    /// Factor applied to the AI score of chunks whose category is demoted
+    pub demote_multiplier: f64,
+}
+
+impl Default for CategoryPolicies {
+    fn default() -> Self {
+        Self {
+            test: CategoryPolicy::Review,
+            docs: CategoryPolicy::Demote,
+            config: CategoryPolicy::Review,
+            demote_multiplier: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryPolicy {
+    /// Drop the chunks without scoring them
+    Filter,
+    /// Score them, then scale the score by `demote_multiplier`
+    Demote,
+    /// Score them with a prompt written for the category
+    #[default]
+    Review,
+}
+
+/// Differences a formatter may introduce that formatting detection should ignore
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct FormattingNormalization {
+    /// Ignore a comma directly before a closing bracket on a later line, as added when a
+    /// list is split over several lines
+    pub trailing_commas: bool,
+    /// Treat single- and double-quoted strings with the same text as equal
+    pub quote_style: bool,
+}
+
+fn default_formatting_normalizations() -> HashMap<String, FormattingNormalization> {
+    let prettier = FormattingNormalization {
+        trailing_commas: true,
+        quote_style: true,
+    };
+    let trailing_commas = FormattingNormalization {
+        trailing_commas: true,
+        quote_style: false,
+    };
+
+    let mut normalizations = HashMap::new();
+    normalizations.insert("javascript".to_string(), prettier);
+    normalizations.insert("typescript".to_string(), prettier);
+    normalizations.insert("rust".to_string(), trailing_commas);
+    normalizations.insert("go".to_string(), trailing_commas);
+    normalizations.insert("kotlin".to_string(), trailing_commas);
+    normalizations
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct TuiConfig {
@@ -196,6 +420,10 @@ pub struct TuiConfig {
     pub show_line_numbers: bool,
     pub diff_tab_width: u8,
     pub analysis_pane_width_percent: u8,
+    /// After approving, discussing or requesting changes, move to the next unreviewed highlight
+    pub auto_advance: bool,
+    /// Whether to pick up the review saved by an earlier run of the same diff
+    pub resume_session: ResumeSession,
 }
 
 impl Default for TuiConfig {
@@ -205,6 +433,8 @@ impl Default for TuiConfig {
             show_line_numbers: true,
             diff_tab_width: 4,
             analysis_pane_width_percent: 35,
+            auto_advance: false,
+            resume_session: ResumeSession::Ask,
         }
     }
 }
@@ -218,6 +448,16 @@ pub enum ColorScheme {
     HighContrast,
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ResumeSession {
+    /// Ask before the review starts
+    #[default]
+    Ask,
+    Always,
+    Never,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct SubagentConfig {
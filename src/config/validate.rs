@@ -0,0 +1,367 @@
+use crate::config::{AiProviderType, Config, ConfigOrigin, DiffMode, READ_ONLY_TOOLS};
+use crate::diff::owners::PathPattern;
+use regex::Regex;
+use toml::{Table, Value};
+
+/// Keys that are valid but absent from the defaults because they default to `None`
+const OPTIONAL_KEYS: [&str; 9] = [
+    "ai.model",
+    "ai.custom_cli_path",
+    "ai.sandbox.working_directory",
+    "subagents.security.model",
+    "subagents.security.custom_prompt",
+    "subagents.performance.model",
+    "subagents.performance.custom_prompt",
+    "subagents.usability.model",
+    "subagents.usability.custom_prompt",
+];
+
+/// Arrays of tables, with the keys each element may have
+const TABLE_ARRAYS: [(&str, &[&str]); 1] = [("filters.path_weights", &["pattern", "owner", "multiplier", "floor"])];
+
+/// Table of named profiles, each checked against the whole schema
+const PROFILES: &str = "profiles";
+
+/// Tables keyed by language name rather than by fixed fields
+const LANGUAGE_MAPS: [&str; 2] = ["filters.import_patterns", "filters.formatting_normalizations"];
+
+const LANGUAGES: [&str; 17] = [
+    "rust", "python", "javascript", "typescript", "go", "java", "csharp", "cpp", "c", "ruby", "kotlin", "swift",
+    "yaml", "json", "toml", "markdown", "shell",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueSeverity {
+    Warning,
+    Error,
+}
+
+/// A problem found in the configuration, located by its dotted key path
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// Dotted key path, with array indices: `filters.path_weights[1].floor`
+    pub key: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+    /// Layer that set the value
+    pub origin: ConfigOrigin,
+}
+
+impl ConfigIssue {
+    pub(crate) fn error(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            key: key.into(),
+            message: message.into(),
+            suggestion: None,
+            origin: ConfigOrigin::Default,
+        }
+    }
+
+    fn warning(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            ..Self::error(key, message)
+        }
+    }
+
+    fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.key, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        if self.origin != ConfigOrigin::Default {
+            write!(f, " [{}]", self.origin)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check values that parse but make no sense: out-of-range numbers, invalid patterns,
+/// settings that contradict each other
+pub fn validate(config: &Config) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    let mut unit_range = |key: &str, value: f64, default: f64| {
+        if !(0.0..=1.0).contains(&value) {
+            issues.push(
+                ConfigIssue::error(key, format!("{} is outside 0.0-1.0", value))
+                    .suggest(format!("use a value between 0.0 and 1.0, e.g. the default {}", default)),
+            );
+        }
+    };
+    unit_range("filters.controversiality_threshold", config.filters.controversiality_threshold, 0.3);
+    unit_range("subagents.security.priority_threshold", config.subagents.security.priority_threshold, 0.5);
+    unit_range("subagents.performance.priority_threshold", config.subagents.performance.priority_threshold, 0.6);
+    unit_range("subagents.usability.priority_threshold", config.subagents.usability.priority_threshold, 0.7);
+
+    for mode in DiffMode::ALL {
+        let Some(name) = config.general.default_profiles.for_mode(mode) else { continue };
+        if !config.profiles.contains_key(name) {
+            let issue = ConfigIssue::error(
+                format!("general.default_profiles.{}", mode.name()),
+                format!("no profile named '{}'", name),
+            );
+            issues.push(match closest(name, config.profiles.keys().map(String::as_str)) {
+                Some(close) => issue.suggest(format!("did you mean '{}'?", close)),
+                None => issue.suggest(format!("add a [profiles.{}] table or remove the key", name)),
+            });
+        }
+    }
+
+    let ai = &config.ai;
+    if ai.concurrent_requests == 0 {
+        issues.push(
+            ConfigIssue::error("ai.concurrent_requests", "0 would never send a request").suggest("set it to 1 or more, e.g. 4"),
+        );
+    }
+    if ai.timeout_seconds == 0 {
+        issues.push(ConfigIssue::error("ai.timeout_seconds", "0 times out every request").suggest("e.g. 60"));
+    }
+    if ai.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+        issues.push(ConfigIssue::warning("ai.model", "empty model name").suggest("remove the key to use the provider's default"));
+    }
+    if ai.provider == AiProviderType::Custom && ai.custom_cli_path.is_none() {
+        issues.push(
+            ConfigIssue::error("ai.custom_cli_path", "required when ai.provider is \"custom\"")
+                .suggest("set it to the path of your AI CLI"),
+        );
+    }
+    let tools = ai.sandbox.allowed_tools.iter().enumerate();
+    for (idx, tool) in tools.filter(|_| ai.provider == AiProviderType::Claude) {
+        if !READ_ONLY_TOOLS.contains(&tool.as_str()) {
+            let issue = ConfigIssue::warning(
+                format!("ai.sandbox.allowed_tools[{}]", idx),
+                format!("'{}' is not a read-only tool and will be ignored", tool),
+            );
+            issues.push(match closest(tool, READ_ONLY_TOOLS.iter().copied()) {
+                Some(close) => issue.suggest(format!("did you mean '{}'?", close)),
+                None => issue.suggest(format!("allowed: {}", READ_ONLY_TOOLS.join(", "))),
+            });
+        }
+    }
+
+    if config.diff.max_file_size_bytes == 0 {
+        issues.push(ConfigIssue::warning("diff.max_file_size_bytes", "0 skips every file").suggest("e.g. 1000000"));
+    }
+
+    let filters = &config.filters;
+    for (idx, pattern) in filters.generated_file_patterns.iter().enumerate() {
+        if let Err(e) = Regex::new(pattern) {
+            issues.push(regex_issue(format!("filters.generated_file_patterns[{}]", idx), e));
+        }
+    }
+    let mut import_patterns: Vec<_> = filters.import_patterns.iter().collect();
+    import_patterns.sort_by_key(|(language, _)| language.as_str());
+    for (language, patterns) in import_patterns {
+        for (idx, pattern) in patterns.iter().enumerate() {
+            if let Err(e) = Regex::new(pattern) {
+                issues.push(regex_issue(format!("filters.import_patterns.{}[{}]", language, idx), e));
+            }
+        }
+    }
+    for map in LANGUAGE_MAPS {
+        let mut languages: Vec<&String> = match map {
+            "filters.import_patterns" => filters.import_patterns.keys().collect(),
+            _ => filters.formatting_normalizations.keys().collect(),
+        };
+        languages.sort();
+        for language in languages.into_iter().filter(|l| !LANGUAGES.contains(&l.as_str())) {
+            let issue = ConfigIssue::warning(format!("{}.{}", map, language), "unknown language; it is never used");
+            issues.push(match closest(language, LANGUAGES) {
+                Some(close) => issue.suggest(format!("did you mean '{}'?", close)),
+                None => issue.suggest(format!("languages: {}", LANGUAGES.join(", "))),
+            });
+        }
+    }
+
+    for (idx, weight) in filters.path_weights.iter().enumerate() {
+        let key = format!("filters.path_weights[{}]", idx);
+        if weight.pattern.is_none() && weight.owner.is_none() {
+            issues.push(
+                ConfigIssue::error(key.clone(), "matches nothing without a pattern or an owner")
+                    .suggest("add pattern = \"src/**\" or owner = \"@org/team\""),
+            );
+        }
+        if let Some(Err(e)) = weight.pattern.as_deref().map(PathPattern::new) {
+            issues.push(ConfigIssue::error(format!("{}.pattern", key), format!("invalid pattern: {}", e)));
+        }
+        if weight.multiplier.is_some_and(|m| m < 0.0) {
+            issues.push(ConfigIssue::error(format!("{}.multiplier", key), "must not be negative").suggest("use 0.0 or more"));
+        }
+        if let Some(floor) = weight.floor.filter(|f| !(0.0..=1.0).contains(f)) {
+            issues.push(
+                ConfigIssue::error(format!("{}.floor", key), format!("{} is outside 0.0-1.0", floor))
+                    .suggest("use a value between 0.0 and 1.0"),
+            );
+        }
+        if weight.multiplier.is_none() && weight.floor.is_none() {
+            issues.push(ConfigIssue::warning(key, "has no effect without a multiplier or a floor"));
+        }
+    }
+
+    let demote = filters.categories.demote_multiplier;
+    if demote < 0.0 {
+        issues.push(
+            ConfigIssue::error("filters.categories.demote_multiplier", "must not be negative").suggest("e.g. 0.5"),
+        );
+    } else if demote > 1.0 {
+        issues.push(
+            ConfigIssue::warning("filters.categories.demote_multiplier", "above 1.0 raises demoted scores")
+                .suggest("use a value below 1.0, e.g. 0.5"),
+        );
+    }
+
+    let tui = &config.tui;
+    if tui.analysis_pane_width_percent == 0 || tui.analysis_pane_width_percent > 100 {
+        issues.push(
+            ConfigIssue::error(
+                "tui.analysis_pane_width_percent",
+                format!("{} is not a percentage between 1 and 100", tui.analysis_pane_width_percent),
+            )
+            .suggest("e.g. 35"),
+        );
+    }
+    if tui.diff_tab_width == 0 {
+        issues.push(ConfigIssue::error("tui.diff_tab_width", "must be at least 1").suggest("e.g. 4"));
+    }
+
+    issues
+}
+
+/// Keys in a config layer that the schema doesn't have, with the closest known key.
+/// `known` is the serialized default config.
+pub fn unknown_keys(layer: &Table, known: &Table) -> Vec<ConfigIssue> {
+    let mut known = known.clone();
+    for key in OPTIONAL_KEYS {
+        insert_placeholder(&mut known, key);
+    }
+    for mode in DiffMode::ALL {
+        insert_placeholder(&mut known, &format!("general.default_profiles.{}", mode.name()));
+    }
+
+    let mut issues = Vec::new();
+    check_table(layer, &known, "", "", &mut issues);
+    // Each profile is a partial config of its own
+    if let Some(Value::Table(profiles)) = layer.get(PROFILES) {
+        for (name, profile) in profiles {
+            match profile {
+                Value::Table(profile) => {
+                    check_table(profile, &known, &format!("{}.{}.", PROFILES, name), "", &mut issues)
+                }
+                _ => issues.push(
+                    ConfigIssue::error(format!("{}.{}", PROFILES, name), "a profile must be a table")
+                        .suggest(format!("write it as [{}.{}]", PROFILES, name)),
+                ),
+            }
+        }
+    }
+    issues
+}
+
+/// Check `layer` against `known`. `path` is the schema path of `layer` and `root` what
+/// precedes it in reported keys, for tables nested in a profile.
+fn check_table(layer: &Table, known: &Table, root: &str, path: &str, issues: &mut Vec<ConfigIssue>) {
+    for (key, value) in layer {
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+
+        let Some(expected) = known.get(key) else {
+            let issue = ConfigIssue::warning(format!("{}{}", root, path), "unknown key; it is ignored");
+            issues.push(match closest(key, known.keys().map(String::as_str)) {
+                Some(close) => issue.suggest(format!("did you mean '{}'?", close)),
+                None => issue,
+            });
+            continue;
+        };
+
+        if path == PROFILES || LANGUAGE_MAPS.contains(&path.as_str()) {
+            continue;
+        }
+        if let Some((_, fields)) = TABLE_ARRAYS.iter().find(|(array, _)| *array == path) {
+            let fields: Table = fields.iter().map(|f| (f.to_string(), Value::Boolean(true))).collect();
+            for (idx, element) in value.as_array().into_iter().flatten().enumerate() {
+                let Some(element) = element.as_table() else { continue };
+                let root = format!("{}{}[{}].", root, path, idx);
+                check_table(element, &fields, &root, "", issues);
+            }
+            continue;
+        }
+        if let (Value::Table(layer), Value::Table(known)) = (value, expected) {
+            check_table(layer, known, root, &path, issues);
+        }
+    }
+}
+
+fn insert_placeholder(table: &mut Table, key: &str) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            if let Some(Value::Table(inner)) = table.get_mut(head) {
+                insert_placeholder(inner, rest);
+            }
+        }
+        None => {
+            table.entry(key.to_string()).or_insert(Value::String(String::new()));
+        }
+    }
+}
+
+fn regex_issue(key: String, error: regex::Error) -> ConfigIssue {
+    // The regex crate's message spans several lines with a caret under the problem
+    let message = error.to_string();
+    let message = message.lines().last().unwrap_or_default().trim();
+    let message = message.strip_prefix("error: ").unwrap_or(message);
+    ConfigIssue::error(key, format!("invalid regex: {}", message))
+        .suggest("escape literal characters such as . ( [ with a backslash")
+}
+
+/// The candidate closest to `name` by edit distance, if it is close enough to be a typo
+fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (edit_distance(&name.to_lowercase(), &c.to_lowercase()), c))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+/// Edit distance counting a swap of adjacent characters as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
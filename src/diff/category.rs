@@ -0,0 +1,293 @@
+use crate::diff::chunk::{ContentKind, DiffChunk, FileDiff, FileStatus, Language, LineKind};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// What a file or chunk is for, so tests, documentation and configuration can be treated
+/// differently from the code they accompany
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeCategory {
+    Source,
+    Test,
+    Docs,
+    Config,
+}
+
+impl ChangeCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Source => "source",
+            Self::Test => "test",
+            Self::Docs => "docs",
+            Self::Config => "config",
+        }
+    }
+
+    /// Category of a file from its path, following each ecosystem's naming conventions
+    pub fn of_file(file: &FileDiff) -> Self {
+        let path = &file.path;
+        if is_test_path(path, file.language) {
+            Self::Test
+        } else if is_docs_path(path, file.language) {
+            Self::Docs
+        } else if is_config_path(path, file.language) {
+            Self::Config
+        } else {
+            Self::Source
+        }
+    }
+
+    /// Category of a chunk. Changes confined to a Rust `#[cfg(test)]` or `mod tests`
+    /// module are tests even though the file itself is source.
+    pub fn of_chunk(chunk: &DiffChunk, file: &FileDiff) -> Self {
+        let category = Self::of_file(file);
+        if category != Self::Source || file.language != Some(Language::Rust) {
+            return category;
+        }
+
+        let old_modules = file.old_content.as_deref().map(rust_test_modules).unwrap_or_default();
+        let new_modules = file.new_content.as_deref().map(rust_test_modules).unwrap_or_default();
+        let inside = |modules: &[RangeInclusive<u32>], line: Option<u32>| {
+            line.is_some_and(|line| modules.iter().any(|m| m.contains(&line)))
+        };
+
+        let mut changes = chunk.lines.iter().filter(|l| l.kind != LineKind::Context).peekable();
+        if changes.peek().is_none() {
+            return category;
+        }
+        let all_test = changes.all(|line| match line.kind {
+            LineKind::Add => inside(&new_modules, line.new_line_num),
+            LineKind::Remove => inside(&old_modules, line.old_line_num),
+            LineKind::Context => true,
+        });
+
+        if all_test {
+            Self::Test
+        } else {
+            category
+        }
+    }
+}
+
+/// Source files changed without any test changing alongside them: the files behind the
+/// "no tests changed" concern. Empty when the diff touches a test anywhere.
+pub fn untested_source(files: &[FileDiff]) -> Vec<&FileDiff> {
+    let reviewable = |file: &&FileDiff| {
+        matches!(file.content_kind, ContentKind::Text)
+            && !file.chunks.is_empty()
+            && !matches!(file.status, FileStatus::Deleted)
+    };
+
+    let tests_changed = files.iter().filter(reviewable).any(|file| {
+        file.chunks
+            .iter()
+            .any(|chunk| ChangeCategory::of_chunk(chunk, file) == ChangeCategory::Test)
+    });
+    if tests_changed {
+        return Vec::new();
+    }
+
+    files
+        .iter()
+        .filter(reviewable)
+        .filter(|file| is_program(file.language) && ChangeCategory::of_file(file) == ChangeCategory::Source)
+        .collect()
+}
+
+/// Languages that hold code worth testing, as opposed to data and prose
+fn is_program(language: Option<Language>) -> bool {
+    !matches!(
+        language,
+        None | Some(Language::Yaml | Language::Json | Language::Toml | Language::Markdown | Language::Unknown)
+    )
+}
+
+const TEST_DIRS: [&str; 8] = ["test", "tests", "__tests__", "spec", "specs", "testdata", "__mocks__", "e2e"];
+
+const DOCS_DIRS: [&str; 3] = ["doc", "docs", "documentation"];
+
+const DOCS_EXTENSIONS: [&str; 5] = ["md", "markdown", "mdx", "rst", "adoc"];
+
+/// Well-known prose files at any depth, matched on the file stem
+const DOCS_STEMS: [&str; 8] = [
+    "readme",
+    "changelog",
+    "changes",
+    "contributing",
+    "license",
+    "authors",
+    "notice",
+    "code_of_conduct",
+];
+
+const CONFIG_EXTENSIONS: [&str; 9] = ["toml", "yaml", "yml", "json", "ini", "cfg", "conf", "properties", "xml"];
+
+const CONFIG_NAMES: [&str; 8] = [
+    "dockerfile",
+    "containerfile",
+    ".gitignore",
+    ".gitattributes",
+    ".dockerignore",
+    ".editorconfig",
+    ".env",
+    "docker-compose.yml",
+];
+
+fn lowercase_parts(path: &Path) -> (Vec<String>, String, String, String) {
+    let dirs = path
+        .parent()
+        .map(|p| {
+            p.components()
+                .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+    let lower = |s: Option<&std::ffi::OsStr>| s.map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+    (dirs, lower(path.file_name()), lower(path.file_stem()), lower(path.extension()))
+}
+
+fn is_test_path(path: &Path, language: Option<Language>) -> bool {
+    let (dirs, name, stem, ext) = lowercase_parts(path);
+    if dirs.iter().any(|d| TEST_DIRS.contains(&d.as_str())) {
+        return true;
+    }
+
+    // Case matters for the JVM and .NET conventions (FooTest.java, FooTests.cs)
+    let original_stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+
+    match language {
+        Some(Language::Rust) => stem == "tests" || stem == "test",
+        Some(Language::Go) => stem.ends_with("_test"),
+        Some(Language::Python) => stem.starts_with("test_") || stem.ends_with("_test") || name == "conftest.py",
+        Some(Language::JavaScript | Language::TypeScript) => {
+            stem.ends_with(".test") || stem.ends_with(".spec") || stem.contains(".test.") || stem.contains(".spec.")
+        }
+        Some(Language::Java | Language::Kotlin | Language::CSharp | Language::Swift) => {
+            original_stem.ends_with("Test") || original_stem.ends_with("Tests") || original_stem.starts_with("Test")
+        }
+        Some(Language::Ruby) => stem.ends_with("_spec") || stem.ends_with("_test"),
+        Some(Language::C | Language::Cpp) => {
+            stem.ends_with("_test") || stem.ends_with("_unittest") || stem.starts_with("test_")
+        }
+        Some(Language::Shell) => stem.ends_with("_test") || ext == "bats",
+        _ => false,
+    }
+}
+
+fn is_docs_path(path: &Path, language: Option<Language>) -> bool {
+    let (dirs, _, stem, ext) = lowercase_parts(path);
+    language == Some(Language::Markdown)
+        || DOCS_EXTENSIONS.contains(&ext.as_str())
+        || DOCS_STEMS.contains(&stem.as_str())
+        || (dirs.iter().any(|d| DOCS_DIRS.contains(&d.as_str())) && !is_program(language))
+}
+
+fn is_config_path(path: &Path, language: Option<Language>) -> bool {
+    let (dirs, name, _, ext) = lowercase_parts(path);
+    matches!(language, Some(Language::Yaml | Language::Toml | Language::Json))
+        || CONFIG_EXTENSIONS.contains(&ext.as_str())
+        || CONFIG_NAMES.contains(&name.as_str())
+        || name.starts_with(".env.")
+        || name.starts_with("dockerfile.")
+        // CI definitions such as .github/workflows/*.yml and .circleci/config.yml
+        || dirs.first().is_some_and(|d| d == ".github" || d == ".circleci")
+}
+
+/// Line ranges (1-based, inclusive) of inline test modules in Rust source: modules marked
+/// `#[cfg(test)]` and modules named `tests`. The range starts at the attribute.
+fn rust_test_modules(content: &str) -> Vec<RangeInclusive<u32>> {
+    let mut modules = Vec::new();
+    let mut cfg_test_at = None;
+    let mut open: Option<(u32, i32)> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_num = idx as u32 + 1;
+        let trimmed = line.trim();
+
+        if let Some((start, depth)) = open.as_mut() {
+            *depth += brace_balance(line);
+            if *depth <= 0 {
+                modules.push(*start..=line_num);
+                open = None;
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("#[cfg(test)]") {
+            cfg_test_at = Some(line_num);
+        }
+        let rest = trimmed.strip_prefix("#[cfg(test)]").map(str::trim_start).unwrap_or(trimmed);
+
+        if let Some(name) = module_name(rest) {
+            if cfg_test_at.is_some() || name == "tests" {
+                let depth = brace_balance(rest);
+                let start = cfg_test_at.unwrap_or(line_num);
+                if depth > 0 {
+                    open = Some((start, depth));
+                } else if rest.contains('{') {
+                    modules.push(start..=line_num);
+                }
+            }
+            cfg_test_at = None;
+        } else if !(rest.is_empty() || rest.starts_with("#[") || rest.starts_with("//")) {
+            // The attribute applied to something other than a module
+            cfg_test_at = None;
+        }
+    }
+
+    if let Some((start, _)) = open {
+        modules.push(start..=content.lines().count() as u32);
+    }
+    modules
+}
+
+/// Name of an inline module declared on this line (`mod tests {`, `pub(crate) mod it {`)
+fn module_name(line: &str) -> Option<&str> {
+    let mut rest = line;
+    if let Some(after) = rest.strip_prefix("pub") {
+        rest = after.trim_start();
+        if rest.starts_with('(') {
+            rest = rest.split_once(')').map(|(_, after)| after.trim_start())?;
+        }
+    }
+    let rest = rest.strip_prefix("mod ")?.trim_start();
+    let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    let (name, tail) = rest.split_at(end);
+    (!name.is_empty() && tail.trim_start().starts_with('{')).then_some(name)
+}
+
+/// Opening minus closing braces on a line, ignoring string and char literals and comments
+fn brace_balance(line: &str) -> i32 {
+    let mut balance = 0;
+    let mut chars = line.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => break,
+            // '{' and '}' char literals
+            '\'' => {
+                let mut lookahead = chars.clone();
+                if matches!(lookahead.next(), Some('{' | '}')) && lookahead.next() == Some('\'') {
+                    chars.next();
+                    chars.next();
+                }
+            }
+            '{' => balance += 1,
+            '}' => balance -= 1,
+            _ => {}
+        }
+    }
+
+    balance
+}
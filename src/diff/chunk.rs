@@ -1,21 +1,203 @@
-use std::path::PathBuf;
+use crate::diff::deps::DependencyDiff;
+use crate::diff::interdiff::Interdiff;
+use crate::diff::moves::MovedCode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct DiffResult {
     pub base_branch: String,
     pub compare_branch: String,
+    /// Commit the diff was taken from when comparing against the merge base (`base...compare`)
+    pub merge_base: Option<String>,
     pub files: Vec<FileDiff>,
     pub parse_errors: Vec<ParseError>,
+    /// How the hunks relate to a previously reviewed revision (`--since`)
+    pub interdiff: Option<Interdiff>,
+    /// Blocks removed in one chunk and added again in another
+    pub moves: Vec<MovedCode>,
+    /// Changes to dependency manifests and lockfiles, also listed as the synthetic
+    /// "Dependencies" file
+    pub dependencies: Option<DependencyDiff>,
+    /// What the compare side stood for when the diff was taken; `None` for patches and
+    /// repositories without commits
+    pub head: Option<ReviewedHead>,
+}
+
+/// The branch and commit behind the compare side of a diff, which saved reviews are kept by
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct ReviewedHead {
+    /// Branch name with `HEAD` resolved, e.g. `feature` or `feature (working directory)`;
+    /// the commit when HEAD is detached
+    pub name: String,
+    /// Commit at the tip; `None` for the working tree and index, which have none
+    pub sha: Option<String>,
+}
+
+impl DiffResult {
+    /// Base for display, noting the merge base when the diff was taken from it
+    pub fn base_label(&self) -> String {
+        match &self.merge_base {
+            Some(sha) => format!(
+                "{} (merge-base {})",
+                self.base_branch,
+                &sha[..sha.len().min(7)]
+            ),
+            None => self.base_branch.clone(),
+        }
+    }
+
+    /// The move a chunk takes part in, as source or destination
+    pub fn move_of(&self, chunk_id: ChunkId) -> Option<&MovedCode> {
+        self.moves.iter().find(|m| m.involves(chunk_id))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileDiff {
     pub path: PathBuf,
+    /// Source path for renamed and copied files
+    pub old_path: Option<PathBuf>,
     pub status: FileStatus,
+    /// File mode change, e.g. the executable bit being flipped
+    pub mode_change: Option<ModeChange>,
+    /// What kind of object changed; only text files carry reviewable chunks
+    pub content_kind: ContentKind,
     pub language: Option<Language>,
     pub chunks: Vec<DiffChunk>,
+    /// Full file contents before and after the change, for text files within the size limit
     pub old_content: Option<String>,
     pub new_content: Option<String>,
+    /// Size in bytes when the file exceeds `max_file_size_bytes`; such files are listed
+    /// but their chunks are skipped
+    pub oversized: Option<u64>,
+    /// Owners from CODEOWNERS, e.g. `@org/team`; empty when nobody owns the file
+    pub owners: Vec<String>,
+}
+
+impl FileDiff {
+    /// Path for display, showing the source for renames and copies ("old -> new")
+    pub fn path_label(&self) -> String {
+        match &self.old_path {
+            Some(old) if old != &self.path => {
+                format!("{} -> {}", old.display(), self.path.display())
+            }
+            _ => self.path.display().to_string(),
+        }
+    }
+}
+
+/// Kind of content a file diff describes
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ContentKind {
+    #[default]
+    Text,
+    /// Binary file; sizes in bytes, `None` when the side does not exist or could not be read
+    Binary {
+        old_size: Option<u64>,
+        new_size: Option<u64>,
+        /// Abbreviated blob ids from the diff's index line
+        old_blob: Option<String>,
+        new_blob: Option<String>,
+    },
+    /// Gitlink to a submodule commit
+    Submodule {
+        old_commit: Option<String>,
+        new_commit: Option<String>,
+        /// Submodule working tree has uncommitted changes (unstaged diffs only)
+        dirty: bool,
+        /// One-line summaries of the commits pulled in by the bump, newest first
+        commits: Vec<String>,
+    },
+    /// Symbolic link; targets are the link contents, not resolved paths
+    Symlink {
+        old_target: Option<String>,
+        new_target: Option<String>,
+    },
+}
+
+impl ContentKind {
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::Text)
+    }
+
+    /// Short marker for file lists
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Binary { .. } => "binary",
+            Self::Submodule { .. } => "submodule",
+            Self::Symlink { .. } => "symlink",
+        }
+    }
+
+    /// One-line description of the change, e.g. "binary, 1.2 KB -> 3.4 KB"
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Text => "text".to_string(),
+            Self::Binary {
+                old_size, new_size, ..
+            } => format!(
+                "binary, {} -> {}",
+                format_size(*old_size),
+                format_size(*new_size)
+            ),
+            Self::Submodule {
+                old_commit,
+                new_commit,
+                dirty,
+                commits,
+            } => {
+                let mut desc = format!(
+                    "submodule {}..{}",
+                    short_sha(old_commit.as_deref()),
+                    short_sha(new_commit.as_deref())
+                );
+                if !commits.is_empty() {
+                    desc.push_str(&format!(
+                        ", {} commit{}",
+                        commits.len(),
+                        if commits.len() == 1 { "" } else { "s" }
+                    ));
+                }
+                if *dirty {
+                    desc.push_str(", dirty");
+                }
+                desc
+            }
+            Self::Symlink {
+                old_target,
+                new_target,
+            } => match (old_target, new_target) {
+                (Some(old), Some(new)) => format!("symlink {} -> {}", old, new),
+                (None, Some(new)) => format!("symlink to {}", new),
+                (Some(old), None) => format!("symlink to {} removed", old),
+                (None, None) => "symlink".to_string(),
+            },
+        }
+    }
+}
+
+fn format_size(size: Option<u64>) -> String {
+    size.map(format_bytes).unwrap_or_else(|| "none".to_string())
+}
+
+/// Human-readable byte count, e.g. "1.2 KB"
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+fn short_sha(sha: Option<&str>) -> &str {
+    match sha {
+        Some(sha) => &sha[..sha.len().min(7)],
+        None => "none",
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,7 +206,7 @@ pub enum FileStatus {
     Deleted,
     Modified,
     Renamed { similarity_percent: u8 },
-    Copied,
+    Copied { similarity_percent: u8 },
 }
 
 impl std::fmt::Display for FileStatus {
@@ -34,7 +216,45 @@ impl std::fmt::Display for FileStatus {
             Self::Deleted => write!(f, "D"),
             Self::Modified => write!(f, "M"),
             Self::Renamed { similarity_percent } => write!(f, "R{}", similarity_percent),
-            Self::Copied => write!(f, "C"),
+            Self::Copied { similarity_percent } => write!(f, "C{}", similarity_percent),
+        }
+    }
+}
+
+/// Git file mode transition, with modes as parsed from the octal header values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeChange {
+    pub old_mode: u32,
+    pub new_mode: u32,
+}
+
+impl ModeChange {
+    const EXECUTABLE: u32 = 0o111;
+
+    /// Parse the octal mode strings from "old mode"/"new mode" header lines
+    pub fn parse(old_mode: &str, new_mode: &str) -> Option<Self> {
+        Some(Self {
+            old_mode: u32::from_str_radix(old_mode.trim(), 8).ok()?,
+            new_mode: u32::from_str_radix(new_mode.trim(), 8).ok()?,
+        })
+    }
+
+    pub fn executable_added(&self) -> bool {
+        self.old_mode & Self::EXECUTABLE == 0 && self.new_mode & Self::EXECUTABLE != 0
+    }
+
+    pub fn executable_removed(&self) -> bool {
+        self.old_mode & Self::EXECUTABLE != 0 && self.new_mode & Self::EXECUTABLE == 0
+    }
+
+    /// Short marker for file lists: "+x", "-x", or the raw octal transition
+    pub fn short_label(&self) -> String {
+        if self.executable_added() {
+            "+x".to_string()
+        } else if self.executable_removed() {
+            "-x".to_string()
+        } else {
+            format!("{:o}->{:o}", self.old_mode, self.new_mode)
         }
     }
 }
@@ -92,6 +312,22 @@ impl Language {
             .unwrap_or(Self::Unknown)
     }
 
+    /// Whether line breaks or indentation carry meaning, so a reflow can change behaviour
+    pub fn is_layout_sensitive(&self) -> bool {
+        matches!(
+            self,
+            Self::Python | Self::Yaml | Self::Toml | Self::Markdown | Self::Shell | Self::Unknown
+        )
+    }
+
+    /// Whether a line break can end a statement, as with automatic semicolon insertion
+    pub fn ends_statements_at_line_breaks(&self) -> bool {
+        matches!(
+            self,
+            Self::JavaScript | Self::TypeScript | Self::Go | Self::Kotlin | Self::Swift
+        )
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Self::Rust => "rust",
@@ -123,6 +359,9 @@ pub struct DiffChunk {
     pub new_range: LineRange,
     pub header: String,
     pub lines: Vec<DiffLine>,
+    /// Enclosing definition of the changes, e.g. `impl App::handle_action`, when the
+    /// language could be parsed
+    pub symbol: Option<String>,
 }
 
 impl DiffChunk {
@@ -137,6 +376,50 @@ impl DiffChunk {
     pub fn changes(&self) -> usize {
         self.additions() + self.deletions()
     }
+
+    /// Identity of the change that survives line shifts: the file path and the added and
+    /// removed lines, ignoring context and line numbers
+    pub fn fingerprint(&self, path: &Path) -> ChunkFingerprint {
+        hash_lines(path, self.lines.iter().filter(|l| l.kind != LineKind::Context))
+    }
+
+    /// Like [`DiffChunk::fingerprint`], but over the context lines too, so the same edit
+    /// made somewhere else in the file is a different hunk
+    pub fn fingerprint_with_context(&self, path: &Path) -> ChunkFingerprint {
+        hash_lines(path, self.lines.iter())
+    }
+}
+
+fn hash_lines<'a>(path: &Path, lines: impl Iterator<Item = &'a DiffLine>) -> ChunkFingerprint {
+    // FNV-1a, so fingerprints stay comparable across builds
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+
+    feed(path.to_string_lossy().as_bytes());
+    for line in lines {
+        feed(&[b'\n', line.kind.prefix() as u8]);
+        feed(line.content.as_bytes());
+    }
+
+    ChunkFingerprint(hash)
+}
+
+/// Content hash of a chunk; see [`DiffChunk::fingerprint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkFingerprint(pub u64);
+
+impl std::fmt::Display for ChunkFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -0,0 +1,285 @@
+use crate::diff::chunk::{DiffChunk, FileDiff, Language, LineKind};
+
+/// Comment delimiters of a language, and the literals to skip when looking for them.
+/// Doc comments (`///`, `/** */`, docstrings) use the same delimiters as ordinary comments,
+/// so they are covered too.
+#[derive(Debug, Clone, Copy)]
+pub struct CommentSyntax {
+    pub line: &'static [&'static str],
+    /// (open, close) pairs
+    pub block: &'static [(&'static str, &'static str)],
+    /// String and character literals that end on the line they start, as (open, close) pairs.
+    /// An opener with no close on its line is read as code, like a Rust lifetime.
+    pub strings: &'static [(&'static str, &'static str)],
+    /// String literals that may span lines. A delimiter that also opens a block comment,
+    /// like Python's `"""`, is a comment only when nothing but whitespace precedes it.
+    pub long_strings: &'static [(&'static str, &'static str)],
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    strings: &[("\"", "\""), ("'", "'")],
+    long_strings: &[],
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    strings: &[("\"", "\""), ("'", "'")],
+    long_strings: &[],
+};
+
+/// A block comment or string literal left open at the end of a line, with its closing delimiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Open {
+    Comment(&'static str),
+    String(&'static str),
+}
+
+/// The next comment or literal opener on a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    LineComment,
+    BlockComment(&'static str),
+    String(&'static str),
+    LongString(&'static str),
+}
+
+impl CommentSyntax {
+    /// Comment syntax for a language; `None` when it has no comments or is unknown
+    pub fn for_language(language: Language) -> Option<Self> {
+        match language {
+            Language::Rust => Some(CommentSyntax {
+                strings: &[("'", "'")],
+                long_strings: &[("\"", "\"")],
+                ..C_LIKE
+            }),
+            Language::JavaScript | Language::TypeScript => Some(CommentSyntax {
+                long_strings: &[("`", "`")],
+                ..C_LIKE
+            }),
+            Language::Go => Some(CommentSyntax {
+                long_strings: &[("`", "`")],
+                ..C_LIKE
+            }),
+            Language::Java | Language::Kotlin | Language::Swift => Some(CommentSyntax {
+                long_strings: &[("\"\"\"", "\"\"\"")],
+                ..C_LIKE
+            }),
+            Language::CSharp | Language::Cpp | Language::C => Some(C_LIKE),
+            // Docstrings are string literals, but read as comments in review
+            Language::Python => Some(CommentSyntax {
+                line: &["#"],
+                block: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+                strings: &[("\"", "\""), ("'", "'")],
+                long_strings: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+            }),
+            Language::Ruby => Some(CommentSyntax {
+                line: &["#"],
+                block: &[("=begin", "=end")],
+                strings: &[],
+                long_strings: &[("\"", "\""), ("'", "'")],
+            }),
+            Language::Shell => Some(CommentSyntax {
+                strings: &[],
+                long_strings: &[("\"", "\""), ("'", "'")],
+                ..HASH
+            }),
+            Language::Toml => Some(CommentSyntax {
+                long_strings: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+                ..HASH
+            }),
+            Language::Yaml => Some(HASH),
+            Language::Markdown => Some(CommentSyntax {
+                line: &[],
+                block: &[("<!--", "-->")],
+                strings: &[],
+                long_strings: &[],
+            }),
+            Language::Json | Language::Unknown => None,
+        }
+    }
+
+    /// Classify every line of `text`, tracking block comments and strings across lines
+    pub fn classify(&self, text: &str) -> Vec<LineClass> {
+        let mut open = None;
+        text.lines().map(|line| self.classify_line(line, &mut open)).collect()
+    }
+
+    /// Classify one line. `open` holds the block comment or string literal the line starts
+    /// in and is updated for the next line. Comment markers inside literals are ignored.
+    pub fn classify_line(&self, line: &str, open: &mut Option<Open>) -> LineClass {
+        let mut has_comment = false;
+        let mut has_code = false;
+        let mut rest = line;
+
+        loop {
+            match *open {
+                Some(Open::Comment(close)) => {
+                    has_comment = true;
+                    match rest.find(close) {
+                        Some(end) => {
+                            rest = &rest[end + close.len()..];
+                            *open = None;
+                        }
+                        None => break,
+                    }
+                }
+                Some(Open::String(close)) => {
+                    has_code = true;
+                    match find_close(rest, close) {
+                        Some(end) => {
+                            rest = &rest[end + close.len()..];
+                            *open = None;
+                        }
+                        None => break,
+                    }
+                }
+                None => {}
+            }
+
+            let Some((pos, delimiter, token)) = self.next_token(rest) else {
+                has_code |= !rest.trim().is_empty();
+                break;
+            };
+            has_code |= !rest[..pos].trim().is_empty();
+            let after = &rest[pos + delimiter.len()..];
+
+            // A docstring delimiter after code on the line opens a string instead
+            let token = match token {
+                Token::BlockComment(close) if has_code && self.long_strings.contains(&(delimiter, close)) => {
+                    Token::LongString(close)
+                }
+                token => token,
+            };
+
+            match token {
+                Token::LineComment => {
+                    has_comment = true;
+                    break;
+                }
+                Token::BlockComment(close) => {
+                    has_comment = true;
+                    rest = after;
+                    *open = Some(Open::Comment(close));
+                }
+                Token::String(close) => {
+                    has_code = true;
+                    rest = match find_close(after, close) {
+                        Some(end) => &after[end + close.len()..],
+                        None => after,
+                    };
+                }
+                Token::LongString(close) => {
+                    has_code = true;
+                    rest = after;
+                    *open = Some(Open::String(close));
+                }
+            }
+        }
+
+        match (has_code, has_comment) {
+            (true, _) => LineClass::Code,
+            (false, true) => LineClass::Comment,
+            (false, false) => LineClass::Blank,
+        }
+    }
+
+    /// Earliest comment or literal opener in `text`, the longest when several start at the
+    /// same place, and a block comment over a string with the same delimiter
+    fn next_token(&self, text: &str) -> Option<(usize, &'static str, Token)> {
+        let line = self.line.iter().map(|open| (*open, Token::LineComment));
+        let block = self.block.iter().map(|(open, close)| (*open, Token::BlockComment(close)));
+        let strings = self.strings.iter().map(|(open, close)| (*open, Token::String(close)));
+        let long_strings = self
+            .long_strings
+            .iter()
+            .map(|(open, close)| (*open, Token::LongString(close)));
+
+        line.chain(block)
+            .chain(long_strings)
+            .chain(strings)
+            .filter_map(|(open, token)| text.find(open).map(|pos| (pos, open, token)))
+            .min_by_key(|(pos, open, _)| (*pos, std::cmp::Reverse(open.len())))
+    }
+
+    /// Whether every changed line of the chunk is a comment or blank, with at least one
+    /// comment. Uses the full file contents when loaded, so hunks starting inside a block
+    /// comment are classified correctly; otherwise scans the hunk on its own.
+    pub fn is_comment_only(&self, chunk: &DiffChunk, file: &FileDiff) -> bool {
+        let old_classes = file.old_content.as_deref().map(|c| self.classify(c));
+        let new_classes = file.new_content.as_deref().map(|c| self.classify(c));
+
+        // Hunk-local fallback, one state per side: what is open, and whether a changed line
+        // was read as comment only because of a block opened in this hunk
+        let mut old_side = (None, false);
+        let mut new_side = (None, false);
+
+        let mut any_comment = false;
+        for line in &chunk.lines {
+            let (classes, line_num, side) = match line.kind {
+                LineKind::Remove => (&old_classes, line.old_line_num, &mut old_side),
+                LineKind::Add => (&new_classes, line.new_line_num, &mut new_side),
+                LineKind::Context => {
+                    // Keep both fallback states in step with the shared lines
+                    for side in [&mut old_side, &mut new_side] {
+                        self.classify_line(&line.content, &mut side.0);
+                        if side.0.is_none() {
+                            side.1 = false;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let from_file = classes
+                .as_ref()
+                .zip(line_num)
+                .and_then(|(classes, num)| classes.get(num.checked_sub(1)? as usize).copied());
+            let local = self.classify_line(&line.content, &mut side.0);
+            match side.0 {
+                None => side.1 = false,
+                Some(Open::Comment(_)) => side.1 |= from_file.is_none() && local == LineClass::Comment,
+                Some(Open::String(_)) => {}
+            }
+
+            match from_file.unwrap_or(local) {
+                LineClass::Code => return false,
+                LineClass::Comment => any_comment = true,
+                LineClass::Blank => {}
+            }
+        }
+
+        // A block comment opened in the hunk and still open at its end may be a misread, like
+        // a `/*` in a string that began before the hunk, so its lines count as code
+        if old_side.1 || new_side.1 {
+            return false;
+        }
+
+        any_comment
+    }
+}
+
+/// Byte offset of the first `close` in `text` not escaped by a backslash
+fn find_close(text: &str, close: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[idx..].starts_with(close) {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// What a single line consists of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineClass {
+    Blank,
+    Comment,
+    Code,
+}
@@ -0,0 +1,800 @@
+use crate::diff::chunk::{ChunkId, ContentKind, DiffChunk, DiffLine, FileDiff, FileStatus, LineKind, LineRange};
+use serde_json::Value as Json;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Path of the synthetic file-tree entry listing dependency changes
+pub const DEPENDENCIES_PATH: &str = "Dependencies";
+
+const CRATES_IO: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Go,
+}
+
+impl Ecosystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Go => "go",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifestKind {
+    CargoToml,
+    CargoLock,
+    PackageJson,
+    PackageLock,
+    GoMod,
+    GoSum,
+}
+
+impl ManifestKind {
+    fn of_path(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            "Cargo.toml" => Some(Self::CargoToml),
+            "Cargo.lock" => Some(Self::CargoLock),
+            "package.json" => Some(Self::PackageJson),
+            "package-lock.json" | "npm-shrinkwrap.json" => Some(Self::PackageLock),
+            "go.mod" => Some(Self::GoMod),
+            "go.sum" => Some(Self::GoSum),
+            _ => None,
+        }
+    }
+
+    fn ecosystem(&self) -> Ecosystem {
+        match self {
+            Self::CargoToml | Self::CargoLock => Ecosystem::Cargo,
+            Self::PackageJson | Self::PackageLock => Ecosystem::Npm,
+            Self::GoMod | Self::GoSum => Ecosystem::Go,
+        }
+    }
+
+    /// Manifests list what the project asks for; lockfiles also pin transitive dependencies
+    fn is_lockfile(&self) -> bool {
+        matches!(self, Self::CargoLock | Self::PackageLock | Self::GoSum)
+    }
+
+    fn parse(&self, content: &str) -> Result<Dependencies, String> {
+        match self {
+            Self::CargoToml => parse_cargo_toml(content),
+            Self::CargoLock => parse_cargo_lock(content),
+            Self::PackageJson => parse_package_json(content),
+            Self::PackageLock => parse_package_lock(content),
+            Self::GoMod => parse_go_mod(content),
+            Self::GoSum => parse_go_sum(content),
+        }
+    }
+}
+
+/// Whether a path is a manifest or lockfile whose dependency changes are analysed
+pub fn is_manifest(path: &Path) -> bool {
+    ManifestKind::of_path(path).is_some()
+}
+
+/// A version requirement or pinned version, with where it comes from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DependencyVersion {
+    pub version: String,
+    /// Registry, git or path source; `None` for the ecosystem's default registry
+    pub source: Option<String>,
+}
+
+impl std::fmt::Display for DependencyVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{} ({})", self.version, source),
+            None => write!(f, "{}", self.version),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyChangeKind {
+    Added,
+    Removed,
+    Upgraded,
+    Downgraded,
+    SourceChanged,
+    /// A requirement changed in a way that has no order, e.g. `^1.2` to `>=1.0, <3`
+    Changed,
+}
+
+impl DependencyChangeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Upgraded => "upgraded",
+            Self::Downgraded => "downgraded",
+            Self::SourceChanged => "source changed",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyChange {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// Manifest or lockfile the change was read from
+    pub manifest: PathBuf,
+    /// Declared in a manifest, as opposed to pinned in a lockfile
+    pub direct: bool,
+    pub kind: DependencyChangeKind,
+    pub old: Option<DependencyVersion>,
+    pub new: Option<DependencyVersion>,
+    /// The version crossed a semver-incompatible boundary (a major, or a minor below 1.0)
+    pub major_bump: bool,
+    /// A registry or git source not used anywhere in the old version of the manifest
+    pub new_registry: Option<String>,
+}
+
+impl DependencyChange {
+    pub fn is_flagged(&self) -> bool {
+        self.major_bump || self.new_registry.is_some()
+    }
+
+    /// Flags for display, e.g. "major, new registry https://npm.example.com"
+    pub fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.major_bump {
+            flags.push("major".to_string());
+        }
+        if let Some(registry) = &self.new_registry {
+            flags.push(format!("new registry {}", registry));
+        }
+        flags.join(", ")
+    }
+
+    /// One-line description, e.g. "serde 1.0.190 -> 2.0.0 (upgraded)"
+    pub fn describe(&self) -> String {
+        let versions = match (&self.old, &self.new) {
+            (Some(old), Some(new)) => format!("{} -> {}", old, new),
+            (None, Some(new)) => new.to_string(),
+            (Some(old), None) => old.to_string(),
+            (None, None) => String::new(),
+        };
+        format!("{} {} ({})", self.name, versions, self.kind.label())
+    }
+}
+
+/// A manifest or lockfile that could not be parsed, so its changes are unknown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+    pub manifest: PathBuf,
+    pub message: String,
+}
+
+impl ManifestError {
+    /// One-line description, e.g. "could not parse Cargo.lock: new version: ..."
+    pub fn describe(&self) -> String {
+        format!("could not parse {}: {}", self.manifest.display(), self.message)
+    }
+}
+
+/// Structured changes to dependency manifests and lockfiles across the diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyDiff {
+    pub changes: Vec<DependencyChange>,
+    /// Manifests left out because either version failed to parse
+    pub errors: Vec<ManifestError>,
+}
+
+impl DependencyDiff {
+    /// Compare the old and new contents of each changed manifest. Files that aren't
+    /// manifests are ignored; a missing side counts as an empty manifest. A manifest
+    /// that fails to parse on either side is recorded as an error rather than compared.
+    pub fn compare<'a>(manifests: impl IntoIterator<Item = (&'a Path, Option<&'a str>, Option<&'a str>)>) -> Self {
+        let mut diff = Self::default();
+        for (path, old, new) in manifests {
+            let Some(kind) = ManifestKind::of_path(path) else {
+                continue;
+            };
+            let parse = |side: &str, content: Option<&str>| {
+                content
+                    .map(|c| kind.parse(c))
+                    .transpose()
+                    .map(Option::unwrap_or_default)
+                    .map_err(|err| format!("{} version: {}", side, err))
+            };
+            match (parse("old", old), parse("new", new)) {
+                (Ok(old), Ok(new)) => diff.changes.extend(compare_dependencies(kind, path, &old, &new)),
+                (old, new) => diff.errors.push(ManifestError {
+                    manifest: path.to_path_buf(),
+                    message: [old.err(), new.err()].into_iter().flatten().collect::<Vec<_>>().join("; "),
+                }),
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.errors.is_empty()
+    }
+
+    pub fn count(&self, kind: DependencyChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+
+    pub fn flagged(&self) -> impl Iterator<Item = &DependencyChange> {
+        self.changes.iter().filter(|c| c.is_flagged())
+    }
+
+    /// Counts by kind, e.g. "3 added, 1 upgraded, 1 manifest not parsed"
+    pub fn describe(&self) -> String {
+        use DependencyChangeKind::*;
+        let mut counts: Vec<String> = [Added, Removed, Upgraded, Downgraded, SourceChanged, Changed]
+            .iter()
+            .filter_map(|&kind| {
+                let count = self.count(kind);
+                (count > 0).then(|| format!("{} {}", count, kind.label()))
+            })
+            .collect();
+        match self.errors.len() {
+            0 => {}
+            1 => counts.push("1 manifest not parsed".to_string()),
+            n => counts.push(format!("{} manifests not parsed", n)),
+        }
+        counts.join(", ")
+    }
+
+    /// The synthetic "Dependencies" file: one chunk per manifest, the old version of each
+    /// changed dependency as a removed line and the new one as an added line. A manifest
+    /// that could not be parsed gets a context line saying so instead.
+    pub fn to_file_diff(&self, first_chunk_id: u64) -> FileDiff {
+        let mut by_manifest: BTreeMap<&Path, (Option<&ManifestError>, Vec<&DependencyChange>)> = BTreeMap::new();
+        for error in &self.errors {
+            by_manifest.entry(&error.manifest).or_default().0 = Some(error);
+        }
+        for change in &self.changes {
+            by_manifest.entry(&change.manifest).or_default().1.push(change);
+        }
+
+        let mut chunks = Vec::new();
+        let mut line_num = 1;
+        for (idx, (manifest, (error, changes))) in by_manifest.into_iter().enumerate() {
+            let start = line_num;
+            let mut lines = Vec::new();
+            let mut push = |kind: LineKind, content: String| {
+                lines.push(DiffLine {
+                    kind,
+                    old_line_num: (kind != LineKind::Add).then_some(line_num),
+                    new_line_num: (kind != LineKind::Remove).then_some(line_num),
+                    content,
+                });
+                line_num += 1;
+            };
+
+            if let Some(error) = error {
+                push(LineKind::Context, error.describe());
+            }
+            for change in changes {
+                let flags = if change.is_flagged() {
+                    format!("  [{}]", change.flags())
+                } else {
+                    String::new()
+                };
+                if let Some(old) = &change.old {
+                    push(LineKind::Remove, format!("{} {}", change.name, old));
+                }
+                if let Some(new) = &change.new {
+                    push(LineKind::Add, format!("{} {}{}", change.name, new, flags));
+                }
+            }
+
+            let range = LineRange {
+                start,
+                count: line_num - start,
+            };
+            chunks.push(DiffChunk {
+                id: ChunkId(first_chunk_id + idx as u64),
+                old_range: range,
+                new_range: range,
+                header: manifest.display().to_string(),
+                lines,
+                symbol: None,
+            });
+        }
+
+        FileDiff {
+            path: PathBuf::from(DEPENDENCIES_PATH),
+            old_path: None,
+            status: FileStatus::Modified,
+            mode_change: None,
+            content_kind: ContentKind::Text,
+            language: None,
+            chunks,
+            old_content: None,
+            new_content: None,
+            oversized: None,
+            owners: Vec::new(),
+        }
+    }
+}
+
+/// Versions of each dependency in one manifest; lockfiles may hold several per name
+type Dependencies = BTreeMap<String, BTreeSet<DependencyVersion>>;
+
+fn compare_dependencies(
+    manifest_kind: ManifestKind,
+    manifest: &Path,
+    old: &Dependencies,
+    new: &Dependencies,
+) -> Vec<DependencyChange> {
+    let ecosystem = manifest_kind.ecosystem();
+    let known_registries: BTreeSet<String> = old
+        .values()
+        .flatten()
+        .filter_map(|v| registry_of(ecosystem, v))
+        .collect();
+
+    let change = |name: &str, old: Option<&DependencyVersion>, new: Option<&DependencyVersion>| {
+        let kind = match (old, new) {
+            (None, _) => DependencyChangeKind::Added,
+            (_, None) => DependencyChangeKind::Removed,
+            (Some(old), Some(new)) => match compare_versions(&old.version, &new.version) {
+                Some(Ordering::Less) => DependencyChangeKind::Upgraded,
+                Some(Ordering::Greater) => DependencyChangeKind::Downgraded,
+                _ if old.source != new.source => DependencyChangeKind::SourceChanged,
+                _ => DependencyChangeKind::Changed,
+            },
+        };
+        let major_bump = match (old, new) {
+            (Some(old), Some(new)) => crosses_major(&old.version, &new.version),
+            _ => false,
+        };
+        let new_registry = new
+            .and_then(|v| registry_of(ecosystem, v))
+            .filter(|r| !known_registries.contains(r));
+
+        DependencyChange {
+            ecosystem,
+            name: name.to_string(),
+            manifest: manifest.to_path_buf(),
+            direct: !manifest_kind.is_lockfile(),
+            kind,
+            old: old.cloned(),
+            new: new.cloned(),
+            major_bump,
+            new_registry,
+        }
+    };
+
+    let empty = BTreeSet::new();
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut changes = Vec::new();
+    for name in names {
+        let old_versions = old.get(name).unwrap_or(&empty);
+        let new_versions = new.get(name).unwrap_or(&empty);
+        let removed: Vec<_> = old_versions.difference(new_versions).collect();
+        let added: Vec<_> = new_versions.difference(old_versions).collect();
+
+        // Pair old and new versions in order; any surplus was added or removed outright
+        for idx in 0..removed.len().max(added.len()) {
+            changes.push(change(name, removed.get(idx).copied(), added.get(idx).copied()));
+        }
+    }
+    changes
+}
+
+/// Registry or git repository a version comes from, when it isn't the default registry
+/// or a local path
+fn registry_of(ecosystem: Ecosystem, version: &DependencyVersion) -> Option<String> {
+    let source = version.source.as_deref()?;
+    if source.starts_with("path+") || source.starts_with("file:") || source.starts_with("link:") {
+        return None;
+    }
+
+    match ecosystem {
+        // Git sources carry the revision after '#' and the branch or tag after '?'
+        Ecosystem::Cargo => Some(source.split(['#', '?']).next().unwrap_or(source).to_string()),
+        Ecosystem::Npm => {
+            let source = source.split('#').next().unwrap_or(source);
+            match source.split_once("://") {
+                Some((scheme, rest)) if scheme == "https" || scheme == "http" => {
+                    let host = rest.split('/').next().unwrap_or(rest);
+                    Some(format!("{}://{}", scheme, host))
+                }
+                _ => Some(source.to_string()),
+            }
+        }
+        // Go modules are fetched from their import path; a replacement points elsewhere
+        Ecosystem::Go => {
+            let module = source.split_whitespace().next().unwrap_or(source);
+            (!module.starts_with('.') && !module.starts_with('/')).then(|| module.to_string())
+        }
+    }
+}
+
+/// Numeric version components, ignoring requirement operators, a leading `v` and any
+/// pre-release or build suffix. `None` for requirements that aren't a single version.
+fn version_numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version
+        .trim()
+        .trim_start_matches(['^', '~', '=', '>', '<', ' '])
+        .trim_start_matches('v');
+    if version.contains([',', '|', ' ']) {
+        return None;
+    }
+    let core = version.split(['-', '+']).next()?;
+    let numbers: Vec<u64> = core
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect();
+    (!numbers.is_empty()).then_some(numbers)
+}
+
+fn compare_versions(old: &str, new: &str) -> Option<Ordering> {
+    let (old, new) = (version_numbers(old)?, version_numbers(new)?);
+    let len = old.len().max(new.len());
+    let pad = |v: Vec<u64>| v.into_iter().chain(std::iter::repeat(0)).take(len).collect::<Vec<_>>();
+    match pad(old).cmp(&pad(new)) {
+        Ordering::Equal => None,
+        ordering => Some(ordering),
+    }
+}
+
+/// Whether two versions are semver-incompatible: a different major, or for 0.x a
+/// different minor (0.0.x: a different patch)
+fn crosses_major(old: &str, new: &str) -> bool {
+    let (Some(old), Some(new)) = (version_numbers(old), version_numbers(new)) else {
+        return false;
+    };
+    let significant = |v: &[u64]| {
+        let first_nonzero = v.iter().position(|&n| n != 0).unwrap_or(v.len() - 1);
+        v[..=first_nonzero.min(2)].to_vec()
+    };
+    significant(&old) != significant(&new)
+}
+
+fn insert(deps: &mut Dependencies, name: &str, version: String, source: Option<String>) {
+    deps.entry(name.to_string())
+        .or_default()
+        .insert(DependencyVersion { version, source });
+}
+
+/// A TOML error on one line, e.g. "line 3: invalid string"
+fn toml_error(content: &str, err: toml::de::Error) -> String {
+    let line = err.span().map_or(1, |span| content[..span.start].matches('\n').count() + 1);
+    format!("line {}: {}", line, err.message())
+}
+
+fn parse_cargo_lock(content: &str) -> Result<Dependencies, String> {
+    let mut deps = Dependencies::new();
+    let lock = content.parse::<toml::Table>().map_err(|err| toml_error(content, err))?;
+    for package in lock.get("package").and_then(|p| p.as_array()).into_iter().flatten() {
+        let field = |key: &str| package.get(key).and_then(|v| v.as_str());
+        // Packages without a source are the workspace's own crates
+        if let (Some(name), Some(version), Some(source)) = (field("name"), field("version"), field("source")) {
+            let source = (!CRATES_IO.contains(&source)).then(|| source.to_string());
+            insert(&mut deps, name, version.to_string(), source);
+        }
+    }
+    Ok(deps)
+}
+
+fn parse_cargo_toml(content: &str) -> Result<Dependencies, String> {
+    const SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+    let mut deps = Dependencies::new();
+    let manifest = content.parse::<toml::Table>().map_err(|err| toml_error(content, err))?;
+
+    let mut tables = Vec::new();
+    for section in SECTIONS {
+        tables.extend(manifest.get(section));
+    }
+    tables.extend(manifest.get("workspace").and_then(|w| w.get("dependencies")));
+    for target in manifest.get("target").and_then(|t| t.as_table()).into_iter().flat_map(|t| t.values()) {
+        for section in SECTIONS {
+            tables.extend(target.get(section));
+        }
+    }
+
+    for (name, spec) in tables.into_iter().filter_map(|t| t.as_table()).flatten() {
+        // The first declaration wins when a crate is listed in several sections
+        if deps.contains_key(name) {
+            continue;
+        }
+        let (version, source) = match spec {
+            toml::Value::String(version) => (version.clone(), None),
+            toml::Value::Table(spec) => {
+                let field = |key: &str| spec.get(key).and_then(|v| v.as_str());
+                let version = if spec.get("workspace").and_then(|v| v.as_bool()) == Some(true) {
+                    "workspace".to_string()
+                } else {
+                    field("version").unwrap_or("*").to_string()
+                };
+                let source = if let Some(git) = field("git") {
+                    let reference = ["rev", "tag", "branch"]
+                        .iter()
+                        .find_map(|key| field(key).map(|value| format!("?{}={}", key, value)))
+                        .unwrap_or_default();
+                    Some(format!("git+{}{}", git, reference))
+                } else if let Some(path) = field("path") {
+                    Some(format!("path+{}", path))
+                } else {
+                    field("registry").map(|registry| format!("registry+{}", registry))
+                };
+                (version, source)
+            }
+            _ => continue,
+        };
+        insert(&mut deps, name, version, source);
+    }
+    Ok(deps)
+}
+
+fn parse_package_json(content: &str) -> Result<Dependencies, String> {
+    const SECTIONS: [&str; 4] = [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ];
+
+    let mut deps = Dependencies::new();
+    let manifest = serde_json::from_str::<Json>(content).map_err(|err| err.to_string())?;
+
+    for section in SECTIONS {
+        let Some(entries) = manifest.get(section).and_then(|s| s.as_object()) else {
+            continue;
+        };
+        for (name, spec) in entries {
+            let Some(spec) = spec.as_str() else { continue };
+            if deps.contains_key(name) {
+                continue;
+            }
+            // Git, tarball and local specs name their source instead of a version
+            let is_source = spec.contains("://")
+                || (spec.contains(':') && !spec.starts_with("npm:"))
+                || spec.starts_with('.')
+                || spec.starts_with('/');
+            if is_source {
+                insert(&mut deps, name, "*".to_string(), Some(spec.to_string()));
+            } else {
+                insert(&mut deps, name, spec.to_string(), None);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+fn parse_package_lock(content: &str) -> Result<Dependencies, String> {
+    let mut deps = Dependencies::new();
+    let lock = serde_json::from_str::<Json>(content).map_err(|err| err.to_string())?;
+
+    // Tarballs from the default registry are left without a source, like manifest versions
+    let resolved = |entry: &Json| {
+        entry
+            .get("resolved")
+            .and_then(|r| r.as_str())
+            .filter(|r| !r.starts_with(NPM_REGISTRY))
+            .map(str::to_string)
+    };
+
+    // lockfileVersion 2 and 3: a flat map keyed by install path
+    if let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (path, entry) in packages {
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if entry.get("link").and_then(|l| l.as_bool()) == Some(true) {
+                continue;
+            }
+            if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+                insert(&mut deps, name, version.to_string(), resolved(entry));
+            }
+        }
+        return Ok(deps);
+    }
+
+    // lockfileVersion 1: nested "dependencies" objects
+    fn walk(entries: &serde_json::Map<String, Json>, deps: &mut Dependencies, resolved: &dyn Fn(&Json) -> Option<String>) {
+        for (name, entry) in entries {
+            if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+                insert(deps, name, version.to_string(), resolved(entry));
+            }
+            if let Some(nested) = entry.get("dependencies").and_then(|d| d.as_object()) {
+                walk(nested, deps, resolved);
+            }
+        }
+    }
+    if let Some(entries) = lock.get("dependencies").and_then(|d| d.as_object()) {
+        walk(entries, &mut deps, &resolved);
+    }
+    Ok(deps)
+}
+
+fn parse_go_mod(content: &str) -> Result<Dependencies, String> {
+    let mut requires = Vec::new();
+    let mut replaces: BTreeMap<String, String> = BTreeMap::new();
+    let mut block = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == ")" {
+            block = None;
+            continue;
+        }
+
+        let (directive, rest) = match block {
+            Some(directive) => (directive, line),
+            None => {
+                let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let rest = rest.trim();
+                if rest == "(" {
+                    block = Some(directive);
+                    continue;
+                }
+                (directive, rest)
+            }
+        };
+
+        match directive {
+            "require" => {
+                let mut fields = rest.split_whitespace();
+                let (Some(module), Some(version)) = (fields.next(), fields.next()) else {
+                    return Err(format!("line {}: require without a version", idx + 1));
+                };
+                requires.push((module.to_string(), version.to_string()));
+            }
+            "replace" => {
+                let Some((from, to)) = rest.split_once("=>") else {
+                    return Err(format!("line {}: replace without \"=>\"", idx + 1));
+                };
+                let from = from.split_whitespace().next().unwrap_or_default();
+                replaces.insert(from.to_string(), to.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+    if let Some(directive) = block {
+        return Err(format!("unclosed {} block", directive));
+    }
+
+    let mut deps = Dependencies::new();
+    for (module, version) in requires {
+        let source = replaces.get(&module).cloned();
+        insert(&mut deps, &module, version, source);
+    }
+    Ok(deps)
+}
+
+fn parse_go_sum(content: &str) -> Result<Dependencies, String> {
+    let mut deps = Dependencies::new();
+    for (idx, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            [] => {}
+            [module, version, _hash] => {
+                let version = version.trim_end_matches("/go.mod");
+                insert(&mut deps, module, version.to_string(), None);
+            }
+            _ => return Err(format!("line {}: expected module, version and hash", idx + 1)),
+        }
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(path: &str, old: &str, new: &str) -> DependencyDiff {
+        DependencyDiff::compare([(Path::new(path), Some(old), Some(new))])
+    }
+
+    fn only_change(diff: &DependencyDiff) -> &DependencyChange {
+        assert!(diff.errors.is_empty(), "unexpected errors: {:?}", diff.errors);
+        assert_eq!(diff.changes.len(), 1, "expected one change: {:?}", diff.changes);
+        &diff.changes[0]
+    }
+
+    #[test]
+    fn major_bump_is_flagged() {
+        let diff = compare(
+            "Cargo.toml",
+            "[dependencies]\nserde = \"1.0.190\"\n",
+            "[dependencies]\nserde = \"2.0.0\"\n",
+        );
+        let change = only_change(&diff);
+        assert_eq!(change.kind, DependencyChangeKind::Upgraded);
+        assert!(change.major_bump);
+    }
+
+    #[test]
+    fn minor_bump_below_one_is_major() {
+        let manifest = |version: &str| format!("[dependencies]\nrand = \"{}\"\n", version);
+        let diff = compare("Cargo.toml", &manifest("0.7.3"), &manifest("0.8.0"));
+        assert!(only_change(&diff).major_bump);
+
+        let diff = compare("Cargo.toml", &manifest("0.8.0"), &manifest("0.8.5"));
+        assert!(!only_change(&diff).major_bump);
+    }
+
+    #[test]
+    fn git_revision_change_is_not_a_new_registry() {
+        let lock = |rev: &str| {
+            format!(
+                "version = 3\n\n[[package]]\nname = \"tool\"\nversion = \"0.1.0\"\n\
+                 source = \"git+https://github.com/example/tool?branch=main#{}\"\n",
+                rev
+            )
+        };
+        let diff = compare("Cargo.lock", &lock("1111111"), &lock("2222222"));
+        let change = only_change(&diff);
+        assert_eq!(change.kind, DependencyChangeKind::SourceChanged);
+        assert!(!change.direct);
+        assert_eq!(change.new_registry, None);
+    }
+
+    #[test]
+    fn new_npm_registry_host_is_flagged() {
+        let lock = |resolved: &str| {
+            format!(
+                r#"{{"lockfileVersion": 3, "packages": {{
+                    "": {{"name": "app"}},
+                    "node_modules/left-pad": {{"version": "1.3.0", "resolved": "{}"}}
+                }}}}"#,
+                resolved
+            )
+        };
+        let diff = compare(
+            "package-lock.json",
+            &lock("https://registry.npmjs.org/left-pad/-/left-pad-1.3.0.tgz"),
+            &lock("https://npm.example.com/left-pad/-/left-pad-1.3.0.tgz"),
+        );
+        let change = only_change(&diff);
+        assert_eq!(change.kind, DependencyChangeKind::SourceChanged);
+        assert_eq!(change.new_registry.as_deref(), Some("https://npm.example.com"));
+    }
+
+    #[test]
+    fn go_replace_changes_the_source() {
+        let old = "module example.com/app\n\nrequire github.com/pkg/errors v0.9.1\n";
+        let new = format!("{}\nreplace github.com/pkg/errors => github.com/fork/errors v0.9.2\n", old);
+        let diff = compare("go.mod", old, &new);
+        let change = only_change(&diff);
+        assert_eq!(change.kind, DependencyChangeKind::SourceChanged);
+        assert_eq!(
+            change.new.as_ref().and_then(|v| v.source.as_deref()),
+            Some("github.com/fork/errors v0.9.2")
+        );
+        assert_eq!(change.new_registry.as_deref(), Some("github.com/fork/errors"));
+    }
+
+    #[test]
+    fn unparsable_manifest_is_noted_instead_of_compared() {
+        let diff = compare(
+            "Cargo.lock",
+            "version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.0\"\n",
+            "version = 3\n<<<<<<< HEAD\n",
+        );
+        assert!(diff.changes.is_empty());
+        assert_eq!(diff.errors.len(), 1);
+        assert!(diff.errors[0].message.starts_with("new version: line 2"), "{}", diff.errors[0].message);
+        assert!(!diff.is_empty());
+
+        let file = diff.to_file_diff(0);
+        assert_eq!(file.chunks.len(), 1);
+        let lines = &file.chunks[0].lines;
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].kind, LineKind::Context);
+        assert!(lines[0].content.starts_with("could not parse Cargo.lock"));
+    }
+}
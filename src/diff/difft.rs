@@ -0,0 +1,249 @@
+use crate::diff::chunk::{ChunkId, DiffChunk, DiffLine, LineKind, LineRange};
+use crate::error::{CraiError, CraiResult};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Syntax-aware diffs via difftastic's JSON display mode
+pub struct Difftastic {
+    difft_path: PathBuf,
+}
+
+/// One file as reported by `difft --display json`
+#[derive(Debug, Deserialize)]
+struct DifftFile {
+    #[serde(default)]
+    chunks: Vec<Vec<DifftLine>>,
+    #[serde(default)]
+    status: String,
+}
+
+/// A row of a difft chunk; either side is absent when the line only exists on the other
+#[derive(Debug, Deserialize)]
+struct DifftLine {
+    lhs: Option<DifftSide>,
+    rhs: Option<DifftSide>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DifftSide {
+    /// Zero-based line number
+    line_number: u32,
+    /// Novel spans on the line; empty when the line is only shown opposite a changed one.
+    /// Older difft versions leave it out, in which case the line counts as changed.
+    changes: Option<Vec<IgnoredAny>>,
+}
+
+impl DifftSide {
+    fn is_changed(&self) -> bool {
+        self.changes.as_ref().is_none_or(|c| !c.is_empty())
+    }
+}
+
+/// A line of the aligned diff with the number of old and new lines before it
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    kind: LineKind,
+    old: u32,
+    new: u32,
+}
+
+impl Difftastic {
+    pub fn new(difft_path: PathBuf) -> Self {
+        Self { difft_path }
+    }
+
+    /// Diff two versions of a file; `None` means the side does not exist.
+    /// Chunk ids start at zero and must be renumbered by the caller.
+    pub async fn diff(
+        &self,
+        path: &Path,
+        old_content: Option<&str>,
+        new_content: Option<&str>,
+    ) -> CraiResult<Vec<DiffChunk>> {
+        // difft picks the parser from the file name, so keep it on both temp files
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_else(|| "file".into());
+        let scratch = std::env::temp_dir().join(format!("crai-difft-{}", Uuid::new_v4().simple()));
+        let old_path = scratch.join("old").join(&file_name);
+        let new_path = scratch.join("new").join(&file_name);
+
+        let result = async {
+            for (side_path, content) in [(&old_path, old_content), (&new_path, new_content)] {
+                if let Some(dir) = side_path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(side_path, content.unwrap_or(""))?;
+            }
+            self.run(&old_path, &new_path).await
+        }
+        .await;
+
+        let _ = std::fs::remove_dir_all(&scratch);
+
+        let file = result?;
+        Ok(map_chunks(
+            &file,
+            old_content.unwrap_or(""),
+            new_content.unwrap_or(""),
+        ))
+    }
+
+    async fn run(&self, old_path: &Path, new_path: &Path) -> CraiResult<DifftFile> {
+        let output = Command::new(&self.difft_path)
+            .env("DFT_UNSTABLE", "yes")
+            .args(["--display", "json", "--color", "never"])
+            .arg(old_path)
+            .arg(new_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => CraiError::DifftasticNotFound,
+                _ => CraiError::Diff(format!("Failed to run difft: {}", e)),
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CraiError::Diff(format!("difft failed: {}", stderr.trim())));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| CraiError::DiffParse(format!("Invalid difft JSON output: {}", e)))
+    }
+}
+
+/// Turn difft's syntactic chunks into unified-style chunks.
+/// Both files are aligned on their unchanged lines first, so lines between the changed lines
+/// of a chunk are emitted as context with matching old and new numbers.
+fn map_chunks(file: &DifftFile, old_content: &str, new_content: &str) -> Vec<DiffChunk> {
+    if file.status == "unchanged" {
+        return Vec::new();
+    }
+
+    let old_lines: Vec<&str> = old_content.lines().collect();
+    let new_lines: Vec<&str> = new_content.lines().collect();
+
+    let chunks: Vec<(BTreeSet<u32>, BTreeSet<u32>)> = file
+        .chunks
+        .iter()
+        .map(|rows| {
+            let old_changed: BTreeSet<u32> = rows
+                .iter()
+                .filter_map(|r| r.lhs.as_ref().filter(|s| s.is_changed()))
+                .map(|s| s.line_number)
+                .collect();
+            let new_changed: BTreeSet<u32> = rows
+                .iter()
+                .filter_map(|r| r.rhs.as_ref().filter(|s| s.is_changed()))
+                .map(|s| s.line_number)
+                .collect();
+            (old_changed, new_changed)
+        })
+        .filter(|(old, new)| !old.is_empty() || !new.is_empty())
+        .collect();
+
+    let all_old: BTreeSet<u32> = chunks.iter().flat_map(|(old, _)| old.iter().copied()).collect();
+    let all_new: BTreeSet<u32> = chunks.iter().flat_map(|(_, new)| new.iter().copied()).collect();
+    let steps = align(&all_old, &all_new, old_lines.len() as u32, new_lines.len() as u32);
+
+    chunks
+        .iter()
+        .filter_map(|(old_changed, new_changed)| {
+            let in_chunk = |step: &Step| match step.kind {
+                LineKind::Remove => old_changed.contains(&step.old),
+                LineKind::Add => new_changed.contains(&step.new),
+                LineKind::Context => false,
+            };
+            let first = steps.iter().position(in_chunk)?;
+            let last = steps.iter().rposition(in_chunk)?;
+            let steps = &steps[first..=last];
+
+            let lines = steps
+                .iter()
+                .map(|step| match step.kind {
+                    LineKind::Remove => line(step.kind, Some(step.old), None, &old_lines),
+                    LineKind::Add => line(step.kind, None, Some(step.new), &new_lines),
+                    LineKind::Context => line(step.kind, Some(step.old), Some(step.new), &new_lines),
+                })
+                .collect();
+
+            let old_count = steps.iter().filter(|s| s.kind != LineKind::Add).count() as u32;
+            let new_count = steps.iter().filter(|s| s.kind != LineKind::Remove).count() as u32;
+            Some(DiffChunk {
+                id: ChunkId(0),
+                old_range: range(steps[0].old, old_count),
+                new_range: range(steps[0].new, new_count),
+                header: String::new(),
+                lines,
+                symbol: None,
+            })
+        })
+        .collect()
+}
+
+/// Walk both files in step, pairing unchanged old and new lines in order like a unified
+/// diff would. Removals come before additions between two unchanged lines.
+fn align(old_changed: &BTreeSet<u32>, new_changed: &BTreeSet<u32>, old_len: u32, new_len: u32) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let (mut old, mut new) = (0, 0);
+    while old < old_len || new < new_len {
+        let kind = if old < old_len && (old_changed.contains(&old) || new >= new_len) {
+            LineKind::Remove
+        } else if new < new_len && (new_changed.contains(&new) || old >= old_len) {
+            LineKind::Add
+        } else {
+            LineKind::Context
+        };
+        steps.push(Step { kind, old, new });
+        if kind != LineKind::Add {
+            old += 1;
+        }
+        if kind != LineKind::Remove {
+            new += 1;
+        }
+    }
+    steps
+}
+
+/// One-based range of `count` lines after the first `before` lines. An empty range starts at
+/// the line before it, as in unified diff headers.
+fn range(before: u32, count: u32) -> LineRange {
+    LineRange {
+        start: if count == 0 { before } else { before + 1 },
+        count,
+    }
+}
+
+/// Give backend chunks the section headers git found for the same place: each takes the
+/// header of the last git hunk starting at or before it in the old file.
+pub fn inherit_headers(chunks: &mut [DiffChunk], git_chunks: &[DiffChunk]) {
+    for chunk in chunks {
+        if let Some(git_chunk) = git_chunks
+            .iter()
+            .rev()
+            .find(|g| g.old_range.start <= chunk.old_range.start.max(1))
+        {
+            chunk.header = git_chunk.header.clone();
+        }
+    }
+}
+
+/// Build a diff line from zero-based numbers, taking content from the side that has it
+fn line(kind: LineKind, old: Option<u32>, new: Option<u32>, source: &[&str]) -> DiffLine {
+    let idx = new.or(old).unwrap_or(0) as usize;
+    DiffLine {
+        kind,
+        old_line_num: old.map(|n| n + 1),
+        new_line_num: new.map(|n| n + 1),
+        content: source.get(idx).copied().unwrap_or("").to_string(),
+    }
+}
@@ -1,6 +1,11 @@
-use crate::config::FilterConfig;
-use crate::diff::chunk::{DiffChunk, FileDiff, Language, LineKind};
-use crate::error::CraiResult;
+use crate::config::{CategoryPolicy, FilterConfig, PathWeight};
+use crate::diff::category::ChangeCategory;
+use crate::diff::chunk::{ChunkId, DiffChunk, FileDiff, Language, LineKind};
+use crate::diff::comments::CommentSyntax;
+use crate::diff::moves::MovedCode;
+use crate::diff::owners::PathPattern;
+use crate::diff::tokens::tokenize;
+use crate::error::{CraiError, CraiResult};
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -8,6 +13,10 @@ pub struct ChunkFilter {
     config: FilterConfig,
     generated_patterns: Vec<Regex>,
     import_patterns: HashMap<String, Vec<Regex>>,
+    /// `path_weights` entries with their compiled patterns
+    path_weights: Vec<(Option<PathPattern>, PathWeight)>,
+    /// Filter hunks that only touch comments (`diff.ignore_comments`)
+    ignore_comments: bool,
 }
 
 impl ChunkFilter {
@@ -20,20 +29,39 @@ impl ChunkFilter {
 
         let mut import_patterns = HashMap::new();
         for (lang, patterns) in &config.import_patterns {
-            let compiled: Vec<Regex> = patterns
+            let compiled = patterns
                 .iter()
-                .filter_map(|p| Regex::new(p).ok())
-                .collect();
+                .map(|p| Regex::new(p))
+                .collect::<Result<Vec<_>, _>>()?;
             import_patterns.insert(lang.clone(), compiled);
         }
 
+        let mut path_weights = Vec::new();
+        for weight in &config.path_weights {
+            if weight.pattern.is_none() && weight.owner.is_none() {
+                return Err(CraiError::Config(
+                    "filters.path_weights entries need a pattern, an owner or both".to_string(),
+                ));
+            }
+            let pattern = weight.pattern.as_deref().map(PathPattern::new).transpose()?;
+            path_weights.push((pattern, weight.clone()));
+        }
+
         Ok(Self {
             config,
             generated_patterns,
             import_patterns,
+            path_weights,
+            ignore_comments: false,
         })
     }
 
+    /// Also filter comment-only hunks, for languages with known comment syntax
+    pub fn with_ignore_comments(mut self, ignore_comments: bool) -> Self {
+        self.ignore_comments = ignore_comments;
+        self
+    }
+
     pub fn filter_chunk(&self, chunk: &DiffChunk, file: &FileDiff) -> FilterResult {
         // Check auto-generated files first
         if self.config.auto_filter_generated && self.is_generated_file(file) {
@@ -44,6 +72,16 @@ impl ChunkFilter {
             };
         }
 
+        // Tests, docs and configuration whose category is set to be filtered
+        let category = self.category(chunk, file);
+        if self.policy(category) == Some(CategoryPolicy::Filter) {
+            return FilterResult {
+                is_filtered: true,
+                reason: FilterReason::for_category(category),
+                can_override: true,
+            };
+        }
+
         // Check for whitespace-only changes
         if self.config.auto_filter_whitespace && self.is_whitespace_only(chunk) {
             return FilterResult {
@@ -53,6 +91,24 @@ impl ChunkFilter {
             };
         }
 
+        // Check for reflows and other formatter-only changes
+        if self.config.auto_filter_formatting && self.is_formatting_only(chunk, file.language) {
+            return FilterResult {
+                is_filtered: true,
+                reason: Some(FilterReason::FormattingOnly),
+                can_override: true,
+            };
+        }
+
+        // Check for comment-only changes
+        if self.ignore_comments && self.is_comment_only(chunk, file) {
+            return FilterResult {
+                is_filtered: true,
+                reason: Some(FilterReason::CommentOnly),
+                can_override: true,
+            };
+        }
+
         // Check for import-only changes
         if self.config.auto_filter_imports && self.is_import_only(chunk, file.language) {
             return FilterResult {
@@ -78,6 +134,34 @@ impl ChunkFilter {
         }
     }
 
+    /// Filter a chunk that is half of a move. A half with edits or other changes is left to
+    /// be scored on its residual diff.
+    pub fn filter_moved(&self, chunk: &DiffChunk, moved: &MovedCode) -> Option<FilterResult> {
+        if !self.config.auto_filter_moved || !moved.involves(chunk.id) {
+            return None;
+        }
+        if moved.residual_of(chunk.id).is_some() {
+            return None;
+        }
+
+        Some(FilterResult {
+            is_filtered: true,
+            reason: Some(FilterReason::Moved {
+                source: moved.source,
+                destination: moved.destination,
+            }),
+            can_override: true,
+        })
+    }
+
+    /// The changes to score instead of the whole chunk when it is half of a move
+    pub fn moved_residual<'a>(&self, chunk: &DiffChunk, moved: &'a MovedCode) -> Option<&'a DiffChunk> {
+        if !self.config.auto_filter_moved {
+            return None;
+        }
+        moved.residual_of(chunk.id)
+    }
+
     pub fn filter_by_score(&self, score: f64) -> FilterResult {
         if score < self.config.controversiality_threshold {
             FilterResult {
@@ -94,6 +178,48 @@ impl ChunkFilter {
         }
     }
 
+    /// Whether a chunk is source, test, documentation or configuration
+    pub fn category(&self, chunk: &DiffChunk, file: &FileDiff) -> ChangeCategory {
+        ChangeCategory::of_chunk(chunk, file)
+    }
+
+    /// Configured policy for a category; source code has none
+    pub fn policy(&self, category: ChangeCategory) -> Option<CategoryPolicy> {
+        let policies = &self.config.categories;
+        match category {
+            ChangeCategory::Source => None,
+            ChangeCategory::Test => Some(policies.test),
+            ChangeCategory::Docs => Some(policies.docs),
+            ChangeCategory::Config => Some(policies.config),
+        }
+    }
+
+    /// Scale an AI score down when the chunk's category is demoted
+    pub fn demote_score(&self, score: f64, category: ChangeCategory) -> f64 {
+        if self.policy(category) == Some(CategoryPolicy::Demote) {
+            (score * self.config.categories.demote_multiplier).clamp(0.0, 1.0)
+        } else {
+            score
+        }
+    }
+
+    /// Apply the last `path_weights` entry matching the file to an AI score
+    pub fn weigh_score(&self, score: f64, file: &FileDiff) -> f64 {
+        let matching = self.path_weights.iter().rev().find(|(pattern, weight)| {
+            pattern.as_ref().is_none_or(|p| p.matches(&file.path))
+                && weight
+                    .owner
+                    .as_ref()
+                    .is_none_or(|owner| file.owners.iter().any(|o| o.eq_ignore_ascii_case(owner)))
+        });
+        let Some((_, weight)) = matching else {
+            return score;
+        };
+
+        let weighted = score * weight.multiplier.unwrap_or(1.0);
+        weight.floor.map_or(weighted, |floor| weighted.max(floor)).clamp(0.0, 1.0)
+    }
+
     pub fn threshold(&self) -> f64 {
         self.config.controversiality_threshold
     }
@@ -132,6 +258,43 @@ impl ChunkFilter {
             .all(|(r, a)| normalize_whitespace(r) == normalize_whitespace(a))
     }
 
+    /// Compare the removed and added sides as token streams, so lines joined or split by a
+    /// formatter still match
+    fn is_formatting_only(&self, chunk: &DiffChunk, language: Option<Language>) -> bool {
+        let Some(language) = language.filter(|l| !l.is_layout_sensitive()) else {
+            return false;
+        };
+        let normalization = self
+            .config
+            .formatting_normalizations
+            .get(language.name())
+            .copied()
+            .unwrap_or_default();
+
+        let side = |kind: LineKind| {
+            chunk
+                .lines
+                .iter()
+                .filter(|l| l.kind == kind)
+                .map(|l| l.content.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let removed = side(LineKind::Remove);
+        let added = side(LineKind::Add);
+        if removed.trim().is_empty() && added.trim().is_empty() {
+            return false;
+        }
+
+        tokenize(&removed, language, normalization) == tokenize(&added, language, normalization)
+    }
+
+    fn is_comment_only(&self, chunk: &DiffChunk, file: &FileDiff) -> bool {
+        file.language
+            .and_then(CommentSyntax::for_language)
+            .is_some_and(|syntax| syntax.is_comment_only(chunk, file))
+    }
+
     fn is_import_only(&self, chunk: &DiffChunk, language: Option<Language>) -> bool {
         let lang_name = language.map(|l| l.name().to_string()).unwrap_or_default();
         let patterns = match self.import_patterns.get(&lang_name) {
@@ -172,20 +335,47 @@ pub struct FilterResult {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FilterReason {
     WhitespaceOnly,
+    FormattingOnly,
+    CommentOnly,
     ImportOnly,
     Rename,
+    /// Half of a block moved from the `source` chunk to the `destination` chunk
+    Moved { source: ChunkId, destination: ChunkId },
     AutoGenerated,
+    /// Test code, documentation or configuration whose category policy is "filter"
+    TestCode,
+    Documentation,
+    Configuration,
     BelowThreshold,
+    /// Identical to a hunk reviewed at the `--since` revision
+    Unchanged,
 }
 
 impl FilterReason {
     pub fn description(&self) -> &'static str {
         match self {
             Self::WhitespaceOnly => "Whitespace-only change",
+            Self::FormattingOnly => "Formatting-only change",
+            Self::CommentOnly => "Comment-only change",
             Self::ImportOnly => "Import statement change",
             Self::Rename => "File rename without content change",
+            Self::Moved { .. } => "Code moved from or to another place in the diff",
             Self::AutoGenerated => "Auto-generated file",
+            Self::TestCode => "Test code",
+            Self::Documentation => "Documentation change",
+            Self::Configuration => "Configuration change",
             Self::BelowThreshold => "Below controversiality threshold",
+            Self::Unchanged => "Unchanged since last review",
+        }
+    }
+
+    /// Reason for filtering a chunk by its category
+    pub fn for_category(category: ChangeCategory) -> Option<Self> {
+        match category {
+            ChangeCategory::Source => None,
+            ChangeCategory::Test => Some(Self::TestCode),
+            ChangeCategory::Docs => Some(Self::Documentation),
+            ChangeCategory::Config => Some(Self::Configuration),
         }
     }
 }
@@ -197,10 +387,17 @@ pub struct FilterStats {
     pub total_lines: u32,
     pub filtered_lines: u32,
     pub whitespace_lines: u32,
+    pub formatting_lines: u32,
+    pub comment_lines: u32,
     pub import_lines: u32,
     pub rename_lines: u32,
+    pub moved_lines: u32,
     pub generated_lines: u32,
+    pub test_lines: u32,
+    pub docs_lines: u32,
+    pub config_lines: u32,
     pub below_threshold_lines: u32,
+    pub unchanged_lines: u32,
 }
 
 impl FilterStats {
@@ -210,10 +407,17 @@ impl FilterStats {
 
         match reason {
             FilterReason::WhitespaceOnly => self.whitespace_lines += line_count,
+            FilterReason::FormattingOnly => self.formatting_lines += line_count,
+            FilterReason::CommentOnly => self.comment_lines += line_count,
             FilterReason::ImportOnly => self.import_lines += line_count,
             FilterReason::Rename => self.rename_lines += line_count,
+            FilterReason::Moved { .. } => self.moved_lines += line_count,
             FilterReason::AutoGenerated => self.generated_lines += line_count,
+            FilterReason::TestCode => self.test_lines += line_count,
+            FilterReason::Documentation => self.docs_lines += line_count,
+            FilterReason::Configuration => self.config_lines += line_count,
             FilterReason::BelowThreshold => self.below_threshold_lines += line_count,
+            FilterReason::Unchanged => self.unchanged_lines += line_count,
         }
     }
 
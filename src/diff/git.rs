@@ -1,7 +1,11 @@
 use crate::diff::chunk::{FileStatus, Language};
+use crate::diff::vcs::{file_at_ref, path_from_bytes, FileAtRef, Vcs};
 use crate::error::{CraiError, CraiResult};
+use async_trait::async_trait;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
 pub struct GitOperations {
@@ -13,38 +17,6 @@ impl GitOperations {
         Self { repo_path }
     }
 
-    pub async fn verify_repository(&self) -> CraiResult<()> {
-        let output = Command::new("git")
-            .args(["-C", &self.repo_path.to_string_lossy(), "rev-parse", "--git-dir"])
-            .output()
-            .await?;
-
-        if !output.status.success() {
-            return Err(CraiError::NotAGitRepository(self.repo_path.clone()));
-        }
-
-        Ok(())
-    }
-
-    pub async fn verify_branch(&self, branch: &str) -> CraiResult<()> {
-        let output = Command::new("git")
-            .args([
-                "-C",
-                &self.repo_path.to_string_lossy(),
-                "rev-parse",
-                "--verify",
-                branch,
-            ])
-            .output()
-            .await?;
-
-        if !output.status.success() {
-            return Err(CraiError::BranchNotFound(branch.to_string()));
-        }
-
-        Ok(())
-    }
-
     pub async fn get_current_branch(&self) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
@@ -75,6 +47,10 @@ impl GitOperations {
                 &self.repo_path.to_string_lossy(),
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 "--name-status",
                 &format!("{}..{}", base, compare),
             ])
@@ -106,6 +82,10 @@ impl GitOperations {
                 &self.repo_path.to_string_lossy(),
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 "--name-status",
                 "HEAD",
             ])
@@ -137,6 +117,10 @@ impl GitOperations {
                 &self.repo_path.to_string_lossy(),
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 "--name-status",
                 "--cached",
                 "HEAD",
@@ -161,42 +145,239 @@ impl GitOperations {
         Ok(files)
     }
 
-    pub async fn get_file_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<String>> {
+    pub async fn get_file_diff(
+        &self,
+        base: &str,
+        compare: &str,
+        file_path: &Path,
+        context_lines: u32,
+    ) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
                 "-C",
                 &self.repo_path.to_string_lossy(),
-                "show",
-                &format!("{}:{}", git_ref, file_path.display()),
+                "diff",
+                "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                &format!("-U{}", context_lines),
+                &format!("{}..{}", base, compare),
+                "--",
+                &file_path.to_string_lossy(),
             ])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CraiError::Git(stderr.to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Size in bytes of a git object, `None` if it does not exist
+    async fn cat_file_size(&self, object: OsString) -> CraiResult<Option<u64>> {
+        let output = Command::new("git")
+            .args(["-C", &self.repo_path.to_string_lossy(), "cat-file", "-s"])
+            .arg(object)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    }
+
+    /// Look up many objects with one `git cat-file --batch-check` (`with_contents` false) or
+    /// `--batch` run. Each is `None` unless it names a blob; sizes come with empty contents
+    /// in check mode.
+    async fn cat_file_batch(&self, objects: &[OsString], with_contents: bool) -> CraiResult<Vec<Option<(u64, Vec<u8>)>>> {
+        if objects.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut input = Vec::new();
+        for object in objects {
+            input.extend_from_slice(object.as_encoded_bytes());
+            input.push(b'\n');
+        }
+
+        let mode = if with_contents { "--batch" } else { "--batch-check" };
+        let mut child = Command::new("git")
+            .args(["-C", &self.repo_path.to_string_lossy(), "cat-file", mode])
+            .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Write from another task so a full stdout pipe can't stall both sides
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| CraiError::Git("git cat-file has no stdin".to_string()))?;
+        let writer = tokio::spawn(async move { stdin.write_all(&input).await });
+        let output = child.wait_with_output().await?;
+        writer
+            .await
+            .map_err(|e| CraiError::Git(format!("Failed to write to git cat-file: {}", e)))??;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CraiError::Git(stderr.to_string()));
+        }
+
+        let stdout = output.stdout;
+        let mut pos = 0;
+        let mut objects_found = Vec::with_capacity(objects.len());
+        for _ in objects {
+            let Some(line_len) = stdout[pos..].iter().position(|b| *b == b'\n') else {
+                return Err(CraiError::Git("Truncated git cat-file output".to_string()));
+            };
+            let header = String::from_utf8_lossy(&stdout[pos..pos + line_len]).into_owned();
+            pos += line_len + 1;
+
+            // "<oid> <type> <size>", or "<object> missing" with nothing after it
+            let mut fields = header.rsplitn(3, ' ');
+            let size = fields.next().and_then(|s| s.parse::<u64>().ok());
+            let kind = fields.next();
+            let Some(size) = size else {
+                objects_found.push(None);
+                continue;
+            };
+
+            let mut contents = Vec::new();
+            if with_contents {
+                let end = (pos + size as usize).min(stdout.len());
+                contents = stdout[pos..end].to_vec();
+                pos = (end + 1).min(stdout.len());
+            }
+            objects_found.push((kind == Some("blob")).then_some((size, contents)));
+        }
+
+        Ok(objects_found)
+    }
+
+    async fn log_commits(&self, args: &[&str]) -> CraiResult<Vec<CommitInfo>> {
+        let output = Command::new("git")
+            .args(["-C", &self.repo_path.to_string_lossy(), "log"])
+            .arg("--format=%H%x1f%an%x1f%s%x1f%B%x1e")
+            .args(args)
             .output()
             .await?;
 
-        if output.status.success() {
-            Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
-        } else {
-            // File doesn't exist at this ref
-            Ok(None)
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CraiError::Git(stderr.to_string()));
         }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .split('\x1e')
+            .filter_map(|record| {
+                let mut fields = record.trim_start_matches('\n').splitn(4, '\x1f');
+                Some(CommitInfo {
+                    sha: fields.next()?.to_string(),
+                    author: fields.next()?.to_string(),
+                    summary: fields.next()?.to_string(),
+                    message: fields.next()?.trim_end().to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Vcs for GitOperations {
+    fn repo_path(&self) -> &Path {
+        &self.repo_path
     }
 
-    pub async fn get_unified_diff(
+    async fn verify_repository(&self) -> CraiResult<()> {
+        let output = Command::new("git")
+            .args(["-C", &self.repo_path.to_string_lossy(), "rev-parse", "--git-dir"])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CraiError::NotAGitRepository(self.repo_path.clone()));
+        }
+
+        Ok(())
+    }
+
+    async fn verify_branch(&self, branch: &str) -> CraiResult<()> {
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &self.repo_path.to_string_lossy(),
+                "rev-parse",
+                "--verify",
+                branch,
+            ])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CraiError::BranchNotFound(branch.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn current_branch(&self) -> CraiResult<Option<String>> {
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &self.repo_path.to_string_lossy(),
+                "symbolic-ref",
+                "--quiet",
+                "--short",
+                "HEAD",
+            ])
+            .output()
+            .await?;
+
+        // Exits with 1 and prints nothing when HEAD is detached
+        if !output.status.success() {
+            if output.status.code() == Some(1) {
+                return Ok(None);
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CraiError::Git(stderr.to_string()));
+        }
+
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(branch))
+    }
+
+    async fn get_unified_diff(
         &self,
         base: &str,
         compare: &str,
         context_lines: u32,
+        ignore_whitespace: bool,
     ) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
                 "-C",
                 &self.repo_path.to_string_lossy(),
+                "-c",
+                "core.quotePath=true",
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 &format!("-U{}", context_lines),
-                &format!("{}..{}", base, compare),
             ])
+            .args(whitespace_args(ignore_whitespace))
+            .arg(format!("{}..{}", base, compare))
             .output()
             .await?;
 
@@ -208,17 +389,27 @@ impl GitOperations {
         Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
 
-    /// Get unstaged unified diff (working directory vs HEAD)
-    pub async fn get_unstaged_unified_diff(&self, context_lines: u32) -> CraiResult<String> {
+    async fn get_unstaged_unified_diff(
+        &self,
+        context_lines: u32,
+        ignore_whitespace: bool,
+    ) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
                 "-C",
                 &self.repo_path.to_string_lossy(),
+                "-c",
+                "core.quotePath=true",
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 &format!("-U{}", context_lines),
-                "HEAD",
             ])
+            .args(whitespace_args(ignore_whitespace))
+            .arg("HEAD")
             .output()
             .await?;
 
@@ -230,18 +421,28 @@ impl GitOperations {
         Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
 
-    /// Get staged unified diff (index vs HEAD)
-    pub async fn get_staged_unified_diff(&self, context_lines: u32) -> CraiResult<String> {
+    async fn get_staged_unified_diff(
+        &self,
+        context_lines: u32,
+        ignore_whitespace: bool,
+    ) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
                 "-C",
                 &self.repo_path.to_string_lossy(),
+                "-c",
+                "core.quotePath=true",
                 "diff",
                 "--no-ext-diff",
+                "--find-renames",
+                "--find-copies",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
                 "--cached",
                 &format!("-U{}", context_lines),
-                "HEAD",
             ])
+            .args(whitespace_args(ignore_whitespace))
+            .arg("HEAD")
             .output()
             .await?;
 
@@ -253,23 +454,58 @@ impl GitOperations {
         Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
 
-    pub async fn get_file_diff(
-        &self,
-        base: &str,
-        compare: &str,
-        file_path: &Path,
-        context_lines: u32,
-    ) -> CraiResult<String> {
+    async fn get_merge_base(&self, base: &str, compare: &str) -> CraiResult<String> {
         let output = Command::new("git")
             .args([
                 "-C",
                 &self.repo_path.to_string_lossy(),
-                "diff",
-                "--no-ext-diff",
-                &format!("-U{}", context_lines),
-                &format!("{}..{}", base, compare),
-                "--",
-                &file_path.to_string_lossy(),
+                "merge-base",
+                base,
+                compare,
+            ])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CraiError::Git(format!(
+                "No merge base between {} and {}",
+                base, compare
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    async fn get_parent(&self, rev: &str) -> CraiResult<String> {
+        let parent = format!("{}^", rev);
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &self.repo_path.to_string_lossy(),
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &parent,
+            ])
+            .output()
+            .await?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Ok(EMPTY_TREE.to_string())
+        }
+    }
+
+    async fn get_untracked_files(&self) -> CraiResult<Vec<PathBuf>> {
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &self.repo_path.to_string_lossy(),
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "-z",
             ])
             .output()
             .await?;
@@ -279,10 +515,117 @@ impl GitOperations {
             return Err(CraiError::Git(stderr.to_string()));
         }
 
-        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        Ok(output
+            .stdout
+            .split(|b| *b == 0)
+            .filter(|s| !s.is_empty())
+            .map(|s| path_from_bytes(s.to_vec()))
+            .collect())
+    }
+
+    async fn get_file_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<String>> {
+        let output = Command::new("git")
+            .args(["-C", &self.repo_path.to_string_lossy(), "show"])
+            .arg(object_at(git_ref, file_path))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await?;
+
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+        } else {
+            // File doesn't exist at this ref
+            Ok(None)
+        }
+    }
+
+    /// Sizes from one `git cat-file --batch-check`, then the contents of the files within
+    /// the limit from one `git cat-file --batch`
+    async fn get_files_at_refs(&self, files: &[(String, PathBuf)], max_size: u64) -> CraiResult<Vec<FileAtRef>> {
+        // Batch input is line based, so names with line breaks are looked up one at a time
+        let (batched, single): (Vec<usize>, Vec<usize>) =
+            (0..files.len()).partition(|&idx| !files[idx].1.as_os_str().as_encoded_bytes().contains(&b'\n'));
+
+        let mut result = vec![FileAtRef::Missing; files.len()];
+        let objects: Vec<OsString> = batched
+            .iter()
+            .map(|&idx| object_at(&files[idx].0, &files[idx].1))
+            .collect();
+        let sizes = self.cat_file_batch(&objects, false).await?;
+
+        let mut to_read = Vec::new();
+        for ((&idx, object), size) in batched.iter().zip(objects).zip(sizes) {
+            match size {
+                Some((size, _)) if size > max_size => result[idx] = FileAtRef::Oversized(size),
+                Some(_) => to_read.push((idx, object)),
+                None => {}
+            }
+        }
+
+        let objects: Vec<OsString> = to_read.iter().map(|(_, object)| object.clone()).collect();
+        let contents = self.cat_file_batch(&objects, true).await?;
+        for ((idx, _), content) in to_read.into_iter().zip(contents) {
+            if let Some((_, bytes)) = content {
+                result[idx] = FileAtRef::Content(String::from_utf8_lossy(&bytes).into_owned());
+            }
+        }
+
+        for idx in single {
+            let (git_ref, path) = &files[idx];
+            result[idx] = file_at_ref(self, git_ref, path, max_size).await?;
+        }
+
+        Ok(result)
+    }
+
+    async fn get_object_size(&self, blob: &str) -> CraiResult<Option<u64>> {
+        self.cat_file_size(blob.into()).await
+    }
+
+    async fn get_file_size_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<u64>> {
+        self.cat_file_size(object_at(git_ref, file_path)).await
+    }
+
+    async fn get_submodule_commits(
+        &self,
+        submodule_path: &Path,
+        old_commit: &str,
+        new_commit: &str,
+    ) -> CraiResult<Vec<String>> {
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &self.repo_path.join(submodule_path).to_string_lossy(),
+                "log",
+                "--format=%h %s",
+                &format!("{}..{}", old_commit, new_commit),
+            ])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().map(|s| s.to_string()).collect())
     }
 
-    pub async fn get_commit_messages(&self, base: &str, compare: &str) -> CraiResult<Vec<String>> {
+    async fn list_commits(&self, base: &str, compare: &str) -> CraiResult<Vec<CommitInfo>> {
+        self.log_commits(&["--reverse", &format!("{}..{}", base, compare)])
+            .await
+    }
+
+    async fn get_commit(&self, rev: &str) -> CraiResult<CommitInfo> {
+        self.log_commits(&["-1", rev])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CraiError::Git(format!("Commit not found: {}", rev)))
+    }
+
+    async fn get_commit_messages(&self, base: &str, compare: &str) -> CraiResult<Vec<String>> {
         let output = Command::new("git")
             .args([
                 "-C",
@@ -304,6 +647,25 @@ impl GitOperations {
     }
 }
 
+/// Object id of git's empty tree, used as the parent of root commits
+pub const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: String,
+    /// First line of the message
+    pub summary: String,
+    /// Full message including the summary line
+    pub message: String,
+}
+
+impl CommitInfo {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ChangedFile {
     pub path: PathBuf,
@@ -312,32 +674,46 @@ pub struct ChangedFile {
     pub language: Language,
 }
 
+/// Object name for a path at a revision; an empty revision names the index entry.
+/// Built from the raw path so non-UTF-8 names reach git intact.
+fn object_at(rev: &str, path: &Path) -> OsString {
+    let mut object = OsString::from(format!("{}:", rev));
+    object.push(path.as_os_str());
+    object
+}
+
+/// `git diff` flags for `diff.ignore_whitespace`
+fn whitespace_args(ignore_whitespace: bool) -> &'static [&'static str] {
+    if ignore_whitespace {
+        &["--ignore-all-space"]
+    } else {
+        &[]
+    }
+}
+
 fn parse_name_status_line(line: &str) -> Option<ChangedFile> {
     let mut parts = line.split('\t');
     let status_str = parts.next()?.trim();
     let path_str = parts.next()?.trim();
 
-    let (status, old_path) = if status_str.starts_with('R') {
-        let similarity = status_str[1..].parse().unwrap_or(100);
-        let old = PathBuf::from(path_str);
-        let _new_path = parts.next()?.trim();
-        (
+    // Renames and copies carry a similarity score ("R087") and a second path column
+    let similarity = status_str.get(1..).and_then(|s| s.parse().ok()).unwrap_or(100);
+    let (status, old_path) = match status_str.chars().next()? {
+        'R' => (
             FileStatus::Renamed { similarity_percent: similarity },
-            Some(old),
-        )
-    } else {
-        let status = match status_str.chars().next()? {
-            'A' => FileStatus::Added,
-            'D' => FileStatus::Deleted,
-            'M' => FileStatus::Modified,
-            'C' => FileStatus::Copied,
-            _ => FileStatus::Modified,
-        };
-        (status, None)
+            Some(PathBuf::from(path_str)),
+        ),
+        'C' => (
+            FileStatus::Copied { similarity_percent: similarity },
+            Some(PathBuf::from(path_str)),
+        ),
+        'A' => (FileStatus::Added, None),
+        'D' => (FileStatus::Deleted, None),
+        _ => (FileStatus::Modified, None),
     };
 
     let final_path = if old_path.is_some() {
-        // For renames, the new path is the third column
+        // For renames and copies, the new path is the third column
         parts.next().map(|s| PathBuf::from(s.trim())).unwrap_or_else(|| PathBuf::from(path_str))
     } else {
         PathBuf::from(path_str)
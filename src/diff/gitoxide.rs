@@ -0,0 +1,1372 @@
+use crate::diff::git::{CommitInfo, EMPTY_TREE};
+use crate::diff::vcs::Vcs;
+use crate::error::{CraiError, CraiResult};
+use async_trait::async_trait;
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::diff::blob::intern::{InternedInput, Token};
+use gix::diff::blob::{diff, Algorithm};
+use gix::hash::ObjectId;
+use gix::objs::tree::EntryKind;
+use std::collections::{btree_map, BTreeMap};
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Lowest similarity, in percent, for an added file to count as renamed or copied (git's default)
+const MIN_SIMILARITY: u8 = 50;
+/// Files on either side above which only exact renames are detected, like `diff.renameLimit`
+const RENAME_LIMIT: usize = 1000;
+/// Bytes inspected for a NUL byte when deciding whether content is binary, as git does
+const BINARY_PROBE_BYTES: usize = 8000;
+
+/// Length of the abbreviated blob ids on `index` lines
+const ABBREV_LEN: usize = 7;
+/// Longest function context shown after a hunk header, as in git
+const FUNCTION_CONTEXT_BYTES: usize = 80;
+
+const MODE_FILE: u32 = 0o100644;
+const MODE_EXECUTABLE: u32 = 0o100755;
+const MODE_SYMLINK: u32 = 0o120000;
+const MODE_GITLINK: u32 = 0o160000;
+
+/// Reads the repository in-process with gitoxide, producing the same diff text as
+/// `git diff` so it feeds the same parser. Needs no `git` binary.
+#[derive(Clone)]
+pub struct Gitoxide {
+    repo: gix::ThreadSafeRepository,
+    repo_path: PathBuf,
+}
+
+impl Gitoxide {
+    pub fn open(repo_path: &Path) -> CraiResult<Self> {
+        let repo = gix::discover(repo_path)
+            .map_err(|_| CraiError::NotAGitRepository(repo_path.to_path_buf()))?;
+        // Diff paths are relative to the top of the working tree
+        let repo_path = repo
+            .workdir()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| repo_path.to_path_buf());
+        Ok(Self {
+            repo: repo.into_sync(),
+            repo_path,
+        })
+    }
+
+    fn repo(&self) -> gix::Repository {
+        self.repo.to_thread_local()
+    }
+
+    /// Run repository work on the blocking thread pool, since gitoxide reads and hashes
+    /// files synchronously
+    async fn blocking<T, F>(&self, work: F) -> CraiResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self, &gix::Repository) -> CraiResult<T> + Send + 'static,
+    {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || work(&this, &this.repo()))
+            .await
+            .map_err(|e| CraiError::Git(format!("Repository task failed: {}", e)))?
+    }
+
+    /// Every file of a revision's tree
+    fn tree_snapshot(&self, repo: &gix::Repository, rev: &str) -> CraiResult<Snapshot> {
+        let id = resolve(repo, rev)?;
+        if id == ObjectId::empty_tree(repo.object_hash()) {
+            return Ok(Snapshot::new());
+        }
+
+        let tree = repo
+            .find_object(id)
+            .map_err(git_error)?
+            .peel_to_tree()
+            .map_err(git_error)?;
+        let mut recorder = gix::traverse::tree::Recorder::default();
+        tree.traverse().breadthfirst(&mut recorder).map_err(git_error)?;
+
+        Ok(recorder
+            .records
+            .into_iter()
+            .filter(|entry| !entry.mode.is_tree())
+            .map(|entry| {
+                let side = SideEntry {
+                    id: entry.oid,
+                    mode: u32::from(entry.mode.value()),
+                    in_worktree: false,
+                };
+                (entry.filepath, side)
+            })
+            .collect())
+    }
+
+    /// Every file staged in the index. Conflicted paths are left out, as `git diff --cached` does.
+    fn index_snapshot(&self, repo: &gix::Repository) -> CraiResult<Snapshot> {
+        let index = repo.index_or_empty().map_err(git_error)?;
+        Ok(index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() == gix::index::entry::Stage::Unconflicted)
+            .map(|entry| {
+                let side = SideEntry {
+                    id: entry.id,
+                    mode: entry.mode.bits(),
+                    in_worktree: false,
+                };
+                (entry.path(&index).to_owned(), side)
+            })
+            .collect())
+    }
+
+    /// Tracked files as they are in the working tree. Files whose size and modification time
+    /// match the index keep the staged blob id; the rest are hashed.
+    fn worktree_snapshot(&self, repo: &gix::Repository) -> CraiResult<Snapshot> {
+        let index = repo.index_or_empty().map_err(git_error)?;
+        // Files changed in the same instant the index was written may not show in their stat
+        let index_written = std::fs::metadata(index.path())
+            .and_then(|m| m.modified())
+            .ok();
+
+        let mut snapshot = Snapshot::new();
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if snapshot.contains_key(path) {
+                // Later stages of a conflicted path
+                continue;
+            }
+            let full_path = self.repo_path.join(gix::path::from_bstr(path));
+            let Ok(metadata) = std::fs::symlink_metadata(&full_path) else {
+                continue;
+            };
+
+            let side = if entry.mode.bits() == MODE_GITLINK {
+                // A submodule's working tree state is its checked-out commit
+                let id = gix::open(&full_path)
+                    .ok()
+                    .and_then(|sub| sub.head_id().ok().map(|id| id.detach()))
+                    .unwrap_or(entry.id);
+                SideEntry {
+                    id,
+                    mode: MODE_GITLINK,
+                    in_worktree: false,
+                }
+            } else if metadata.file_type().is_symlink() {
+                let target = read_worktree(&full_path, MODE_SYMLINK)?;
+                SideEntry {
+                    id: hash_blob(repo, &target)?,
+                    mode: MODE_SYMLINK,
+                    in_worktree: true,
+                }
+            } else if metadata.is_file() {
+                let mode = worktree_file_mode(&metadata, entry.mode.bits());
+                let modified = metadata.modified().ok();
+                let stat_matches = modified
+                    .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                    .is_some_and(|m| {
+                        m.as_secs() as u32 == entry.stat.mtime.secs
+                            && m.subsec_nanos() == entry.stat.mtime.nsecs
+                            && metadata.len() as u32 == entry.stat.size
+                    });
+                let racy = modified.zip(index_written).is_none_or(|(m, i)| m >= i);
+
+                let id = if stat_matches && !racy && mode == entry.mode.bits() {
+                    entry.id
+                } else {
+                    hash_blob(repo, &read_worktree(&full_path, mode)?)?
+                };
+                SideEntry {
+                    id,
+                    mode,
+                    in_worktree: true,
+                }
+            } else {
+                // A directory where a file used to be
+                continue;
+            };
+            snapshot.insert(path.to_owned(), side);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Contents of one side of a file pair
+    fn content(&self, repo: &gix::Repository, path: &BStr, side: &SideEntry) -> CraiResult<Vec<u8>> {
+        if side.mode == MODE_GITLINK {
+            return Ok(format!("Subproject commit {}\n", side.id).into_bytes());
+        }
+        if side.in_worktree {
+            return read_worktree(&self.repo_path.join(gix::path::from_bstr(path)), side.mode);
+        }
+        Ok(repo.find_blob(side.id).map_err(git_error)?.data.clone())
+    }
+
+    /// Render the changes between two snapshots as `git diff` output
+    fn render_diff(
+        &self,
+        repo: &gix::Repository,
+        old: &Snapshot,
+        new: &Snapshot,
+        context_lines: u32,
+        ignore_whitespace: bool,
+    ) -> CraiResult<String> {
+        let pairs = self.pair_changes(repo, old, new)?;
+
+        let mut out = String::new();
+        for pair in &pairs {
+            let old_content = match &pair.old {
+                Some((path, side)) => Some(self.content(repo, path.as_ref(), side)?),
+                None => None,
+            };
+            let new_content = match &pair.new {
+                Some((path, side)) => Some(self.content(repo, path.as_ref(), side)?),
+                None => None,
+            };
+            render_pair(
+                &mut out,
+                pair,
+                old_content.as_deref(),
+                new_content.as_deref(),
+                context_lines,
+                ignore_whitespace,
+            );
+        }
+        Ok(out)
+    }
+
+    /// Match up the two snapshots into added, deleted, modified, renamed and copied files,
+    /// ordered by path like `git diff`
+    fn pair_changes(&self, repo: &gix::Repository, old: &Snapshot, new: &Snapshot) -> CraiResult<Vec<FilePair>> {
+        let mut pairs = Vec::new();
+        let mut added: Vec<(&BString, &SideEntry)> = Vec::new();
+        let mut deleted: Vec<(&BString, &SideEntry)> = Vec::new();
+        // Modified files are copy sources, as with `git diff --find-copies`
+        let mut modified: Vec<(&BString, &SideEntry)> = Vec::new();
+
+        for (path, old_side) in old {
+            match new.get(path) {
+                None => deleted.push((path, old_side)),
+                Some(new_side) if old_side.id == new_side.id && old_side.mode == new_side.mode => {}
+                // A file turning into a symlink or submodule is a deletion and an addition
+                Some(new_side) if object_kind(old_side.mode) != object_kind(new_side.mode) => {
+                    deleted.push((path, old_side));
+                    added.push((path, new_side));
+                }
+                Some(new_side) => {
+                    modified.push((path, old_side));
+                    pairs.push(FilePair::new(Some((path, old_side)), Some((path, new_side)), PairKind::Modified));
+                }
+            }
+        }
+        for (path, new_side) in new {
+            if !old.contains_key(path) {
+                added.push((path, new_side));
+            }
+        }
+
+        // Candidate (score, destination, source) matches, best first. On equal scores a deleted
+        // source wins over a modified one, so a move is reported as a rename.
+        let sources: Vec<(Source, &BString, &SideEntry)> = deleted
+            .iter()
+            .enumerate()
+            .map(|(idx, (path, side))| (Source::Deleted(idx), *path, *side))
+            .chain(modified.iter().map(|(path, side)| (Source::Modified, *path, *side)))
+            .filter(|(_, _, side)| is_regular(side.mode))
+            .collect();
+        let compare = added.len() <= RENAME_LIMIT && sources.len() <= RENAME_LIMIT;
+
+        let mut candidates: Vec<(u8, usize, usize)> = Vec::new();
+        let mut contents: BTreeMap<ObjectId, Vec<u8>> = BTreeMap::new();
+        for (dst, (path, side)) in added.iter().enumerate() {
+            if !is_regular(side.mode) {
+                continue;
+            }
+            let new_content = match compare {
+                true => Some(self.content(repo, path.as_ref(), side)?),
+                false => None,
+            };
+            for (src, (_, old_path, old_side)) in sources.iter().enumerate() {
+                let score = if old_side.id == side.id {
+                    100
+                } else if let Some(new_content) = &new_content {
+                    let old_content = cached_content(self, repo, &mut contents, old_path, old_side)?;
+                    similarity(old_content, new_content)
+                } else {
+                    continue;
+                };
+                if score >= MIN_SIMILARITY {
+                    candidates.push((score, dst, src));
+                }
+            }
+        }
+        candidates.sort_by_key(|&(score, dst, src)| {
+            (std::cmp::Reverse(score), !matches!(sources[src].0, Source::Deleted(_)), dst, src)
+        });
+
+        // Like git: first renames, each deleted file going to at most one destination, then
+        // destinations still unmatched may copy from any source
+        let mut matched: Vec<Option<(usize, u8)>> = vec![None; added.len()];
+        let mut source_used = vec![false; sources.len()];
+        for copies in [false, true] {
+            for &(score, dst, src) in &candidates {
+                let is_rename = matches!(sources[src].0, Source::Deleted(_)) && !source_used[src];
+                if matched[dst].is_none() && (copies || is_rename) {
+                    matched[dst] = Some((src, score));
+                    source_used[src] = true;
+                }
+            }
+        }
+
+        // A deleted file counts as renamed to the last of its destinations and copied to the rest
+        let mut renamed_to: BTreeMap<usize, usize> = BTreeMap::new();
+        for (dst, matched) in matched.iter().enumerate() {
+            if let Some((src, _)) = matched {
+                if let Source::Deleted(idx) = sources[*src].0 {
+                    renamed_to.insert(idx, dst);
+                }
+            }
+        }
+
+        for (dst, (path, side)) in added.iter().enumerate() {
+            let Some((src, score)) = matched[dst] else {
+                pairs.push(FilePair::new(None, Some((path, side)), PairKind::Added));
+                continue;
+            };
+            let (source, old_path, old_side) = sources[src];
+            let kind = match source {
+                Source::Deleted(idx) if renamed_to.get(&idx) == Some(&dst) => PairKind::Renamed(score),
+                _ => PairKind::Copied(score),
+            };
+            pairs.push(FilePair::new(Some((old_path, old_side)), Some((path, side)), kind));
+        }
+        for (idx, (path, side)) in deleted.into_iter().enumerate() {
+            if !renamed_to.contains_key(&idx) {
+                pairs.push(FilePair::new(Some((path, side)), None, PairKind::Deleted));
+            }
+        }
+
+        // Deletions sort before additions of the same path, as with type changes in git
+        pairs.sort_by(|a, b| {
+            (a.sort_path(), a.new.is_some()).cmp(&(b.sort_path(), b.new.is_some()))
+        });
+        Ok(pairs)
+    }
+
+    /// Blob id of a path at a revision, or in the index for an empty revision
+    fn blob_at(&self, repo: &gix::Repository, git_ref: &str, file_path: &Path) -> CraiResult<Option<ObjectId>> {
+        if git_ref.is_empty() {
+            let index = repo.index_or_empty().map_err(git_error)?;
+            let path = gix::path::into_bstr(file_path);
+            return Ok(index
+                .entry_by_path(path.as_ref())
+                .filter(|entry| entry.mode.bits() != MODE_GITLINK)
+                .map(|entry| entry.id));
+        }
+
+        let Ok(id) = resolve(repo, git_ref) else {
+            return Ok(None);
+        };
+        let Ok(tree) = repo.find_object(id).map_err(git_error)?.peel_to_tree() else {
+            return Ok(None);
+        };
+        Ok(tree
+            .lookup_entry_by_path(file_path)
+            .map_err(git_error)?
+            .filter(|entry| entry.mode().is_blob_or_symlink())
+            .map(|entry| entry.object_id()))
+    }
+
+    /// Commits reachable from `compare` but not `base`, newest first
+    fn walk(&self, repo: &gix::Repository, base: &str, compare: &str) -> CraiResult<Vec<CommitInfo>> {
+        let base = resolve(repo, base)?;
+        let compare = resolve(repo, compare)?;
+        walk_commits(repo, compare, base)
+    }
+}
+
+#[async_trait]
+impl Vcs for Gitoxide {
+    fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
+    async fn verify_repository(&self) -> CraiResult<()> {
+        // Opening already checked it
+        Ok(())
+    }
+
+    async fn verify_branch(&self, rev: &str) -> CraiResult<()> {
+        let rev = rev.to_string();
+        self.blocking(move |_, repo| resolve(repo, &rev).map(|_| ())).await
+    }
+
+    async fn current_branch(&self) -> CraiResult<Option<String>> {
+        self.blocking(|_, repo| {
+            let name = repo.head_name().map_err(git_error)?;
+            Ok(name.map(|n| n.shorten().to_string()))
+        })
+        .await
+    }
+
+    async fn get_unified_diff(
+        &self,
+        base: &str,
+        compare: &str,
+        context_lines: u32,
+        ignore_whitespace: bool,
+    ) -> CraiResult<String> {
+        let (base, compare) = (base.to_string(), compare.to_string());
+        self.blocking(move |this, repo| {
+            let old = this.tree_snapshot(repo, &base)?;
+            let new = this.tree_snapshot(repo, &compare)?;
+            this.render_diff(repo, &old, &new, context_lines, ignore_whitespace)
+        })
+        .await
+    }
+
+    async fn get_unstaged_unified_diff(&self, context_lines: u32, ignore_whitespace: bool) -> CraiResult<String> {
+        self.blocking(move |this, repo| {
+            let old = this.tree_snapshot(repo, "HEAD")?;
+            let new = this.worktree_snapshot(repo)?;
+            this.render_diff(repo, &old, &new, context_lines, ignore_whitespace)
+        })
+        .await
+    }
+
+    async fn get_staged_unified_diff(&self, context_lines: u32, ignore_whitespace: bool) -> CraiResult<String> {
+        self.blocking(move |this, repo| {
+            let old = this.tree_snapshot(repo, "HEAD")?;
+            let new = this.index_snapshot(repo)?;
+            this.render_diff(repo, &old, &new, context_lines, ignore_whitespace)
+        })
+        .await
+    }
+
+    async fn get_merge_base(&self, base: &str, compare: &str) -> CraiResult<String> {
+        let (base, compare) = (base.to_string(), compare.to_string());
+        self.blocking(move |_, repo| {
+            let one = resolve(repo, &base)?;
+            let two = resolve(repo, &compare)?;
+            repo.merge_base(one, two)
+                .map(|id| id.to_string())
+                .map_err(|_| CraiError::Git(format!("No merge base between {} and {}", base, compare)))
+        })
+        .await
+    }
+
+    async fn get_parent(&self, rev: &str) -> CraiResult<String> {
+        let rev = rev.to_string();
+        self.blocking(move |_, repo| {
+            let commit = repo
+                .find_object(resolve(repo, &rev)?)
+                .map_err(git_error)?
+                .peel_to_commit()
+                .map_err(git_error)?;
+            let parent = commit.parent_ids().next().map(|id| id.to_string());
+            Ok(parent.unwrap_or_else(|| EMPTY_TREE.to_string()))
+        })
+        .await
+    }
+
+    async fn get_untracked_files(&self) -> CraiResult<Vec<PathBuf>> {
+        self.blocking(|_, repo| {
+            let index = repo.index_or_empty().map_err(git_error)?;
+            let options = repo
+                .dirwalk_options()
+                .map_err(git_error)?
+                .emit_untracked(gix::dir::walk::EmissionMode::Matching);
+
+            let mut collect = gix::dir::walk::delegate::Collect::default();
+            repo.dirwalk(
+                &index,
+                None::<&str>,
+                &Default::default(),
+                options,
+                &mut collect,
+            )
+            .map_err(git_error)?;
+
+            Ok(collect
+                .into_entries_by_path()
+                .into_iter()
+                .filter(|(entry, _)| entry.status == gix::dir::entry::Status::Untracked)
+                .map(|(entry, _)| match entry.disk_kind {
+                    // Nested repositories are listed as directories, like `git ls-files --others`
+                    Some(gix::dir::entry::Kind::Repository | gix::dir::entry::Kind::Directory) => {
+                        let mut path = entry.rela_path;
+                        path.push(b'/');
+                        gix::path::from_bstring(path)
+                    }
+                    _ => gix::path::from_bstring(entry.rela_path),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_file_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<String>> {
+        let (git_ref, file_path) = (git_ref.to_string(), file_path.to_path_buf());
+        self.blocking(move |this, repo| {
+            let Some(id) = this.blob_at(repo, &git_ref, &file_path)? else {
+                return Ok(None);
+            };
+            let blob = repo.find_blob(id).map_err(git_error)?;
+            Ok(Some(String::from_utf8_lossy(&blob.data).into_owned()))
+        })
+        .await
+    }
+
+    async fn get_object_size(&self, blob: &str) -> CraiResult<Option<u64>> {
+        let blob = blob.to_string();
+        self.blocking(move |_, repo| {
+            let Ok(id) = resolve(repo, &blob) else {
+                return Ok(None);
+            };
+            Ok(repo.find_header(id).ok().map(|header| header.size()))
+        })
+        .await
+    }
+
+    async fn get_file_size_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<u64>> {
+        let (git_ref, file_path) = (git_ref.to_string(), file_path.to_path_buf());
+        self.blocking(move |this, repo| {
+            let Some(id) = this.blob_at(repo, &git_ref, &file_path)? else {
+                return Ok(None);
+            };
+            Ok(repo.find_header(id).ok().map(|header| header.size()))
+        })
+        .await
+    }
+
+    async fn get_submodule_commits(
+        &self,
+        submodule_path: &Path,
+        old_commit: &str,
+        new_commit: &str,
+    ) -> CraiResult<Vec<String>> {
+        let sub_path = self.repo_path.join(submodule_path);
+        let (old_commit, new_commit) = (old_commit.to_string(), new_commit.to_string());
+        self.blocking(move |_, _| {
+            let Ok(sub) = gix::open(sub_path) else {
+                return Ok(Vec::new());
+            };
+            let (Ok(old), Ok(new)) = (resolve(&sub, &old_commit), resolve(&sub, &new_commit)) else {
+                return Ok(Vec::new());
+            };
+            Ok(walk_commits(&sub, new, old)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|commit| format!("{} {}", commit.short_sha(), commit.summary))
+                .collect())
+        })
+        .await
+    }
+
+    async fn list_commits(&self, base: &str, compare: &str) -> CraiResult<Vec<CommitInfo>> {
+        let (base, compare) = (base.to_string(), compare.to_string());
+        self.blocking(move |this, repo| {
+            let mut commits = this.walk(repo, &base, &compare)?;
+            commits.reverse();
+            Ok(commits)
+        })
+        .await
+    }
+
+    async fn get_commit(&self, rev: &str) -> CraiResult<CommitInfo> {
+        let rev = rev.to_string();
+        self.blocking(move |_, repo| {
+            let id = resolve(repo, &rev).map_err(|_| CraiError::Git(format!("Commit not found: {}", rev)))?;
+            let commit = repo
+                .find_object(id)
+                .map_err(git_error)?
+                .peel_to_commit()
+                .map_err(git_error)?;
+            commit_info(&commit)
+        })
+        .await
+    }
+
+    async fn get_commit_messages(&self, base: &str, compare: &str) -> CraiResult<Vec<String>> {
+        let (base, compare) = (base.to_string(), compare.to_string());
+        self.blocking(move |this, repo| {
+            Ok(this
+                .walk(repo, &base, &compare)?
+                .into_iter()
+                .map(|commit| commit.summary)
+                .collect())
+        })
+        .await
+    }
+}
+
+/// One side of a file in a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SideEntry {
+    id: ObjectId,
+    /// Git object mode, e.g. 0o100644
+    mode: u32,
+    /// Content lives in the working tree rather than the object database
+    in_worktree: bool,
+}
+
+/// Files of a tree, the index or the working tree by repository-relative path
+type Snapshot = BTreeMap<BString, SideEntry>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PairKind {
+    Added,
+    Deleted,
+    Modified,
+    /// Similarity in percent
+    Renamed(u8),
+    Copied(u8),
+}
+
+/// Where a renamed or copied file came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// Index into the deleted files
+    Deleted(usize),
+    /// A file modified in place, which can only be copied from
+    Modified,
+}
+
+/// A file as it was and as it is
+struct FilePair {
+    old: Option<(BString, SideEntry)>,
+    new: Option<(BString, SideEntry)>,
+    kind: PairKind,
+}
+
+impl FilePair {
+    fn new(old: Option<(&BString, &SideEntry)>, new: Option<(&BString, &SideEntry)>, kind: PairKind) -> Self {
+        Self {
+            old: old.map(|(path, side)| (path.clone(), *side)),
+            new: new.map(|(path, side)| (path.clone(), *side)),
+            kind,
+        }
+    }
+
+    fn sort_path(&self) -> &BStr {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .map(|(path, _)| path.as_ref())
+            .unwrap_or_default()
+    }
+}
+
+/// Append one file's `git diff` section
+fn render_pair(
+    out: &mut String,
+    pair: &FilePair,
+    old_content: Option<&[u8]>,
+    new_content: Option<&[u8]>,
+    context_lines: u32,
+    ignore_whitespace: bool,
+) {
+    let old_path = pair.old.as_ref().or(pair.new.as_ref()).map(|(p, _)| p.as_ref()).unwrap_or_default();
+    let new_path = pair.new.as_ref().or(pair.old.as_ref()).map(|(p, _)| p.as_ref()).unwrap_or_default();
+    let old_side = pair.old.as_ref().map(|(_, side)| side);
+    let new_side = pair.new.as_ref().map(|(_, side)| side);
+
+    let binary = old_content.is_some_and(is_binary) || new_content.is_some_and(is_binary);
+    let hunks = if binary {
+        String::new()
+    } else {
+        unified_hunks(
+            old_content.unwrap_or_default(),
+            new_content.unwrap_or_default(),
+            context_lines,
+            ignore_whitespace,
+        )
+    };
+    let content_changed = old_side.map(|s| s.id) != new_side.map(|s| s.id);
+
+    let mut header = format!("diff --git {} {}\n", quote_path("a/", old_path), quote_path("b/", new_path));
+    match (old_side, new_side) {
+        (None, Some(new)) => {
+            let _ = writeln!(header, "new file mode {:06o}", new.mode);
+        }
+        (Some(old), None) => {
+            let _ = writeln!(header, "deleted file mode {:06o}", old.mode);
+        }
+        (Some(old), Some(new)) if old.mode != new.mode => {
+            let _ = writeln!(header, "old mode {:06o}\nnew mode {:06o}", old.mode, new.mode);
+        }
+        _ => {}
+    }
+    match pair.kind {
+        PairKind::Renamed(score) => {
+            let _ = writeln!(
+                header,
+                "similarity index {}%\nrename from {}\nrename to {}",
+                score,
+                quote_path("", old_path),
+                quote_path("", new_path)
+            );
+        }
+        PairKind::Copied(score) => {
+            let _ = writeln!(
+                header,
+                "similarity index {}%\ncopy from {}\ncopy to {}",
+                score,
+                quote_path("", old_path),
+                quote_path("", new_path)
+            );
+        }
+        _ => {}
+    }
+
+    let is_modification = matches!(pair.kind, PairKind::Modified);
+    // `git diff -w` leaves out files whose changes are all whitespace
+    if ignore_whitespace && is_modification && hunks.is_empty() && !binary && old_side.map(|s| s.mode) == new_side.map(|s| s.mode) {
+        return;
+    }
+
+    out.push_str(&header);
+    if !content_changed {
+        return;
+    }
+
+    let null = ObjectId::null(old_side.or(new_side).map(|s| s.id.kind()).unwrap_or_default());
+    let _ = write!(
+        out,
+        "index {}..{}",
+        old_side.map(|s| s.id).unwrap_or(null).to_hex_with_len(ABBREV_LEN),
+        new_side.map(|s| s.id).unwrap_or(null).to_hex_with_len(ABBREV_LEN)
+    );
+    match (old_side, new_side) {
+        (Some(old), Some(new)) if old.mode == new.mode => {
+            let _ = writeln!(out, " {:06o}", old.mode);
+        }
+        _ => out.push('\n'),
+    }
+
+    let old_label = if old_side.is_some() { quote_path("a/", old_path) } else { "/dev/null".to_string() };
+    let new_label = if new_side.is_some() { quote_path("b/", new_path) } else { "/dev/null".to_string() };
+    if binary {
+        let _ = writeln!(out, "Binary files {} and {} differ", old_label, new_label);
+    } else if !hunks.is_empty() {
+        // git ends names containing spaces with a tab so they can be told from a timestamp
+        let terminator = |label: &str| if label.contains(' ') { "\t" } else { "" };
+        let _ = writeln!(
+            out,
+            "--- {}{}\n+++ {}{}",
+            old_label,
+            terminator(&old_label),
+            new_label,
+            terminator(&new_label)
+        );
+        out.push_str(&hunks);
+    }
+}
+
+/// Hunks of a line diff in unified format, starting at the first "@@" line
+fn unified_hunks(old: &[u8], new: &[u8], context_lines: u32, ignore_whitespace: bool) -> String {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let key = |line: &&[u8]| -> Vec<u8> {
+        if ignore_whitespace {
+            line.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect()
+        } else {
+            line.to_vec()
+        }
+    };
+
+    let mut input = InternedInput::default();
+    input.update_before(old_lines.iter().map(key));
+    input.update_after(new_lines.iter().map(key));
+
+    let mut old_changed = ChangeMap::new(old_lines.len());
+    let mut new_changed = ChangeMap::new(new_lines.len());
+    diff(Algorithm::Myers, &input, |before: Range<u32>, after: Range<u32>| {
+        before.for_each(|idx| old_changed.set(idx as usize, true));
+        after.for_each(|idx| new_changed.set(idx as usize, true));
+    });
+    compact(&mut old_changed, &mut new_changed, &input.before, &old_lines);
+    compact(&mut new_changed, &mut old_changed, &input.after, &new_lines);
+    let changes = paired_changes(&old_changed, &new_changed);
+
+    let mut out = String::new();
+    let mut idx = 0;
+    while idx < changes.len() {
+        // Join changes whose context would touch or overlap
+        let mut last = idx;
+        while last + 1 < changes.len()
+            && changes[last + 1].0.start - changes[last].0.end <= 2 * context_lines
+        {
+            last += 1;
+        }
+
+        let (first_before, first_after) = &changes[idx];
+        let (last_before, last_after) = &changes[last];
+        let lead = first_before.start.min(context_lines);
+        let trail = (old_lines.len() as u32 - last_before.end).min(context_lines);
+        let old_start = first_before.start - lead;
+        let new_start = first_after.start - lead;
+        let old_count = last_before.end + trail - old_start;
+        let new_count = last_after.end + trail - new_start;
+
+        let _ = write!(
+            out,
+            "@@ -{} +{} @@",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        );
+        match function_context(&old_lines[..old_start as usize]) {
+            Some(context) => {
+                let _ = writeln!(out, " {}", context);
+            }
+            None => out.push('\n'),
+        }
+
+        // Context comes from the new side, which differs from the old only under -w
+        let mut new_pos = new_start;
+        for (before, after) in &changes[idx..=last] {
+            for line in &new_lines[new_pos as usize..after.start as usize] {
+                push_line(&mut out, ' ', line);
+            }
+            for line in &old_lines[before.start as usize..before.end as usize] {
+                push_line(&mut out, '-', line);
+            }
+            for line in &new_lines[after.start as usize..after.end as usize] {
+                push_line(&mut out, '+', line);
+            }
+            new_pos = after.end;
+        }
+        for line in &new_lines[new_pos as usize..(last_after.end + trail) as usize] {
+            push_line(&mut out, ' ', line);
+        }
+
+        idx = last + 1;
+    }
+    out
+}
+
+/// Which lines of one side are changed, with an unchanged line before the first and after
+/// the last so groups can be walked without bounds checks
+struct ChangeMap {
+    changed: Vec<bool>,
+}
+
+impl ChangeMap {
+    fn new(lines: usize) -> Self {
+        Self {
+            changed: vec![false; lines + 2],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.changed.len() - 2
+    }
+
+    /// Whether line `idx` is changed; -1 and `len()` are never changed
+    fn get(&self, idx: isize) -> bool {
+        self.changed[(idx + 1) as usize]
+    }
+
+    fn set(&mut self, idx: usize, changed: bool) {
+        self.changed[idx + 1] = changed;
+    }
+}
+
+/// A run of changed lines `start..end` on one side; empty between two unchanged lines
+#[derive(Debug, Clone, Copy)]
+struct Group {
+    start: usize,
+    end: usize,
+}
+
+impl Group {
+    fn first(map: &ChangeMap) -> Self {
+        let mut end = 0;
+        while map.get(end as isize) {
+            end += 1;
+        }
+        Self { start: 0, end }
+    }
+
+    fn next(&mut self, map: &ChangeMap) -> bool {
+        if self.end == map.len() {
+            return false;
+        }
+        self.start = self.end + 1;
+        self.end = self.start;
+        while map.get(self.end as isize) {
+            self.end += 1;
+        }
+        true
+    }
+
+    fn previous(&mut self, map: &ChangeMap) -> bool {
+        if self.start == 0 {
+            return false;
+        }
+        self.end = self.start - 1;
+        self.start = self.end;
+        while map.get(self.start as isize - 1) {
+            self.start -= 1;
+        }
+        true
+    }
+
+    /// Move the group down a line when the line after it equals its first line
+    fn slide_down(&mut self, map: &mut ChangeMap, tokens: &[Token]) -> bool {
+        if self.end >= map.len() || tokens[self.start] != tokens[self.end] {
+            return false;
+        }
+        map.set(self.start, false);
+        map.set(self.end, true);
+        self.start += 1;
+        self.end += 1;
+        while map.get(self.end as isize) {
+            self.end += 1;
+        }
+        true
+    }
+
+    /// Move the group up a line when the line before it equals its last line
+    fn slide_up(&mut self, map: &mut ChangeMap, tokens: &[Token]) -> bool {
+        if self.start == 0 || tokens[self.start - 1] != tokens[self.end - 1] {
+            return false;
+        }
+        self.start -= 1;
+        self.end -= 1;
+        map.set(self.start, true);
+        map.set(self.end, false);
+        while map.get(self.start as isize - 1) {
+            self.start -= 1;
+        }
+        true
+    }
+}
+
+/// Place each ambiguous block of added or removed lines the way git does: lined up with a
+/// change on the other side if possible, otherwise where the indent heuristic finds the most
+/// natural boundaries (a port of xdiff's `xdl_change_compact`)
+fn compact(map: &mut ChangeMap, other: &mut ChangeMap, tokens: &[Token], lines: &[&[u8]]) {
+    let mut group = Group::first(map);
+    let mut other_group = Group::first(other);
+
+    loop {
+        if group.end != group.start {
+            let mut earliest_end;
+            let mut end_matching_other;
+            loop {
+                let size = group.end - group.start;
+                end_matching_other = None;
+
+                while group.slide_up(map, tokens) {
+                    other_group.previous(other);
+                }
+                earliest_end = group.end;
+                if other_group.end > other_group.start {
+                    end_matching_other = Some(group.end);
+                }
+
+                while group.slide_down(map, tokens) {
+                    other_group.next(other);
+                    if other_group.end > other_group.start {
+                        end_matching_other = Some(group.end);
+                    }
+                }
+
+                // Sliding may have merged groups; repeat until the size settles
+                if size == group.end - group.start {
+                    break;
+                }
+            }
+
+            if group.end == earliest_end {
+                // Nowhere to slide
+            } else if end_matching_other.is_some() {
+                while other_group.end == other_group.start {
+                    group.slide_up(map, tokens);
+                    other_group.previous(other);
+                }
+            } else {
+                let size = group.end - group.start;
+                let lowest = earliest_end
+                    .max((group.end as isize - size as isize - 1).max(0) as usize)
+                    .max(group.end.saturating_sub(INDENT_HEURISTIC_MAX_SLIDING));
+
+                let mut best: Option<(usize, SplitScore)> = None;
+                for shift in lowest..=group.end {
+                    let mut score = SplitScore::default();
+                    score.add(&SplitMeasurement::new(lines, shift));
+                    score.add(&SplitMeasurement::new(lines, shift - size));
+                    if best.as_ref().is_none_or(|(_, best)| score.cmp(best) <= 0) {
+                        best = Some((shift, score));
+                    }
+                }
+
+                if let Some((best_shift, _)) = best {
+                    while group.end > best_shift {
+                        group.slide_up(map, tokens);
+                        other_group.previous(other);
+                    }
+                }
+            }
+        }
+
+        if !group.next(map) {
+            break;
+        }
+        other_group.next(other);
+    }
+}
+
+/// Changed line ranges of both sides, paired up in order
+fn paired_changes(old: &ChangeMap, new: &ChangeMap) -> Vec<(Range<u32>, Range<u32>)> {
+    let mut changes = Vec::new();
+    let (mut old_idx, mut new_idx) = (0, 0);
+    while old_idx < old.len() || new_idx < new.len() {
+        if old.get(old_idx as isize) || new.get(new_idx as isize) {
+            let (old_start, new_start) = (old_idx, new_idx);
+            while old.get(old_idx as isize) {
+                old_idx += 1;
+            }
+            while new.get(new_idx as isize) {
+                new_idx += 1;
+            }
+            changes.push((old_start as u32..old_idx as u32, new_start as u32..new_idx as u32));
+        } else {
+            old_idx += 1;
+            new_idx += 1;
+        }
+    }
+    changes
+}
+
+// Weights of git's indent heuristic, tuned upstream against a corpus of human-judged diffs
+const MAX_INDENT: i32 = 200;
+const MAX_BLANKS: i32 = 20;
+const INDENT_HEURISTIC_MAX_SLIDING: usize = 100;
+const START_OF_FILE_PENALTY: i32 = 1;
+const END_OF_FILE_PENALTY: i32 = 21;
+const TOTAL_BLANK_WEIGHT: i32 = -30;
+const POST_BLANK_WEIGHT: i32 = 6;
+const RELATIVE_INDENT_PENALTY: i32 = -4;
+const RELATIVE_INDENT_WITH_BLANK_PENALTY: i32 = 10;
+const RELATIVE_OUTDENT_PENALTY: i32 = 24;
+const RELATIVE_OUTDENT_WITH_BLANK_PENALTY: i32 = 17;
+const RELATIVE_DEDENT_PENALTY: i32 = 23;
+const RELATIVE_DEDENT_WITH_BLANK_PENALTY: i32 = 17;
+const INDENT_WEIGHT: i32 = 60;
+
+/// Surroundings of a boundary between two lines
+struct SplitMeasurement {
+    end_of_file: bool,
+    /// Indent of the line after the split, -1 if blank
+    indent: i32,
+    pre_blank: i32,
+    pre_indent: i32,
+    post_blank: i32,
+    post_indent: i32,
+}
+
+impl SplitMeasurement {
+    fn new(lines: &[&[u8]], split: usize) -> Self {
+        let (end_of_file, indent) = match lines.get(split) {
+            Some(line) => (false, line_indent(line)),
+            None => (true, -1),
+        };
+
+        let mut pre_blank = 0;
+        let mut pre_indent = -1;
+        for line in lines[..split.min(lines.len())].iter().rev() {
+            pre_indent = line_indent(line);
+            if pre_indent != -1 {
+                break;
+            }
+            pre_blank += 1;
+            if pre_blank == MAX_BLANKS {
+                pre_indent = 0;
+                break;
+            }
+        }
+
+        let mut post_blank = 0;
+        let mut post_indent = -1;
+        for line in lines.iter().skip(split + 1) {
+            post_indent = line_indent(line);
+            if post_indent != -1 {
+                break;
+            }
+            post_blank += 1;
+            if post_blank == MAX_BLANKS {
+                post_indent = 0;
+                break;
+            }
+        }
+
+        Self {
+            end_of_file,
+            indent,
+            pre_blank,
+            pre_indent,
+            post_blank,
+            post_indent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SplitScore {
+    effective_indent: i32,
+    penalty: i32,
+}
+
+impl SplitScore {
+    fn add(&mut self, m: &SplitMeasurement) {
+        if m.pre_indent == -1 && m.pre_blank == 0 {
+            self.penalty += START_OF_FILE_PENALTY;
+        }
+        if m.end_of_file {
+            self.penalty += END_OF_FILE_PENALTY;
+        }
+
+        let post_blank = if m.indent == -1 { 1 + m.post_blank } else { 0 };
+        let total_blank = m.pre_blank + post_blank;
+        self.penalty += TOTAL_BLANK_WEIGHT * total_blank;
+        self.penalty += POST_BLANK_WEIGHT * post_blank;
+
+        let indent = if m.indent != -1 { m.indent } else { m.post_indent };
+        let any_blanks = total_blank != 0;
+        self.effective_indent += indent;
+
+        if indent == -1 || m.pre_indent == -1 || indent == m.pre_indent {
+            // No adjustment
+        } else if indent > m.pre_indent {
+            self.penalty += if any_blanks { RELATIVE_INDENT_WITH_BLANK_PENALTY } else { RELATIVE_INDENT_PENALTY };
+        } else if m.post_indent != -1 && m.post_indent > indent {
+            self.penalty += if any_blanks { RELATIVE_OUTDENT_WITH_BLANK_PENALTY } else { RELATIVE_OUTDENT_PENALTY };
+        } else {
+            self.penalty += if any_blanks { RELATIVE_DEDENT_WITH_BLANK_PENALTY } else { RELATIVE_DEDENT_PENALTY };
+        }
+    }
+
+    /// Negative when `self` is the better split
+    fn cmp(&self, other: &Self) -> i32 {
+        let indents = (self.effective_indent > other.effective_indent) as i32
+            - (self.effective_indent < other.effective_indent) as i32;
+        INDENT_WEIGHT * indents + (self.penalty - other.penalty)
+    }
+}
+
+/// Indent width of a line with tabs to multiples of 8, or -1 for a blank line
+fn line_indent(line: &[u8]) -> i32 {
+    let mut indent = 0;
+    for &b in line {
+        if !b.is_ascii_whitespace() && b != 0x0b {
+            return indent;
+        }
+        match b {
+            b' ' => indent += 1,
+            b'\t' => indent += 8 - indent % 8,
+            _ => {}
+        }
+        if indent >= MAX_INDENT {
+            return MAX_INDENT;
+        }
+    }
+    -1
+}
+
+/// The text git shows after a hunk header: the closest line above the hunk that starts
+/// with a letter, `_` or `$`, cut to 80 bytes
+fn function_context(before: &[&[u8]]) -> Option<String> {
+    let line = before
+        .iter()
+        .rev()
+        .find(|line| line.first().is_some_and(|&b| b.is_ascii_alphabetic() || b == b'_' || b == b'$'))?;
+    let line = &line[..line.len().min(FUNCTION_CONTEXT_BYTES)];
+    Some(String::from_utf8_lossy(line).trim_end().to_string())
+}
+
+/// "start,count" of a hunk header from a zero-based start; empty ranges name the line before
+fn hunk_range(start: u32, count: u32) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+fn push_line(out: &mut String, prefix: char, line: &[u8]) {
+    out.push(prefix);
+    match line.strip_suffix(b"\n") {
+        Some(text) => {
+            out.push_str(&String::from_utf8_lossy(text));
+            out.push('\n');
+        }
+        None => {
+            out.push_str(&String::from_utf8_lossy(line));
+            out.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+/// Lines including their terminator, so a missing final newline counts as a change
+fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+    data.split_inclusive(|b| *b == b'\n').collect()
+}
+
+/// Share of the larger file's bytes found in lines both files have, in percent
+fn similarity(old: &[u8], new: &[u8]) -> u8 {
+    let largest = old.len().max(new.len());
+    if old.is_empty() || new.is_empty() {
+        return 0;
+    }
+
+    let old_lines = split_lines(old);
+    let input = InternedInput::new(old, new);
+    let mut removed = vec![false; old_lines.len()];
+    diff(Algorithm::Histogram, &input, |before: Range<u32>, _after: Range<u32>| {
+        for idx in before {
+            removed[idx as usize] = true;
+        }
+    });
+
+    let kept: usize = old_lines
+        .iter()
+        .zip(&removed)
+        .filter(|(_, removed)| !**removed)
+        .map(|(line, _)| line.len())
+        .sum();
+    (kept * 100 / largest) as u8
+}
+
+fn cached_content<'a>(
+    vcs: &Gitoxide,
+    repo: &gix::Repository,
+    cache: &'a mut BTreeMap<ObjectId, Vec<u8>>,
+    path: &BString,
+    side: &SideEntry,
+) -> CraiResult<&'a [u8]> {
+    Ok(match cache.entry(side.id) {
+        btree_map::Entry::Occupied(entry) => entry.into_mut(),
+        btree_map::Entry::Vacant(entry) => entry.insert(vcs.content(repo, path.as_ref(), side)?),
+    })
+}
+
+/// Same heuristic as git: a NUL byte near the start means binary
+fn is_binary(content: &[u8]) -> bool {
+    content[..content.len().min(BINARY_PROBE_BYTES)].contains(&0)
+}
+
+/// Regular files and executables, the only entries renames and copies are detected for
+fn is_regular(mode: u32) -> bool {
+    mode == MODE_FILE || mode == MODE_EXECUTABLE
+}
+
+fn object_kind(mode: u32) -> EntryKind {
+    match mode {
+        MODE_SYMLINK => EntryKind::Link,
+        MODE_GITLINK => EntryKind::Commit,
+        _ => EntryKind::Blob,
+    }
+}
+
+/// Mode of a working tree file; the executable bit only counts where the file system has one
+fn worktree_file_mode(metadata: &std::fs::Metadata, index_mode: u32) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = index_mode;
+        if metadata.permissions().mode() & 0o111 != 0 {
+            MODE_EXECUTABLE
+        } else {
+            MODE_FILE
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        if index_mode == MODE_EXECUTABLE {
+            MODE_EXECUTABLE
+        } else {
+            MODE_FILE
+        }
+    }
+}
+
+/// Contents of a working tree file, or a symlink's target
+fn read_worktree(path: &Path, mode: u32) -> CraiResult<Vec<u8>> {
+    if mode == MODE_SYMLINK {
+        let target = std::fs::read_link(path)?;
+        return Ok(gix::path::into_bstr(target).into_owned().into());
+    }
+    Ok(std::fs::read(path)?)
+}
+
+fn hash_blob(repo: &gix::Repository, data: &[u8]) -> CraiResult<ObjectId> {
+    gix::objs::compute_hash(repo.object_hash(), gix::objs::Kind::Blob, data).map_err(git_error)
+}
+
+fn resolve(repo: &gix::Repository, rev: &str) -> CraiResult<ObjectId> {
+    repo.rev_parse_single(rev)
+        .map(|id| id.detach())
+        .map_err(|_| CraiError::BranchNotFound(rev.to_string()))
+}
+
+/// Commits reachable from `tip` but not `hidden`, newest first
+fn walk_commits(repo: &gix::Repository, tip: ObjectId, hidden: ObjectId) -> CraiResult<Vec<CommitInfo>> {
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden([hidden])
+        .sorting(gix::revision::walk::Sorting::ByCommitTime(Default::default()))
+        .all()
+        .map_err(git_error)?;
+
+    let mut commits = Vec::new();
+    for info in walk {
+        let commit = info.map_err(git_error)?.object().map_err(git_error)?;
+        commits.push(commit_info(&commit)?);
+    }
+    Ok(commits)
+}
+
+fn commit_info(commit: &gix::Commit<'_>) -> CraiResult<CommitInfo> {
+    let message = commit.message_raw().map_err(git_error)?;
+    let summary = commit.message().map_err(git_error)?.summary().to_string();
+    Ok(CommitInfo {
+        sha: commit.id.to_string(),
+        author: commit.author().map_err(git_error)?.name.to_string(),
+        summary,
+        message: message.to_str_lossy().trim_end().to_string(),
+    })
+}
+
+/// Quote a path the way git does when it has special or non-ASCII bytes, with `prefix`
+/// (a/ or b/) inside the quotes
+fn quote_path(prefix: &str, path: &BStr) -> String {
+    let needs_quoting = path
+        .iter()
+        .any(|&b| !(0x20..0x7f).contains(&b) || b == b'"' || b == b'\\');
+    if !needs_quoting {
+        return format!("{}{}", prefix, path.to_str_lossy());
+    }
+
+    let mut quoted = String::from("\"");
+    quoted.push_str(prefix);
+    for &b in path.iter() {
+        match b {
+            b'"' => quoted.push_str("\\\""),
+            b'\\' => quoted.push_str("\\\\"),
+            b'\t' => quoted.push_str("\\t"),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            0x20..=0x7e => quoted.push(b as char),
+            _ => {
+                let _ = write!(quoted, "\\{:03o}", b);
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn git_error(e: impl std::fmt::Display) -> CraiError {
+    CraiError::Git(e.to_string())
+}
@@ -0,0 +1,74 @@
+use crate::diff::chunk::FileDiff;
+use crate::diff::owners::PathPattern;
+use std::path::Path;
+
+/// File in the repository root listing paths to leave out of reviews
+pub const IGNORE_FILE: &str = ".craiignore";
+
+/// Paths excluded from review by `.craiignore`, in gitignore syntax: one glob per line,
+/// `#` comments, and `!` to bring back a path an earlier line excluded
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    /// Patterns with whether they negate ("!pattern")
+    rules: Vec<(PathPattern, bool)>,
+}
+
+impl IgnoreRules {
+    /// Parse `.craiignore`. Lines with an invalid pattern are skipped with a warning.
+    pub fn parse(content: &str) -> Self {
+        let mut rules = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+
+            match PathPattern::new(pattern) {
+                Ok(pattern) => rules.push((pattern, negated)),
+                Err(e) => tracing::warn!("{} line {}: invalid pattern '{}': {}", IGNORE_FILE, idx + 1, pattern, e),
+            }
+        }
+
+        Self { rules }
+    }
+
+    /// Rules from the `.craiignore` at the root of the repository containing `repo`;
+    /// empty when there is none
+    pub fn load(repo: &Path) -> Self {
+        let repo = repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf());
+        for dir in repo.ancestors() {
+            if let Ok(content) = std::fs::read_to_string(dir.join(IGNORE_FILE)) {
+                return Self::parse(&content);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a path is excluded. The last matching line decides.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(path))
+            .is_some_and(|(_, negated)| !negated)
+    }
+
+    /// Drop excluded files, judging renames by their new path
+    pub fn retain(&self, files: &mut Vec<FileDiff>) {
+        if !self.is_empty() {
+            files.retain(|file| !self.is_ignored(&file.path));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::diff::chunk::{ChunkFingerprint, ChunkId, DiffChunk, DiffResult, LineKind};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Share of changed lines two hunks must have in common to count as the same hunk, edited
+const CHANGED_HUNK_SIMILARITY: f64 = 0.5;
+
+/// How a hunk relates to the previously reviewed revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkChange {
+    /// Not present before
+    New,
+    /// An edited version of the hunk `old` from the previous diff
+    Changed { old: ChunkId },
+    /// Identical to the hunk `old` from the previous diff
+    Unchanged { old: ChunkId },
+}
+
+/// A hunk of the previous diff that no longer appears
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedHunk {
+    pub path: PathBuf,
+    pub chunk: DiffChunk,
+}
+
+/// Range-diff between the diff reviewed at an old branch tip and the diff at the new tip.
+/// Hunks are matched by their changed and context lines first, then edited hunks by shared
+/// changed lines.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Interdiff {
+    /// The previously reviewed revision, as given by the user
+    pub since: String,
+    /// Change of every hunk of the new diff
+    pub changes: HashMap<ChunkId, HunkChange>,
+    /// Hunks of the previous diff that disappeared
+    pub removed: Vec<RemovedHunk>,
+}
+
+impl Interdiff {
+    pub fn compute(since: &str, previous: &DiffResult, current: &DiffResult) -> Self {
+        let mut unmatched: HashMap<ChunkFingerprint, Vec<(usize, &DiffChunk)>> = HashMap::new();
+        for (file_idx, file) in previous.files.iter().enumerate() {
+            for chunk in &file.chunks {
+                unmatched
+                    .entry(chunk.fingerprint_with_context(&file.path))
+                    .or_default()
+                    .push((file_idx, chunk));
+            }
+        }
+
+        // Identical hunks, context included, wherever they moved within the file; repeats are
+        // paired in order
+        let mut changes = HashMap::new();
+        let mut pending = Vec::new();
+        for file in &current.files {
+            for chunk in &file.chunks {
+                let matched = unmatched
+                    .get_mut(&chunk.fingerprint_with_context(&file.path))
+                    .filter(|candidates| !candidates.is_empty())
+                    .map(|candidates| candidates.remove(0));
+                match matched {
+                    Some((_, old)) => {
+                        changes.insert(chunk.id, HunkChange::Unchanged { old: old.id });
+                    }
+                    None => pending.push((file, chunk)),
+                }
+            }
+        }
+
+        let mut remaining: Vec<(usize, &DiffChunk)> = unmatched.into_values().flatten().collect();
+        remaining.sort_by_key(|(file_idx, chunk)| (*file_idx, chunk.id.0));
+
+        // Edited hunks: the closest remaining hunk of the same file
+        for (file, chunk) in pending {
+            let best = remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, (file_idx, _))| previous.files[*file_idx].path == file.path)
+                .map(|(idx, (_, old))| (idx, similarity(old, chunk)))
+                .filter(|(_, score)| *score >= CHANGED_HUNK_SIMILARITY)
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+            let change = match best {
+                Some((idx, _)) => HunkChange::Changed {
+                    old: remaining.remove(idx).1.id,
+                },
+                None => HunkChange::New,
+            };
+            changes.insert(chunk.id, change);
+        }
+
+        let removed = remaining
+            .into_iter()
+            .map(|(file_idx, chunk)| RemovedHunk {
+                path: previous.files[file_idx].path.clone(),
+                chunk: chunk.clone(),
+            })
+            .collect();
+
+        Self {
+            since: since.to_string(),
+            changes,
+            removed,
+        }
+    }
+
+    /// Change of a hunk in the new diff; hunks not seen by `compute` are new
+    pub fn change(&self, id: ChunkId) -> HunkChange {
+        self.changes.get(&id).copied().unwrap_or(HunkChange::New)
+    }
+
+    pub fn is_unchanged(&self, id: ChunkId) -> bool {
+        matches!(self.change(id), HunkChange::Unchanged { .. })
+    }
+
+    /// Hunks of the new diff that need review: new ones and edited ones
+    pub fn new_count(&self) -> usize {
+        self.count(|c| matches!(c, HunkChange::New))
+    }
+
+    pub fn changed_count(&self) -> usize {
+        self.count(|c| matches!(c, HunkChange::Changed { .. }))
+    }
+
+    pub fn unchanged_count(&self) -> usize {
+        self.count(|c| matches!(c, HunkChange::Unchanged { .. }))
+    }
+
+    fn count(&self, pred: impl Fn(&HunkChange) -> bool) -> usize {
+        self.changes.values().filter(|c| pred(c)).count()
+    }
+}
+
+/// Fraction of changed lines the two hunks share
+fn similarity(a: &DiffChunk, b: &DiffChunk) -> f64 {
+    let changed = |chunk: &DiffChunk| -> Vec<(LineKind, String)> {
+        chunk
+            .lines
+            .iter()
+            .filter(|l| l.kind != LineKind::Context)
+            .map(|l| (l.kind, l.content.trim().to_string()))
+            .collect()
+    };
+
+    let a_lines = changed(a);
+    let mut b_lines = changed(b);
+    let longest = a_lines.len().max(b_lines.len());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    let mut shared = 0;
+    for line in &a_lines {
+        if let Some(pos) = b_lines.iter().position(|l| l == line) {
+            b_lines.swap_remove(pos);
+            shared += 1;
+        }
+    }
+
+    shared as f64 / longest as f64
+}
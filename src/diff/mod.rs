@@ -1,9 +1,24 @@
+pub mod category;
 pub mod chunk;
+pub mod comments;
+pub mod deps;
+pub mod difft;
 pub mod filter;
 pub mod git;
+#[cfg(feature = "gitoxide")]
+pub mod gitoxide;
+pub mod ignore;
+pub mod interdiff;
+pub mod moves;
+pub mod owners;
 pub mod parser;
+pub mod patch;
+pub mod symbols;
+pub mod tokens;
+pub mod vcs;
 
 pub use chunk::*;
 pub use filter::ChunkFilter;
 pub use git::GitOperations;
 pub use parser::DiffParser;
+pub use vcs::{Vcs, VcsFactory};
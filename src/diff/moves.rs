@@ -0,0 +1,331 @@
+use crate::diff::chunk::{ChunkId, DiffChunk, DiffLine, FileDiff, LineKind};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Fewest non-blank lines a block needs before it counts as moved
+const MIN_MOVED_LINES: usize = 3;
+/// Share of lines the two halves must have in common, in order, to count as one move
+const MIN_MOVED_SIMILARITY: f64 = 0.8;
+/// Blocks longer than this are not compared, to bound the alignment cost
+const MAX_MOVED_LINES: usize = 2000;
+
+/// A block removed in one chunk that reappears as added lines in another, in the same or
+/// another file. Either chunk may make other changes besides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedCode {
+    pub source_file: usize,
+    /// Chunk that removes the block
+    pub source: ChunkId,
+    pub destination_file: usize,
+    /// Chunk that adds it again
+    pub destination: ChunkId,
+    /// The source chunk without the removed block; `None` when the chunk only removes it
+    pub source_residual: Option<DiffChunk>,
+    /// The destination chunk with the block replaced by the edits made during the move, as
+    /// the removed block diffed against the added one. `None` when the chunk only adds the
+    /// block, unchanged apart from whitespace.
+    pub residual: Option<DiffChunk>,
+}
+
+impl MovedCode {
+    /// Whether the chunk is either half of this move
+    pub fn involves(&self, chunk_id: ChunkId) -> bool {
+        self.source == chunk_id || self.destination == chunk_id
+    }
+
+    /// What is left to review of either half once the move is accounted for
+    pub fn residual_of(&self, chunk_id: ChunkId) -> Option<&DiffChunk> {
+        if chunk_id == self.destination {
+            self.residual.as_ref()
+        } else if chunk_id == self.source {
+            self.source_residual.as_ref()
+        } else {
+            None
+        }
+    }
+}
+
+/// A run of consecutive removed or added lines within a chunk
+struct Block<'a> {
+    file_idx: usize,
+    chunk: &'a DiffChunk,
+    /// Indices of the run in the chunk's lines
+    run: Range<usize>,
+    /// Non-blank lines of the run, with their indices in the chunk's lines
+    lines: Vec<&'a DiffLine>,
+    positions: Vec<usize>,
+    normalized: Vec<String>,
+}
+
+impl<'a> Block<'a> {
+    /// Every run of `kind` lines in the chunk long enough to count as moved
+    fn runs(file_idx: usize, chunk: &'a DiffChunk, kind: LineKind) -> Vec<Self> {
+        let mut blocks = Vec::new();
+        let mut idx = 0;
+        while idx < chunk.lines.len() {
+            if chunk.lines[idx].kind != kind {
+                idx += 1;
+                continue;
+            }
+            let start = idx;
+            while idx < chunk.lines.len() && chunk.lines[idx].kind == kind {
+                idx += 1;
+            }
+            blocks.extend(Self::new(file_idx, chunk, start..idx));
+        }
+        blocks
+    }
+
+    fn new(file_idx: usize, chunk: &'a DiffChunk, run: Range<usize>) -> Option<Self> {
+        // Blank lines carry nothing to match on
+        let positions: Vec<usize> = run
+            .clone()
+            .filter(|&idx| !chunk.lines[idx].content.trim().is_empty())
+            .collect();
+        if positions.len() < MIN_MOVED_LINES || positions.len() > MAX_MOVED_LINES {
+            return None;
+        }
+
+        let lines: Vec<&DiffLine> = positions.iter().map(|&idx| &chunk.lines[idx]).collect();
+        let normalized = lines.iter().map(|l| normalize(&l.content)).collect();
+        Some(Self {
+            file_idx,
+            chunk,
+            run,
+            lines,
+            positions,
+            normalized,
+        })
+    }
+
+    /// The part of the run from its `first` to its `last` non-blank line
+    fn narrow(&self, first: usize, last: usize) -> Self {
+        Self {
+            file_idx: self.file_idx,
+            chunk: self.chunk,
+            run: self.positions[first]..self.positions[last] + 1,
+            lines: self.lines[first..=last].to_vec(),
+            positions: self.positions[first..=last].to_vec(),
+            normalized: self.normalized[first..=last].to_vec(),
+        }
+    }
+
+    /// Whether the chunk changes nothing outside the run
+    fn is_whole_chunk(&self) -> bool {
+        self.chunk
+            .lines
+            .iter()
+            .enumerate()
+            .all(|(idx, line)| line.kind == LineKind::Context || self.run.contains(&idx))
+    }
+}
+
+/// Find runs of removed lines that another chunk adds again, after whitespace
+/// normalization, also where the runs border other edits. Each chunk takes part in at most
+/// one move.
+pub fn detect_moves(files: &[FileDiff]) -> Vec<MovedCode> {
+    let mut removals = Vec::new();
+    let mut additions = Vec::new();
+    for (file_idx, file) in files.iter().enumerate() {
+        for chunk in &file.chunks {
+            removals.extend(Block::runs(file_idx, chunk, LineKind::Remove));
+            additions.extend(Block::runs(file_idx, chunk, LineKind::Add));
+        }
+    }
+
+    let mut used: HashSet<ChunkId> = HashSet::new();
+    let mut moves = Vec::new();
+
+    for removal in &removals {
+        if used.contains(&removal.chunk.id) {
+            continue;
+        }
+        let removed_set: HashSet<&str> = removal.normalized.iter().map(String::as_str).collect();
+
+        let best = additions
+            .iter()
+            .enumerate()
+            .filter(|(_, addition)| addition.chunk.id != removal.chunk.id && !used.contains(&addition.chunk.id))
+            // Cheap check before aligning
+            .filter(|(_, addition)| {
+                let shared = addition
+                    .normalized
+                    .iter()
+                    .filter(|l| removed_set.contains(l.as_str()))
+                    .count();
+                shared >= MIN_MOVED_LINES
+            })
+            .filter_map(|(_, addition)| matched_spans(removal, addition))
+            .max_by(|a, b| a.similarity.partial_cmp(&b.similarity).unwrap_or(std::cmp::Ordering::Equal));
+
+        let Some(Match {
+            removal,
+            addition,
+            alignment,
+            ..
+        }) = best
+        else {
+            continue;
+        };
+
+        used.insert(removal.chunk.id);
+        used.insert(addition.chunk.id);
+        moves.push(MovedCode {
+            source_file: removal.file_idx,
+            source: removal.chunk.id,
+            destination_file: addition.file_idx,
+            destination: addition.chunk.id,
+            source_residual: source_residual(&removal),
+            residual: residual(&removal, &addition, &alignment),
+        });
+    }
+
+    moves
+}
+
+/// Two runs narrowed to the lines between their first and last matching ones
+struct Match<'a> {
+    similarity: f64,
+    removal: Block<'a>,
+    addition: Block<'a>,
+    alignment: Vec<(usize, usize)>,
+}
+
+/// The parts of two runs between their first and last matching lines, when similar enough
+/// to be one move. Lines outside them are edits of their own next to the move.
+fn matched_spans<'a>(removal: &Block<'a>, addition: &Block<'a>) -> Option<Match<'a>> {
+    let alignment = align(&removal.normalized, &addition.normalized);
+    if alignment.len() < MIN_MOVED_LINES {
+        return None;
+    }
+
+    let (first_i, first_j) = alignment[0];
+    let (last_i, last_j) = alignment[alignment.len() - 1];
+    let longest = (last_i - first_i).max(last_j - first_j) + 1;
+    let similarity = alignment.len() as f64 / longest as f64;
+    if similarity < MIN_MOVED_SIMILARITY {
+        return None;
+    }
+
+    let alignment = alignment
+        .into_iter()
+        .map(|(i, j)| (i - first_i, j - first_j))
+        .collect();
+    Some(Match {
+        similarity,
+        removal: removal.narrow(first_i, last_i),
+        addition: addition.narrow(first_j, last_j),
+        alignment,
+    })
+}
+
+/// Matching (removed, added) line index pairs of the longest common subsequence
+fn align(old: &[String], new: &[String]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// The source chunk with the removed block taken out, when it changes anything else
+fn source_residual(removal: &Block) -> Option<DiffChunk> {
+    if removal.is_whole_chunk() {
+        return None;
+    }
+
+    let lines = removal
+        .chunk
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !removal.run.contains(idx))
+        .map(|(_, line)| line.clone())
+        .collect();
+    Some(DiffChunk {
+        lines,
+        ..removal.chunk.clone()
+    })
+}
+
+/// The destination chunk with the added block replaced by the edits between the two halves
+/// of the move, matched lines shown as context
+fn residual(removal: &Block, addition: &Block, alignment: &[(usize, usize)]) -> Option<DiffChunk> {
+    let unedited = alignment.len() == removal.lines.len() && alignment.len() == addition.lines.len();
+    let whole_chunk = addition.is_whole_chunk();
+    if unedited && whole_chunk {
+        return None;
+    }
+
+    let chunk_lines = &addition.chunk.lines;
+    let mut lines = chunk_lines[..addition.run.start].to_vec();
+    let (mut i, mut j) = (0, 0);
+    let ends = (removal.lines.len(), addition.lines.len());
+    for &(match_i, match_j) in alignment.iter().chain(std::iter::once(&ends)) {
+        for old in &removal.lines[i..match_i] {
+            lines.push(DiffLine {
+                kind: LineKind::Remove,
+                old_line_num: old.old_line_num,
+                new_line_num: None,
+                content: old.content.clone(),
+            });
+        }
+        for new in &addition.lines[j..match_j] {
+            lines.push(DiffLine {
+                kind: LineKind::Add,
+                old_line_num: None,
+                new_line_num: new.new_line_num,
+                content: new.content.clone(),
+            });
+        }
+        if let (Some(old), Some(new)) = (removal.lines.get(match_i), addition.lines.get(match_j)) {
+            lines.push(DiffLine {
+                kind: LineKind::Context,
+                old_line_num: old.old_line_num,
+                new_line_num: new.new_line_num,
+                content: new.content.clone(),
+            });
+        }
+        i = match_i + 1;
+        j = match_j + 1;
+    }
+    lines.extend_from_slice(&chunk_lines[addition.run.end..]);
+
+    Some(DiffChunk {
+        id: addition.chunk.id,
+        // Line numbers of the edits point into where the block came from
+        old_range: if whole_chunk {
+            removal.chunk.old_range
+        } else {
+            addition.chunk.old_range
+        },
+        new_range: addition.chunk.new_range,
+        header: String::new(),
+        lines,
+        symbol: addition.chunk.symbol.clone(),
+    })
+}
+
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
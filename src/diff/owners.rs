@@ -0,0 +1,186 @@
+use crate::diff::chunk::FileDiff;
+use crate::error::CraiResult;
+use regex::Regex;
+use std::path::Path;
+
+/// Where CODEOWNERS is looked for, in GitHub's order; the first one found is used
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A gitignore-style path glob, as used in CODEOWNERS and `filters.path_weights`.
+///
+/// `*` and `?` stay within a directory and `**` crosses them. A pattern containing a `/`
+/// other than a trailing one is relative to the repository root; otherwise it matches at any
+/// depth. A trailing `/` matches everything in a directory, and a pattern without wildcards
+/// in its last component also matches everything beneath it.
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl PathPattern {
+    pub fn new(pattern: &str) -> CraiResult<Self> {
+        let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+        let dir_only = pattern.ends_with('/');
+        let body = pattern.trim_start_matches('/').trim_end_matches('/');
+        let last = body.rsplit('/').next().unwrap_or_default();
+
+        let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+        regex.push_str(&translate(body));
+        if dir_only {
+            regex.push_str("/.*$");
+        } else if last.contains(['*', '?', '[']) {
+            regex.push('$');
+        } else {
+            regex.push_str("(?:/.*)?$");
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex: Regex::new(&regex)?,
+        })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.regex.is_match(&path.to_string_lossy().replace('\\', "/"))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// Translate glob syntax into a regular expression
+fn translate(glob: &str) -> String {
+    let mut out = String::new();
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // "**/" is zero or more directories
+                    chars.next();
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                let closed = chars.clone().any(|c| c == ']');
+                let raw: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if closed {
+                    let (negated, members) = match raw.strip_prefix(['!', '^']) {
+                        Some(members) => (true, members),
+                        None => (false, raw.as_str()),
+                    };
+                    out.push('[');
+                    if negated {
+                        out.push('^');
+                    }
+                    out.push_str(&members.replace('\\', r"\\").replace('[', r"\["));
+                    out.push(']');
+                } else {
+                    // No closing bracket: a literal "["
+                    out.push_str(r"\[");
+                    out.push_str(&regex::escape(&raw));
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    out.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    out
+}
+
+/// Ownership rules from a CODEOWNERS file
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<(PathPattern, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Parse CODEOWNERS. Lines with an invalid pattern are skipped with a warning, like
+    /// GitHub does, rather than failing the review.
+    pub fn parse(content: &str) -> Self {
+        let mut rules = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            // Comments and GitLab's "[Section]" headers
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') || line.starts_with("^[") {
+                continue;
+            }
+
+            let mut fields = split_fields(line).into_iter();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            let owners: Vec<String> = fields.take_while(|f| !f.starts_with('#')).collect();
+
+            match PathPattern::new(&pattern) {
+                Ok(pattern) => rules.push((pattern, owners)),
+                Err(e) => tracing::warn!("CODEOWNERS line {}: invalid pattern '{}': {}", idx + 1, pattern, e),
+            }
+        }
+
+        Self { rules }
+    }
+
+    /// Owners of a path. The last matching rule wins; a rule without owners leaves the
+    /// path unowned.
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Set `owners` on every file
+    pub fn annotate(&self, files: &mut [FileDiff]) {
+        for file in files {
+            file.owners = self.owners_of(&file.path).to_vec();
+        }
+    }
+}
+
+/// Split a line on whitespace, keeping backslash-escaped spaces in a field
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    if !next.is_whitespace() && next != '#' {
+                        current.push('\\');
+                    }
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    fields.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        fields.push(current);
+    }
+
+    fields
+}
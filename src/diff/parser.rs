@@ -1,21 +1,39 @@
+use crate::config::{DiffBackend, DiffConfig};
 use crate::diff::chunk::{
-    ChunkId, DiffChunk, DiffLine, DiffResult, FileDiff, FileStatus, Language, LineKind, LineRange,
-    ParseError,
+    ChunkId, ContentKind, DiffChunk, DiffLine, DiffResult, FileDiff, FileStatus, Language, LineKind, LineRange,
+    ModeChange, ParseError, ReviewedHead,
 };
+use crate::diff::deps::{is_manifest, DependencyDiff};
+use crate::diff::difft::{self, Difftastic};
 use crate::diff::git::GitOperations;
-use crate::error::CraiResult;
-use std::path::PathBuf;
+use crate::diff::ignore::IgnoreRules;
+use crate::diff::moves::detect_moves;
+use crate::diff::owners::{CodeOwners, CODEOWNERS_LOCATIONS};
+use crate::diff::symbols;
+use crate::diff::vcs::{path_from_bytes, FileAtRef, Vcs};
+use crate::error::{CraiError, CraiResult};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub struct DiffParser {
-    git: GitOperations,
-    context_lines: u32,
+    vcs: Arc<dyn Vcs>,
+    config: DiffConfig,
+    /// Paths excluded by `.craiignore`
+    ignore: IgnoreRules,
 }
 
 impl DiffParser {
-    pub fn new(repo_path: PathBuf, context_lines: u32) -> Self {
+    /// Parser reading the repository through the `git` binary
+    pub fn new(repo_path: PathBuf, config: &DiffConfig) -> Self {
+        Self::with_vcs(Arc::new(GitOperations::new(repo_path)), config)
+    }
+
+    pub fn with_vcs(vcs: Arc<dyn Vcs>, config: &DiffConfig) -> Self {
+        let ignore = IgnoreRules::load(vcs.repo_path());
         Self {
-            git: GitOperations::new(repo_path),
-            context_lines,
+            vcs,
+            config: config.clone(),
+            ignore,
         }
     }
 
@@ -26,133 +44,744 @@ impl DiffParser {
     ) -> CraiResult<DiffResult> {
         // Get unified diff for all files
         let unified_diff = self
-            .git
-            .get_unified_diff(base_branch, compare_branch, self.context_lines)
+            .vcs
+            .get_unified_diff(
+                base_branch,
+                compare_branch,
+                self.config.context_lines,
+                self.config.ignore_whitespace,
+            )
             .await?;
 
-        let (files, parse_errors) = self.parse_unified_diff(&unified_diff)?;
+        let (mut files, parse_errors) = parse_unified_diff(&unified_diff)?;
+        self.ignore.retain(&mut files);
+        self.resolve_content_metadata(&mut files, false).await?;
+
+        let source = DiffSource::Refs {
+            base: base_branch.to_string(),
+            compare: compare_branch.to_string(),
+        };
+        self.load_contents(&mut files, &source).await?;
+        self.apply_backend(&mut files).await?;
+        if self.config.symbols {
+            symbols::annotate(&mut files);
+        }
+        self.assign_owners(&mut files, &source).await?;
+
+        let moves = detect_moves(&files);
+        let dependencies = self.add_dependencies(&mut files, &source).await?;
+        let head = self.reviewed_head(compare_branch).await.ok();
 
         Ok(DiffResult {
             base_branch: base_branch.to_string(),
             compare_branch: compare_branch.to_string(),
+            merge_base: None,
             files,
             parse_errors,
+            interdiff: None,
+            moves,
+            dependencies,
+            head,
         })
     }
 
+    /// Parse `base...compare`: the changes on `compare` since it diverged from `base`,
+    /// which is what a pull request shows
+    pub async fn parse_merge_base(
+        &self,
+        base_branch: &str,
+        compare_branch: &str,
+    ) -> CraiResult<DiffResult> {
+        let merge_base = self.vcs.get_merge_base(base_branch, compare_branch).await?;
+        let mut result = self.parse_branches(&merge_base, compare_branch).await?;
+        result.base_branch = base_branch.to_string();
+        result.merge_base = Some(merge_base);
+        Ok(result)
+    }
+
+    /// Parse a single commit against its first parent (or the empty tree for a root commit)
+    pub async fn parse_commit(&self, commit: &str) -> CraiResult<DiffResult> {
+        let parent = self.vcs.get_parent(commit).await?;
+        let mut result = self.parse_branches(&parent, commit).await?;
+        result.base_branch = format!("{}^", commit);
+        Ok(result)
+    }
+
     /// Parse unstaged changes (working directory vs HEAD)
     pub async fn parse_unstaged(&self) -> CraiResult<DiffResult> {
         let unified_diff = self
-            .git
-            .get_unstaged_unified_diff(self.context_lines)
+            .vcs
+            .get_unstaged_unified_diff(self.config.context_lines, self.config.ignore_whitespace)
             .await?;
 
-        let (files, parse_errors) = self.parse_unified_diff(&unified_diff)?;
+        let (mut files, mut parse_errors) = parse_unified_diff(&unified_diff)?;
+        self.ignore.retain(&mut files);
+        self.resolve_content_metadata(&mut files, true).await?;
+
+        if self.config.include_untracked {
+            self.add_untracked_files(&mut files, &mut parse_errors).await?;
+        }
+
+        self.load_contents(&mut files, &DiffSource::WorkingTree).await?;
+        self.apply_backend(&mut files).await?;
+        if self.config.symbols {
+            symbols::annotate(&mut files);
+        }
+        self.assign_owners(&mut files, &DiffSource::WorkingTree).await?;
+
+        let moves = detect_moves(&files);
+        let dependencies = self.add_dependencies(&mut files, &DiffSource::WorkingTree).await?;
+        let head = self.reviewed_head("HEAD").await.ok().map(|head| ReviewedHead {
+            name: format!("{} (working directory)", head.name),
+            sha: None,
+        });
 
         Ok(DiffResult {
             base_branch: "HEAD".to_string(),
             compare_branch: "(working directory)".to_string(),
+            merge_base: None,
             files,
             parse_errors,
+            interdiff: None,
+            moves,
+            dependencies,
+            head,
         })
     }
 
     /// Parse staged changes (index vs HEAD)
     pub async fn parse_staged(&self) -> CraiResult<DiffResult> {
         let unified_diff = self
-            .git
-            .get_staged_unified_diff(self.context_lines)
+            .vcs
+            .get_staged_unified_diff(self.config.context_lines, self.config.ignore_whitespace)
             .await?;
 
-        let (files, parse_errors) = self.parse_unified_diff(&unified_diff)?;
+        let (mut files, parse_errors) = parse_unified_diff(&unified_diff)?;
+        self.ignore.retain(&mut files);
+        self.resolve_content_metadata(&mut files, false).await?;
+        self.load_contents(&mut files, &DiffSource::Index).await?;
+        self.apply_backend(&mut files).await?;
+        if self.config.symbols {
+            symbols::annotate(&mut files);
+        }
+        self.assign_owners(&mut files, &DiffSource::Index).await?;
+
+        let moves = detect_moves(&files);
+        let dependencies = self.add_dependencies(&mut files, &DiffSource::Index).await?;
+        let head = self.reviewed_head("HEAD").await.ok().map(|head| ReviewedHead {
+            name: format!("{} (staged)", head.name),
+            sha: None,
+        });
 
         Ok(DiffResult {
             base_branch: "HEAD".to_string(),
             compare_branch: "(staged)".to_string(),
+            merge_base: None,
             files,
             parse_errors,
+            interdiff: None,
+            moves,
+            dependencies,
+            head,
         })
     }
 
-    fn parse_unified_diff(&self, diff_text: &str) -> CraiResult<(Vec<FileDiff>, Vec<ParseError>)> {
-        let mut files = Vec::new();
-        let mut errors = Vec::new();
-        let mut chunk_id_counter = 0u64;
+    /// Name and tip of the compare side. `HEAD`, also as in `HEAD~2`, is named after the
+    /// branch it is on, so reviews of different branches are kept apart.
+    async fn reviewed_head(&self, compare_branch: &str) -> CraiResult<ReviewedHead> {
+        let sha = self.vcs.get_commit(compare_branch).await?.sha;
+        let name = match compare_branch.strip_prefix("HEAD") {
+            Some(rest) if rest.is_empty() || rest.starts_with(['~', '^']) => {
+                match self.vcs.current_branch().await? {
+                    Some(branch) => format!("{}{}", branch, rest),
+                    None => sha.clone(),
+                }
+            }
+            _ => compare_branch.to_string(),
+        };
+        Ok(ReviewedHead { name, sha: Some(sha) })
+    }
+
+    /// Annotate files with their owners from the CODEOWNERS file on the new side, if any
+    async fn assign_owners(&self, files: &mut [FileDiff], source: &DiffSource) -> CraiResult<()> {
+        for location in CODEOWNERS_LOCATIONS {
+            if let Some(content) = source.read_new(self.vcs.as_ref(), Path::new(location)).await? {
+                CodeOwners::parse(&content).annotate(files);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare the dependency manifests and lockfiles in the diff and append the synthetic
+    /// "Dependencies" entry when anything changed. Manifests too large to load are read
+    /// again here, since lockfiles often are.
+    async fn add_dependencies(&self, files: &mut Vec<FileDiff>, source: &DiffSource) -> CraiResult<Option<DependencyDiff>> {
+        if !self.config.dependencies {
+            return Ok(None);
+        }
+
+        let mut manifests = Vec::new();
+        for file in files.iter().filter(|f| f.content_kind.is_text() && is_manifest(&f.path)) {
+            let old_path = file.old_path.as_ref().unwrap_or(&file.path);
+            let old = match (&file.old_content, file.status) {
+                (_, FileStatus::Added) => None,
+                (Some(content), _) => Some(content.clone()),
+                (None, _) => source.read_old(self.vcs.as_ref(), old_path).await?,
+            };
+            let new = match (&file.new_content, file.status) {
+                (_, FileStatus::Deleted) => None,
+                (Some(content), _) => Some(content.clone()),
+                (None, _) => source.read_new(self.vcs.as_ref(), &file.path).await?,
+            };
+            manifests.push((file.path.clone(), old, new));
+        }
+
+        let dependencies = DependencyDiff::compare(
+            manifests
+                .iter()
+                .map(|(path, old, new)| (path.as_path(), old.as_deref(), new.as_deref())),
+        );
+        if dependencies.is_empty() {
+            return Ok(None);
+        }
+
+        let next_chunk_id = files
+            .iter()
+            .flat_map(|f| &f.chunks)
+            .map(|c| c.id.0 + 1)
+            .max()
+            .unwrap_or(0);
+        files.push(dependencies.to_file_diff(next_chunk_id));
+        Ok(Some(dependencies))
+    }
+
+    /// Load the old and new content of each changed text file with hunks; files without
+    /// hunks are never scored, so nothing reads their content. Blobs are fetched in one batch.
+    /// Files larger than `max_file_size_bytes` on either side are marked oversized and
+    /// lose their chunks, so they are listed but never scored.
+    async fn load_contents(&self, files: &mut [FileDiff], source: &DiffSource) -> CraiResult<()> {
+        let limit = self.config.max_file_size_bytes;
+        let needs_content =
+            |file: &FileDiff| file.content_kind.is_text() && file.oversized.is_none() && !file.chunks.is_empty();
+
+        // (file index, new side?) for each blob asked for
+        let mut wanted = Vec::new();
+        let mut objects = Vec::new();
+        for (idx, file) in files.iter().enumerate().filter(|(_, f)| needs_content(f)) {
+            if file.status != FileStatus::Added {
+                let old_path = file.old_path.as_ref().unwrap_or(&file.path);
+                wanted.push((idx, false));
+                objects.push((source.old_rev().to_string(), old_path.clone()));
+            }
+            if let Some(rev) = source.new_rev() {
+                if file.status != FileStatus::Deleted && file.new_content.is_none() {
+                    wanted.push((idx, true));
+                    objects.push((rev.to_string(), file.path.clone()));
+                }
+            }
+        }
+        let blobs = self.vcs.get_files_at_refs(&objects, limit).await?;
+
+        let mut loaded: Vec<(Option<FileAtRef>, Option<FileAtRef>)> = vec![(None, None); files.len()];
+        for ((idx, new_side), blob) in wanted.into_iter().zip(blobs) {
+            let slot = if new_side { &mut loaded[idx].1 } else { &mut loaded[idx].0 };
+            *slot = Some(blob);
+        }
+
+        for (file, (old, new)) in files.iter_mut().zip(loaded) {
+            if !needs_content(file) {
+                continue;
+            }
+
+            // The working tree side is read from disk rather than through git
+            let new = match new {
+                None if source.new_rev().is_none()
+                    && file.status != FileStatus::Deleted
+                    && file.new_content.is_none() =>
+                {
+                    Some(read_working_file(self.vcs.repo_path(), &file.path, limit))
+                }
+                new => new,
+            };
+
+            let oversized = [&old, &new]
+                .into_iter()
+                .filter_map(|blob| match blob {
+                    Some(FileAtRef::Oversized(size)) => Some(*size),
+                    _ => None,
+                })
+                .max()
+                .or_else(|| file.new_content.as_ref().map(|c| c.len() as u64).filter(|s| *s > limit));
+            if let Some(size) = oversized {
+                file.oversized = Some(size);
+                file.chunks.clear();
+                file.new_content = None;
+                continue;
+            }
+
+            if let Some(FileAtRef::Content(content)) = old {
+                file.old_content = Some(content);
+            }
+            if let Some(FileAtRef::Content(content)) = new {
+                file.new_content = Some(content);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace git's hunks with those of the configured backend, if it is not git.
+    /// Files keep their git hunks when the backend fails; a missing difft disables it entirely.
+    async fn apply_backend(&self, files: &mut [FileDiff]) -> CraiResult<()> {
+        if self.config.backend == DiffBackend::Git {
+            return Ok(());
+        }
 
-        let mut current_file: Option<FileDiffBuilder> = None;
+        let difft = Difftastic::new(self.config.difft_path.clone());
+        for file in files.iter_mut() {
+            if !file.content_kind.is_text() || file.chunks.is_empty() {
+                continue;
+            }
 
-        for line in diff_text.lines() {
-            if line.starts_with("diff --git") {
-                // Finish previous file if any
-                if let Some(builder) = current_file.take() {
-                    files.push(builder.build());
+            match difft
+                .diff(
+                    &file.path,
+                    file.old_content.as_deref(),
+                    file.new_content.as_deref(),
+                )
+                .await
+            {
+                Ok(mut chunks) => {
+                    difft::inherit_headers(&mut chunks, &file.chunks);
+                    file.chunks = chunks;
+                }
+                Err(e @ CraiError::DifftasticNotFound) => {
+                    tracing::warn!("{}; falling back to git diff", e);
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "difftastic failed for {}, using git diff: {}",
+                        file.path.display(),
+                        e
+                    );
                 }
+            }
+        }
+
+        // Backend chunks are numbered per file; make ids unique across the diff again
+        for (id, chunk) in files.iter_mut().flat_map(|f| f.chunks.iter_mut()).enumerate() {
+            chunk.id = ChunkId(id as u64);
+        }
+
+        Ok(())
+    }
+
+    /// Append untracked files not ignored by git or `.craiignore` as added files with a single
+    /// synthesized chunk
+    async fn add_untracked_files(
+        &self,
+        files: &mut Vec<FileDiff>,
+        errors: &mut Vec<ParseError>,
+    ) -> CraiResult<()> {
+        let untracked = self.vcs.get_untracked_files().await?;
+        if untracked.is_empty() {
+            return Ok(());
+        }
+
+        let mut next_chunk_id = files
+            .iter()
+            .flat_map(|f| &f.chunks)
+            .map(|c| c.id.0 + 1)
+            .max()
+            .unwrap_or(0);
+
+        for path in untracked {
+            if self.ignore.is_ignored(&path) {
+                continue;
+            }
+            let full_path = self.vcs.repo_path().join(&path);
+            let metadata = match std::fs::symlink_metadata(&full_path) {
+                Ok(m) => m,
+                Err(e) => {
+                    errors.push(ParseError {
+                        file_path: path,
+                        message: format!("Failed to read untracked file: {}", e),
+                        line: None,
+                    });
+                    continue;
+                }
+            };
+
+            // Untracked nested repositories are listed as directories
+            if metadata.is_dir() {
+                continue;
+            }
+
+            let mut builder = FileDiffBuilder::new(path.clone(), path.clone());
+            builder.status = FileStatus::Added;
+            builder.in_header = false;
 
-                // Start new file
-                let path = parse_diff_header(line);
-                current_file = Some(FileDiffBuilder::new(path));
-            } else if let Some(ref mut builder) = current_file {
-                if line.starts_with("---") {
-                    // Old file path (we already have it from diff --git)
-                    if line == "--- /dev/null" {
-                        builder.status = FileStatus::Added;
+            if metadata.file_type().is_symlink() {
+                let Ok(target) = std::fs::read_link(&full_path) else {
+                    continue;
+                };
+                builder.new_mode = Some(SYMLINK_MODE.to_string());
+                builder.add_synthesized_chunk(ChunkId(next_chunk_id), &target.to_string_lossy());
+                next_chunk_id += 1;
+                files.push(builder.build());
+                continue;
+            }
+
+            if metadata.len() > self.config.max_file_size_bytes {
+                let mut file = builder.build();
+                file.oversized = Some(metadata.len());
+                files.push(file);
+                continue;
+            }
+
+            let bytes = match std::fs::read(&full_path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    errors.push(ParseError {
+                        file_path: path,
+                        message: format!("Failed to read untracked file: {}", e),
+                        line: None,
+                    });
+                    continue;
+                }
+            };
+            // Same heuristic as git: a NUL byte near the start means binary, whatever the
+            // encoding of the rest
+            if is_binary(&bytes) {
+                let mut file = builder.build();
+                file.content_kind = ContentKind::Binary {
+                    old_size: None,
+                    new_size: Some(metadata.len()),
+                    old_blob: None,
+                    new_blob: None,
+                };
+                files.push(file);
+            } else {
+                let text = String::from_utf8_lossy(&bytes).into_owned();
+                if !text.is_empty() {
+                    builder.add_synthesized_chunk(ChunkId(next_chunk_id), &text);
+                    next_chunk_id += 1;
+                }
+                let mut file = builder.build();
+                file.new_content = Some(text);
+                files.push(file);
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(())
+    }
+
+    /// Fill in binary sizes and submodule commit ranges, which the diff text does not carry.
+    /// When `new_side_in_worktree` is set, new binary sizes fall back to the working tree file.
+    async fn resolve_content_metadata(
+        &self,
+        files: &mut [FileDiff],
+        new_side_in_worktree: bool,
+    ) -> CraiResult<()> {
+        for file in files.iter_mut() {
+            match &mut file.content_kind {
+                ContentKind::Text | ContentKind::Symlink { .. } => {}
+                ContentKind::Binary {
+                    old_size,
+                    new_size,
+                    old_blob,
+                    new_blob,
+                } => {
+                    if let Some(blob) = old_blob {
+                        *old_size = self.vcs.get_object_size(blob).await?;
                     }
-                } else if line.starts_with("+++") {
-                    // New file path
-                    if line == "+++ /dev/null" {
-                        builder.status = FileStatus::Deleted;
+                    if let Some(blob) = new_blob {
+                        *new_size = self.vcs.get_object_size(blob).await?;
                     }
-                } else if line.starts_with("@@") {
-                    // Chunk header
-                    if let Some((old_range, new_range, header)) = parse_chunk_header(line) {
-                        builder.start_chunk(ChunkId(chunk_id_counter), old_range, new_range, header);
-                        chunk_id_counter += 1;
-                    } else {
-                        errors.push(ParseError {
-                            file_path: builder.path.clone(),
-                            message: format!("Failed to parse chunk header: {}", line),
-                            line: None,
-                        });
+                    // Working tree blobs are hashed for the diff but never written to the object store
+                    if new_size.is_none() && new_side_in_worktree && file.status != FileStatus::Deleted {
+                        *new_size = std::fs::metadata(self.vcs.repo_path().join(&file.path))
+                            .ok()
+                            .map(|m| m.len());
                     }
-                } else if line.starts_with('+') && !line.starts_with("+++") {
-                    builder.add_line(LineKind::Add, &line[1..]);
-                } else if line.starts_with('-') && !line.starts_with("---") {
-                    builder.add_line(LineKind::Remove, &line[1..]);
-                } else if line.starts_with(' ') || line.is_empty() {
-                    let content = if line.is_empty() { "" } else { &line[1..] };
-                    builder.add_line(LineKind::Context, content);
-                } else if line.starts_with('\\') {
-                    // "\ No newline at end of file" - skip
                 }
+                ContentKind::Submodule {
+                    old_commit: Some(old),
+                    new_commit: Some(new),
+                    commits,
+                    ..
+                } if old != new => {
+                    *commits = self.vcs.get_submodule_commits(&file.path, old, new).await?;
+                }
+                ContentKind::Submodule { .. } => {}
             }
         }
 
-        // Don't forget the last file
-        if let Some(builder) = current_file {
-            files.push(builder.build());
+        Ok(())
+    }
+}
+
+/// Where the old and new sides of a diff are read from
+#[derive(Debug, Clone)]
+enum DiffSource {
+    /// Two commits (or any revisions)
+    Refs { base: String, compare: String },
+    /// HEAD against the index
+    Index,
+    /// HEAD against the working tree
+    WorkingTree,
+}
+
+impl DiffSource {
+    /// Revision holding the old side
+    fn old_rev(&self) -> &str {
+        match self {
+            Self::Refs { base, .. } => base,
+            Self::Index | Self::WorkingTree => "HEAD",
         }
+    }
 
-        Ok((files, errors))
+    /// Revision holding the new side; empty for the index, `None` for the working tree
+    fn new_rev(&self) -> Option<&str> {
+        match self {
+            Self::Refs { compare, .. } => Some(compare),
+            Self::Index => Some(""),
+            Self::WorkingTree => None,
+        }
+    }
+
+    async fn read_old(&self, vcs: &dyn Vcs, path: &Path) -> CraiResult<Option<String>> {
+        vcs.get_file_at_ref(self.old_rev(), path).await
+    }
+
+    async fn read_new(&self, vcs: &dyn Vcs, path: &Path) -> CraiResult<Option<String>> {
+        match self.new_rev() {
+            Some(rev) => vcs.get_file_at_ref(rev, path).await,
+            None => Ok(std::fs::read(vcs.repo_path().join(path))
+                .ok()
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())),
+        }
     }
 }
 
+/// A file in the working tree, read only when it is within `max_size` bytes
+fn read_working_file(repo_path: &Path, path: &Path, max_size: u64) -> FileAtRef {
+    let full_path = repo_path.join(path);
+    match std::fs::metadata(&full_path) {
+        Ok(metadata) if metadata.len() > max_size => FileAtRef::Oversized(metadata.len()),
+        Ok(_) => std::fs::read(&full_path)
+            .map(|bytes| FileAtRef::Content(String::from_utf8_lossy(&bytes).into_owned()))
+            .unwrap_or(FileAtRef::Missing),
+        Err(_) => FileAtRef::Missing,
+    }
+}
+
+/// How much of a file git looks at when deciding whether it is binary
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Git's binary check: a NUL byte within the first 8000 bytes
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+/// Git object mode of a submodule entry
+const GITLINK_MODE: &str = "160000";
+/// Git object mode of a symbolic link
+const SYMLINK_MODE: &str = "120000";
+
 struct FileDiffBuilder {
     path: PathBuf,
+    old_path: PathBuf,
     status: FileStatus,
+    old_mode: Option<String>,
+    new_mode: Option<String>,
+    similarity: Option<u8>,
+    /// Mode shown on the "index" line when it did not change
+    index_mode: Option<String>,
+    old_blob: Option<String>,
+    new_blob: Option<String>,
+    is_binary: bool,
     language: Language,
     chunks: Vec<DiffChunk>,
     current_chunk: Option<ChunkBuilder>,
+    /// True until the first hunk header; extended git headers are only valid here
+    in_header: bool,
 }
 
 impl FileDiffBuilder {
-    fn new(path: PathBuf) -> Self {
+    fn new(old_path: PathBuf, path: PathBuf) -> Self {
         let language = Language::from_path(&path);
         Self {
             path,
+            old_path,
             status: FileStatus::Modified,
+            old_mode: None,
+            new_mode: None,
+            similarity: None,
+            index_mode: None,
+            old_blob: None,
+            new_blob: None,
+            is_binary: false,
             language,
             chunks: Vec::new(),
             current_chunk: None,
+            in_header: true,
+        }
+    }
+
+    /// Interpret one extended header line (between "diff --git" and the first "@@")
+    fn parse_header_line(&mut self, line: &str) {
+        if let Some(rest) = line.strip_prefix("--- ") {
+            if rest == "/dev/null" {
+                self.status = FileStatus::Added;
+            } else {
+                self.old_path = parse_git_path(rest);
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if rest == "/dev/null" {
+                self.status = FileStatus::Deleted;
+            } else {
+                self.set_new_path(parse_git_path(rest));
+            }
+        } else if let Some(mode) = line.strip_prefix("new file mode ") {
+            self.status = FileStatus::Added;
+            self.new_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            self.status = FileStatus::Deleted;
+            self.old_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            self.old_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            self.new_mode = Some(mode.to_string());
+        } else if let Some(pct) = line.strip_prefix("similarity index ") {
+            self.similarity = pct.trim_end_matches('%').parse().ok();
+            self.apply_similarity();
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            self.old_path = path_from_bytes(unquote_path(path));
+            self.status = FileStatus::Renamed {
+                similarity_percent: self.similarity.unwrap_or(100),
+            };
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            self.set_new_path(path_from_bytes(unquote_path(path)));
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            self.old_path = path_from_bytes(unquote_path(path));
+            self.status = FileStatus::Copied {
+                similarity_percent: self.similarity.unwrap_or(100),
+            };
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            self.set_new_path(path_from_bytes(unquote_path(path)));
+        } else if let Some(rest) = line.strip_prefix("index ") {
+            // "index <old>..<new> [mode]"
+            let mut parts = rest.split_whitespace();
+            if let Some((old, new)) = parts.next().and_then(|r| r.split_once("..")) {
+                self.old_blob = non_null_blob(old);
+                self.new_blob = non_null_blob(new);
+            }
+            self.index_mode = parts.next().map(str::to_string);
+        } else if (line.starts_with("Binary files ") && line.ends_with(" differ"))
+            || line == "GIT binary patch"
+        {
+            self.is_binary = true;
+        }
+        // "dissimilarity index" carries nothing we track
+    }
+
+    /// Object mode of the file, preferring the new side
+    fn object_mode(&self) -> Option<&str> {
+        self.new_mode
+            .as_deref()
+            .or(self.index_mode.as_deref())
+            .or(self.old_mode.as_deref())
+    }
+
+    /// Work out what kind of object changed; gitlinks and symlinks are turned into metadata
+    fn content_kind(&self) -> ContentKind {
+        match self.object_mode() {
+            Some(GITLINK_MODE) => {
+                let (old, new) = self.removed_and_added(|l| l.strip_prefix("Subproject commit "));
+                let dirty = new.as_deref().is_some_and(|c| c.ends_with("-dirty"));
+                ContentKind::Submodule {
+                    old_commit: old.map(|c| c.trim_end_matches("-dirty").to_string()),
+                    new_commit: new.map(|c| c.trim_end_matches("-dirty").to_string()),
+                    dirty,
+                    commits: Vec::new(),
+                }
+            }
+            Some(SYMLINK_MODE) => {
+                let (old_target, new_target) = self.removed_and_added(Some);
+                ContentKind::Symlink {
+                    old_target,
+                    new_target,
+                }
+            }
+            _ if self.is_binary => ContentKind::Binary {
+                old_size: None,
+                new_size: None,
+                old_blob: self.old_blob.clone(),
+                new_blob: self.new_blob.clone(),
+            },
+            _ => ContentKind::Text,
+        }
+    }
+
+    /// First removed and added line of the (single) hunk, passed through `extract`
+    fn removed_and_added<'a>(
+        &'a self,
+        extract: impl Fn(&'a str) -> Option<&'a str>,
+    ) -> (Option<String>, Option<String>) {
+        let lines = self
+            .chunks
+            .iter()
+            .flat_map(|c| &c.lines)
+            .chain(self.current_chunk.iter().flat_map(|c| &c.lines));
+        let mut old = None;
+        let mut new = None;
+        for line in lines {
+            let slot = match line.kind {
+                LineKind::Remove => &mut old,
+                LineKind::Add => &mut new,
+                LineKind::Context => continue,
+            };
+            if slot.is_none() {
+                *slot = extract(&line.content).map(str::to_string);
+            }
+        }
+        (old, new)
+    }
+
+    fn set_new_path(&mut self, path: PathBuf) {
+        self.language = Language::from_path(&path);
+        self.path = path;
+    }
+
+    /// Similarity may be reported before or after the rename/copy lines
+    fn apply_similarity(&mut self) {
+        let Some(pct) = self.similarity else { return };
+        match &mut self.status {
+            FileStatus::Renamed { similarity_percent } | FileStatus::Copied { similarity_percent } => {
+                *similarity_percent = pct;
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a chunk that adds every line of `content`, as git would show for a new file
+    fn add_synthesized_chunk(&mut self, id: ChunkId, content: &str) {
+        let line_count = content.lines().count() as u32;
+        self.start_chunk(
+            id,
+            LineRange { start: 0, count: 0 },
+            LineRange {
+                start: 1,
+                count: line_count,
+            },
+            String::new(),
+        );
+        for line in content.lines() {
+            self.add_line(LineKind::Add, line);
         }
     }
 
@@ -170,9 +799,16 @@ impl FileDiffBuilder {
             lines: Vec::new(),
             current_old_line: old_range.start,
             current_new_line: new_range.start,
+            remaining_old: old_range.count,
+            remaining_new: new_range.count,
         });
     }
 
+    /// Inside a hunk that still expects lines, where "---" and "+++" are removed and added lines
+    fn in_hunk(&self) -> bool {
+        self.current_chunk.as_ref().is_some_and(|chunk| !chunk.is_exhausted())
+    }
+
     fn add_line(&mut self, kind: LineKind, content: &str) {
         if let Some(ref mut chunk) = self.current_chunk {
             chunk.add_line(kind, content);
@@ -180,18 +816,40 @@ impl FileDiffBuilder {
     }
 
     fn build(mut self) -> FileDiff {
+        let content_kind = self.content_kind();
+
         // Finish current chunk if any
         if let Some(chunk) = self.current_chunk.take() {
             self.chunks.push(chunk.build());
         }
 
+        // Submodule and symlink "hunks" only restate the metadata above
+        if !content_kind.is_text() {
+            self.chunks.clear();
+        }
+
+        let old_path = match self.status {
+            FileStatus::Renamed { .. } | FileStatus::Copied { .. } => Some(self.old_path),
+            _ => None,
+        };
+
+        let mode_change = match (&self.old_mode, &self.new_mode) {
+            (Some(old), Some(new)) if old != new => ModeChange::parse(old, new),
+            _ => None,
+        };
+
         FileDiff {
             path: self.path,
+            old_path,
             status: self.status,
+            mode_change,
+            content_kind,
             language: Some(self.language),
             chunks: self.chunks,
             old_content: None,
             new_content: None,
+            oversized: None,
+            owners: Vec::new(),
         }
     }
 }
@@ -204,6 +862,9 @@ struct ChunkBuilder {
     lines: Vec<DiffLine>,
     current_old_line: u32,
     current_new_line: u32,
+    /// Old and new lines the "@@" header announced that have not been read yet
+    remaining_old: u32,
+    remaining_new: u32,
 }
 
 impl ChunkBuilder {
@@ -214,16 +875,20 @@ impl ChunkBuilder {
                 let new = self.current_new_line;
                 self.current_old_line += 1;
                 self.current_new_line += 1;
+                self.remaining_old = self.remaining_old.saturating_sub(1);
+                self.remaining_new = self.remaining_new.saturating_sub(1);
                 (Some(old), Some(new))
             }
             LineKind::Add => {
                 let new = self.current_new_line;
                 self.current_new_line += 1;
+                self.remaining_new = self.remaining_new.saturating_sub(1);
                 (None, Some(new))
             }
             LineKind::Remove => {
                 let old = self.current_old_line;
                 self.current_old_line += 1;
+                self.remaining_old = self.remaining_old.saturating_sub(1);
                 (Some(old), None)
             }
         };
@@ -236,6 +901,10 @@ impl ChunkBuilder {
         });
     }
 
+    fn is_exhausted(&self) -> bool {
+        self.remaining_old == 0 && self.remaining_new == 0
+    }
+
     fn build(self) -> DiffChunk {
         DiffChunk {
             id: self.id,
@@ -243,21 +912,220 @@ impl ChunkBuilder {
             new_range: self.new_range,
             header: self.header,
             lines: self.lines,
+            symbol: None,
         }
     }
 }
 
-fn parse_diff_header(line: &str) -> PathBuf {
-    // Format: "diff --git a/path/to/file b/path/to/file"
-    if let Some(rest) = line.strip_prefix("diff --git ") {
-        // Try to find "b/" which marks the new path
-        if let Some(b_idx) = rest.find(" b/") {
-            let new_path = &rest[b_idx + 3..];
-            return PathBuf::from(new_path);
+/// Parse unified diff text into files and chunks.
+/// Accepts `git diff` output as well as plain `diff -u` output without "diff --git" lines.
+pub fn parse_unified_diff(diff_text: &str) -> CraiResult<(Vec<FileDiff>, Vec<ParseError>)> {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    let mut chunk_id_counter = 0u64;
+
+    let mut current_file: Option<FileDiffBuilder> = None;
+
+    let lines: Vec<&str> = diff_text.lines().collect();
+    for (idx, &line) in lines.iter().enumerate() {
+        let in_git_header = current_file.as_ref().is_some_and(|b| b.in_header);
+        let in_hunk = current_file.as_ref().is_some_and(|b| b.in_hunk());
+
+        if line.starts_with("diff --git") {
+            // Finish previous file if any
+            if let Some(builder) = current_file.take() {
+                files.push(builder.build());
+            }
+
+            // Start new file
+            let (old_path, new_path) = parse_diff_header(line);
+            current_file = Some(FileDiffBuilder::new(old_path, new_path));
+        } else if let Some((old_path, new_path)) =
+            plain_header_paths(&lines[idx..]).filter(|_| !in_git_header && !in_hunk)
+        {
+            // A "---"/"+++" pair outside a git header and between hunks starts a file in
+            // plain diff output
+            if let Some(builder) = current_file.take() {
+                files.push(builder.build());
+            }
+
+            let mut builder = FileDiffBuilder::new(old_path, new_path);
+            builder.parse_header_line(line);
+            current_file = Some(builder);
+        } else if let Some(ref mut builder) = current_file {
+            if builder.in_header {
+                // Extended header lines only appear before the first hunk
+                if line.starts_with("@@") {
+                    builder.in_header = false;
+                } else {
+                    builder.parse_header_line(line);
+                    continue;
+                }
+            }
+
+            if line.starts_with("@@") {
+                // Chunk header
+                if let Some((old_range, new_range, header)) = parse_chunk_header(line) {
+                    builder.start_chunk(ChunkId(chunk_id_counter), old_range, new_range, header);
+                    chunk_id_counter += 1;
+                } else {
+                    errors.push(ParseError {
+                        file_path: builder.path.clone(),
+                        message: format!("Failed to parse chunk header: {}", line),
+                        line: None,
+                    });
+                }
+            } else if let Some(content) = line.strip_prefix('+') {
+                builder.add_line(LineKind::Add, content);
+            } else if let Some(content) = line.strip_prefix('-') {
+                builder.add_line(LineKind::Remove, content);
+            } else if line.starts_with(' ') || line.is_empty() {
+                let content = if line.is_empty() { "" } else { &line[1..] };
+                builder.add_line(LineKind::Context, content);
+            } else if line.starts_with('\\') {
+                // "\ No newline at end of file" - skip
+            }
+        }
+    }
+
+    // Don't forget the last file
+    if let Some(builder) = current_file {
+        files.push(builder.build());
+    }
+
+    Ok((files, errors))
+}
+
+/// Old and new paths when `lines` starts with a "--- old", "+++ new", "@@" sequence.
+/// A missing side ("/dev/null") takes the path of the other.
+fn plain_header_paths(lines: &[&str]) -> Option<(PathBuf, PathBuf)> {
+    let old = lines.first()?.strip_prefix("--- ")?;
+    let new = lines.get(1)?.strip_prefix("+++ ")?;
+    if !lines.get(2)?.starts_with("@@") {
+        return None;
+    }
+
+    let old_path = parse_git_path(old);
+    let new_path = parse_git_path(new);
+    let dev_null = Path::new("/dev/null");
+    match (old_path == dev_null, new_path == dev_null) {
+        (true, _) => Some((new_path.clone(), new_path)),
+        (_, true) => Some((old_path.clone(), old_path)),
+        _ => Some((old_path, new_path)),
+    }
+}
+
+fn parse_diff_header(line: &str) -> (PathBuf, PathBuf) {
+    // Format: "diff --git a/path/to/file b/path/to/file" (paths may be C-quoted)
+    let Some(rest) = line.strip_prefix("diff --git ") else {
+        return (PathBuf::from("unknown"), PathBuf::from("unknown"));
+    };
+
+    if rest.starts_with('"') {
+        if let Some((old, remainder)) = split_quoted(rest) {
+            return (strip_side_prefix(old), parse_git_path(remainder.trim_start()));
         }
     }
+
+    // Unquoted: when old and new paths are identical the header splits evenly
+    if rest.len() % 2 == 1 {
+        let mid = rest.len() / 2;
+        if rest.is_char_boundary(mid) && &rest[mid..mid + 1] == " " {
+            let (old, new) = (&rest[..mid], &rest[mid + 1..]);
+            if old.get(2..) == new.get(2..) {
+                return (parse_git_path(old), parse_git_path(new));
+            }
+        }
+    }
+
+    // Otherwise find " b/" which marks the new path; renames are corrected by later header lines
+    if let Some(b_idx) = rest.find(" b/") {
+        return (parse_git_path(&rest[..b_idx]), parse_git_path(&rest[b_idx + 1..]));
+    }
+
     // Fallback: just use what we can find
-    PathBuf::from("unknown")
+    (PathBuf::from("unknown"), PathBuf::from("unknown"))
+}
+
+/// Blob id from an index line, or `None` for the all-zero id of a missing side
+fn non_null_blob(id: &str) -> Option<String> {
+    (!id.is_empty() && !id.bytes().all(|b| b == b'0')).then(|| id.to_string())
+}
+
+/// Parse a path from a diff header, removing quoting and the a/ or b/ prefix
+fn parse_git_path(raw: &str) -> PathBuf {
+    // "--- a/file\t" may carry a trailing tab when the path contains spaces,
+    // and plain diff output puts a timestamp after the tab
+    strip_side_prefix(unquote_path(raw.split('\t').next().unwrap_or(raw)))
+}
+
+/// Remove the a/ or b/ prefix from an unquoted path
+fn strip_side_prefix(mut path: Vec<u8>) -> PathBuf {
+    if path.starts_with(b"a/") || path.starts_with(b"b/") {
+        path.drain(..2);
+    }
+    path_from_bytes(path)
+}
+
+/// Split a leading C-quoted string from the rest of the input
+fn split_quoted(s: &str) -> Option<(Vec<u8>, &str)> {
+    let mut escaped = false;
+    for (idx, c) in s.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some((unquote_path(&s[..=idx]), &s[idx + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Undo git's C-style path quoting ("\"dir/na\\303\\257ve.rs\"" -> dir/naïve.rs).
+/// Returns raw bytes, since quoted names need not be UTF-8.
+fn unquote_path(s: &str) -> Vec<u8> {
+    let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) else {
+        return s.as_bytes().to_vec();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('a') => bytes.push(0x07),
+            Some('b') => bytes.push(0x08),
+            Some('f') => bytes.push(0x0c),
+            Some('v') => bytes.push(0x0b),
+            Some(d @ '0'..='7') => {
+                let mut value = d.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => {
+                let mut buf = [0u8; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+
+    bytes
 }
 
 fn parse_chunk_header(line: &str) -> Option<(LineRange, LineRange, String)> {
@@ -288,3 +1156,39 @@ fn parse_range(s: &str) -> Option<LineRange> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashed_lines_inside_a_hunk_are_not_file_headers() {
+        let diff = "\
+--- a/notes.md
++++ b/notes.md
+@@ -1,2 +1,2 @@
+ # Notes
+--- old rule
++++ new rule
+@@ -9,2 +9,2 @@
+-a
++b
+ end
+--- a/other.md
++++ b/other.md
+@@ -1 +1 @@
+-x
++y
+";
+        let (files, errors) = parse_unified_diff(diff).expect("parse");
+        assert!(errors.is_empty());
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("notes.md"), PathBuf::from("other.md")]);
+
+        assert_eq!(files[0].chunks.len(), 2);
+        let lines = &files[0].chunks[0].lines;
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[1].kind, lines[1].content.as_str()), (LineKind::Remove, "-- old rule"));
+        assert_eq!((lines[2].kind, lines[2].content.as_str()), (LineKind::Add, "++ new rule"));
+    }
+}
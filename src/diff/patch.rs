@@ -0,0 +1,298 @@
+use crate::diff::chunk::DiffResult;
+use crate::diff::git::CommitInfo;
+use crate::diff::moves::detect_moves;
+use crate::diff::parser::parse_unified_diff;
+use crate::error::CraiResult;
+
+/// Base label for patches that don't name the commit they were made from
+const PRE_IMAGE: &str = "pre-image";
+
+/// One diff read from patch input, with the commit it describes when the input has one
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub commit: Option<CommitInfo>,
+    pub diff_result: DiffResult,
+}
+
+/// Parse patch input without a repository: a `git format-patch` mbox, `git show` or
+/// `git log -p` output, or a plain unified diff. `source` names the input in labels.
+pub fn parse_patch(input: &str, source: &str) -> CraiResult<Vec<Patch>> {
+    let lines: Vec<&str> = input.lines().collect();
+    let first = lines.iter().find(|l| !l.trim().is_empty()).copied().unwrap_or("");
+
+    if first.starts_with("From ") {
+        // Body lines starting with "From " are escaped as ">From ", so a blank line
+        // followed by "From " always starts the next message
+        split_at(&lines, |idx| {
+            lines[idx].starts_with("From ") && (idx == 0 || lines[idx - 1].is_empty())
+        })
+        .into_iter()
+        .map(parse_mbox_message)
+        // Cover letters carry no diff
+        .filter(|(_, diff)| !diff.is_empty())
+        .map(|(commit, diff)| patch_for(Some(commit), &diff, source))
+        .collect()
+    } else if is_commit_line(first) {
+        split_at(&lines, |idx| is_commit_line(lines[idx]))
+            .into_iter()
+            .map(|message| {
+                let (commit, diff) = parse_show_commit(message);
+                patch_for(Some(commit), &diff, source)
+            })
+            .collect()
+    } else {
+        Ok(vec![patch_for(None, input, source)?])
+    }
+}
+
+fn patch_for(commit: Option<CommitInfo>, diff: &str, source: &str) -> CraiResult<Patch> {
+    let (files, parse_errors) = parse_unified_diff(diff)?;
+
+    let (base_branch, compare_branch) = match &commit {
+        Some(commit) if !commit.sha.is_empty() => (format!("{}^", commit.sha), commit.sha.clone()),
+        _ => (PRE_IMAGE.to_string(), source.to_string()),
+    };
+
+    let moves = detect_moves(&files);
+
+    Ok(Patch {
+        commit,
+        diff_result: DiffResult {
+            base_branch,
+            compare_branch,
+            merge_base: None,
+            files,
+            parse_errors,
+            interdiff: None,
+            moves,
+            dependencies: None,
+            head: None,
+        },
+    })
+}
+
+/// Split `lines` into sections, each starting at a line for which `starts` holds.
+/// Anything before the first such line is dropped.
+fn split_at<'a>(lines: &'a [&'a str], starts: impl Fn(usize) -> bool) -> Vec<&'a [&'a str]> {
+    let starts: Vec<usize> = (0..lines.len()).filter(|&idx| starts(idx)).collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+            &lines[start..end]
+        })
+        .collect()
+}
+
+/// "commit <sha>" as printed by `git show` and `git log`, possibly followed by decorations
+fn is_commit_line(line: &str) -> bool {
+    line.strip_prefix("commit ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .is_some_and(is_sha)
+}
+
+fn is_sha(s: &str) -> bool {
+    s.len() >= 7 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Split one mbox message into its commit and the diff text
+fn parse_mbox_message(lines: &[&str]) -> (CommitInfo, String) {
+    // "From <sha> Mon Sep 17 00:00:00 2001"; mailing list archives put an address here instead
+    let sha = lines[0]
+        .split_whitespace()
+        .nth(1)
+        .filter(|s| is_sha(s))
+        .unwrap_or("")
+        .to_string();
+
+    let header_end = lines.iter().position(|l| l.is_empty()).unwrap_or(lines.len());
+    let headers = unfold_headers(&lines[1..header_end]);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| decode_header(value))
+    };
+
+    let author = header("From").map(|from| author_name(&from)).unwrap_or_default();
+    let summary = header("Subject")
+        .map(|subject| strip_subject_tags(&subject).to_string())
+        .unwrap_or_default();
+
+    let rest = lines.get(header_end + 1..).unwrap_or(&[]);
+    let diff_start = rest.iter().position(|l| starts_diff(l)).unwrap_or(rest.len());
+
+    // The message body ends at the "---" line above the diffstat
+    let body_end = rest[..diff_start]
+        .iter()
+        .position(|l| *l == "---")
+        .unwrap_or(diff_start);
+    let body = rest[..body_end].join("\n");
+
+    let diff = strip_signature(&rest[diff_start..]).join("\n");
+
+    (commit_info(sha, author, summary, body.trim()), diff)
+}
+
+/// Split one `git show` commit into its commit and the diff text
+fn parse_show_commit(lines: &[&str]) -> (CommitInfo, String) {
+    let sha = lines[0]
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("")
+        .to_string();
+
+    let header_end = lines.iter().position(|l| l.is_empty()).unwrap_or(lines.len());
+    let author = lines[1..header_end]
+        .iter()
+        .find_map(|l| l.strip_prefix("Author:"))
+        .map(author_name)
+        .unwrap_or_default();
+
+    let rest = lines.get(header_end + 1..).unwrap_or(&[]);
+    let diff_start = rest.iter().position(|l| starts_diff(l)).unwrap_or(rest.len());
+
+    // The message is indented by four spaces
+    let message: Vec<&str> = rest[..diff_start]
+        .iter()
+        .take_while(|l| l.is_empty() || l.starts_with("    "))
+        .map(|l| l.strip_prefix("    ").unwrap_or(l))
+        .collect();
+    let message = message.join("\n");
+    let message = message.trim();
+    let summary = message.lines().next().unwrap_or("").to_string();
+    let body = message.split_once('\n').map(|(_, body)| body.trim()).unwrap_or("");
+
+    (
+        commit_info(sha, author, summary, body),
+        rest[diff_start..].join("\n"),
+    )
+}
+
+fn commit_info(sha: String, author: String, summary: String, body: &str) -> CommitInfo {
+    let message = if body.is_empty() {
+        summary.clone()
+    } else {
+        format!("{}\n\n{}", summary, body)
+    };
+    CommitInfo {
+        sha,
+        author,
+        summary,
+        message,
+    }
+}
+
+/// Whether a line starts the diff part of a patch
+fn starts_diff(line: &str) -> bool {
+    line.starts_with("diff ") || line.starts_with("--- ")
+}
+
+/// Drop the "-- " signature `git format-patch` appends after the last hunk
+fn strip_signature<'a>(lines: &'a [&'a str]) -> &'a [&'a str] {
+    let end = lines.iter().rposition(|l| !l.is_empty()).map_or(0, |idx| idx + 1);
+    match lines[..end].iter().rposition(|l| *l == "-- ") {
+        // The signature is the git version, at most a couple of lines
+        Some(sig) if end - sig <= 3 => &lines[..sig],
+        _ => &lines[..end],
+    }
+}
+
+/// Join folded header lines and split them into (name, value) pairs
+fn unfold_headers(lines: &[&str]) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in lines {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+/// "Jane Doe <jane@example.com>" -> "Jane Doe", falling back to the address
+fn author_name(from: &str) -> String {
+    let from = from.trim();
+    match from.split_once('<') {
+        Some((name, address)) => {
+            let name = name.trim().trim_matches('"');
+            if name.is_empty() {
+                address.trim_end_matches('>').to_string()
+            } else {
+                name.to_string()
+            }
+        }
+        None => from.to_string(),
+    }
+}
+
+/// Remove "[PATCH v2 1/3]" style prefixes from a subject
+fn strip_subject_tags(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while subject.starts_with('[') {
+        match subject.find(']') {
+            Some(end) => subject = subject[end + 1..].trim_start(),
+            None => break,
+        }
+    }
+    subject
+}
+
+/// Decode RFC 2047 Q-encoded words, which `git format-patch` uses for non-ASCII headers.
+/// Other encodings are left as they are.
+fn decode_header(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let Some((text, end)) = decode_word(&rest[start..]) else {
+            break;
+        };
+        // Whitespace between adjacent encoded words is not part of the text
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            decoded.push_str(between);
+        }
+        decoded.push_str(&text);
+        rest = &rest[start + end..];
+        after_word = true;
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decode one "=?charset?q?text?=" word, returning the text and the word's length
+fn decode_word(word: &str) -> Option<(String, usize)> {
+    let inner = word.strip_prefix("=?")?;
+    let (_charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    if !encoding.eq_ignore_ascii_case("q") {
+        return None;
+    }
+    let end = inner.find("?=")?;
+    let encoded = &inner[..end];
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut iter = encoded.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [iter.next()?, iter.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            _ => bytes.push(b),
+        }
+    }
+
+    let len = word.len() - inner.len() + end + 2;
+    Some((String::from_utf8_lossy(&bytes).into_owned(), len))
+}
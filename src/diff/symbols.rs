@@ -0,0 +1,326 @@
+use crate::diff::chunk::{ChunkId, DiffChunk, FileDiff, Language, LineKind, LineRange};
+use std::path::Path;
+
+/// A definition and the lines it spans (1-based, inclusive)
+#[derive(Debug, Clone)]
+struct SymbolSpan {
+    start: u32,
+    end: u32,
+    path: String,
+}
+
+/// Definitions found in one side of a file, outermost first
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    spans: Vec<SymbolSpan>,
+}
+
+impl SymbolIndex {
+    /// Parse `source` and collect its definitions. `None` when the language has no grammar
+    /// or crai was built without the `syntax` feature.
+    pub fn build(path: &Path, language: Language, source: &str) -> Option<Self> {
+        syntax::definitions(path, language, source).map(|spans| Self { spans })
+    }
+
+    /// Path of the innermost definition containing `line`
+    pub fn symbol_at(&self, line: u32) -> Option<&str> {
+        // Spans are in pre-order, so the last one containing the line is the innermost
+        self.spans
+            .iter()
+            .rev()
+            .find(|s| s.start <= line && line <= s.end)
+            .map(|s| s.path.as_str())
+    }
+}
+
+/// Label every chunk with the innermost definition its changes fall in, and split chunks
+/// whose changes span several definitions into one chunk per definition, so each is scored
+/// on its own. Chunk ids are renumbered across the diff afterwards.
+pub fn annotate(files: &mut [FileDiff]) {
+    for file in files.iter_mut() {
+        let Some(language) = file.language else {
+            continue;
+        };
+        let index = |content: &Option<String>| {
+            content
+                .as_deref()
+                .and_then(|c| SymbolIndex::build(&file.path, language, c))
+        };
+        let old = index(&file.old_content);
+        let new = index(&file.new_content);
+        if old.is_none() && new.is_none() {
+            continue;
+        }
+
+        file.chunks = std::mem::take(&mut file.chunks)
+            .into_iter()
+            .flat_map(|chunk| split_chunk(chunk, old.as_ref(), new.as_ref()))
+            .collect();
+    }
+
+    for (id, chunk) in files.iter_mut().flat_map(|f| f.chunks.iter_mut()).enumerate() {
+        chunk.id = ChunkId(id as u64);
+    }
+}
+
+/// Split a chunk where its changed lines move from one definition to the next.
+/// Context lines between two definitions go with the later one once they leave the
+/// earlier one, so a sub-chunk starts at its definition's signature where possible.
+fn split_chunk(chunk: DiffChunk, old: Option<&SymbolIndex>, new: Option<&SymbolIndex>) -> Vec<DiffChunk> {
+    let symbol_of = |kind: LineKind, old_num: Option<u32>, new_num: Option<u32>| -> Option<String> {
+        let on_new = new.zip(new_num).and_then(|(index, num)| index.symbol_at(num));
+        let on_old = old.zip(old_num).and_then(|(index, num)| index.symbol_at(num));
+        match kind {
+            LineKind::Add => on_new,
+            LineKind::Remove => on_old,
+            LineKind::Context => on_new.or(on_old),
+        }
+        .map(str::to_string)
+    };
+    let symbols: Vec<Option<String>> = chunk
+        .lines
+        .iter()
+        .map(|l| symbol_of(l.kind, l.old_line_num, l.new_line_num))
+        .collect();
+
+    let changed: Vec<usize> = (0..chunk.lines.len())
+        .filter(|&idx| chunk.lines[idx].kind != LineKind::Context)
+        .collect();
+
+    // Index of the first line of every sub-chunk after the first
+    let mut cuts = Vec::new();
+    for pair in changed.windows(2) {
+        let (prev, next) = (pair[0], pair[1]);
+        if symbols[prev] != symbols[next] {
+            let cut = (prev + 1..next)
+                .find(|&idx| symbols[idx] != symbols[prev])
+                .unwrap_or(next);
+            cuts.push(cut);
+        }
+    }
+
+    let first_symbol = changed.first().and_then(|&idx| symbols[idx].clone());
+    if cuts.is_empty() {
+        return vec![DiffChunk {
+            symbol: first_symbol,
+            ..chunk
+        }];
+    }
+
+    let mut parts = Vec::with_capacity(cuts.len() + 1);
+    let mut next_old = chunk.old_range.start;
+    let mut next_new = chunk.new_range.start;
+    let bounds: Vec<usize> = std::iter::once(0)
+        .chain(cuts)
+        .chain(std::iter::once(chunk.lines.len()))
+        .collect();
+
+    for (n, range) in bounds.windows(2).enumerate() {
+        let lines = chunk.lines[range[0]..range[1]].to_vec();
+        let symbol = (range[0]..range[1])
+            .find(|&idx| chunk.lines[idx].kind != LineKind::Context)
+            .and_then(|idx| symbols[idx].clone());
+
+        let old_count = lines.iter().filter(|l| l.old_line_num.is_some()).count() as u32;
+        let new_count = lines.iter().filter(|l| l.new_line_num.is_some()).count() as u32;
+        let old_range = side_range(next_old, old_count);
+        let new_range = side_range(next_new, new_count);
+        next_old += old_count;
+        next_new += new_count;
+
+        parts.push(DiffChunk {
+            id: chunk.id,
+            old_range,
+            new_range,
+            // git's function context describes where the original hunk starts
+            header: if n == 0 { chunk.header.clone() } else { String::new() },
+            lines,
+            symbol,
+        });
+    }
+
+    parts
+}
+
+/// Range of `count` lines starting at `start`; empty ranges point at the line before,
+/// as in git's hunk headers
+fn side_range(start: u32, count: u32) -> LineRange {
+    LineRange {
+        start: if count == 0 { start.saturating_sub(1) } else { start },
+        count,
+    }
+}
+
+#[cfg(feature = "syntax")]
+mod syntax {
+    use super::SymbolSpan;
+    use crate::diff::chunk::Language;
+    use std::path::Path;
+    use tree_sitter::{Node, Parser};
+
+    /// Separator between the levels of a symbol path, as in `impl App::handle_action`
+    const PATH_SEPARATOR: &str = "::";
+
+    pub fn definitions(path: &Path, language: Language, source: &str) -> Option<Vec<SymbolSpan>> {
+        let grammar = match language {
+            Language::Rust => tree_sitter_rust::LANGUAGE,
+            Language::Python => tree_sitter_python::LANGUAGE,
+            Language::JavaScript => tree_sitter_javascript::LANGUAGE,
+            Language::TypeScript if path.extension().is_some_and(|e| e == "tsx") => {
+                tree_sitter_typescript::LANGUAGE_TSX
+            }
+            Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+            Language::Go => tree_sitter_go::LANGUAGE,
+            Language::Java => tree_sitter_java::LANGUAGE,
+            Language::C => tree_sitter_c::LANGUAGE,
+            Language::Cpp => tree_sitter_cpp::LANGUAGE,
+            Language::CSharp => tree_sitter_c_sharp::LANGUAGE,
+            Language::Ruby => tree_sitter_ruby::LANGUAGE,
+            _ => return None,
+        };
+
+        let mut parser = Parser::new();
+        if let Err(e) = parser.set_language(&grammar.into()) {
+            tracing::warn!("tree-sitter grammar for {} unusable: {}", language.name(), e);
+            return None;
+        }
+        let tree = parser.parse(source, None)?;
+        let source = source.as_bytes();
+
+        // (node, index of the enclosing span)
+        let mut spans: Vec<SymbolSpan> = Vec::new();
+        let mut stack: Vec<(Node, Option<usize>)> = vec![(tree.root_node(), None)];
+        let mut cursor = tree.walk();
+
+        while let Some((node, parent)) = stack.pop() {
+            let mut scope = parent;
+            if let Some(label) = label(node, language, source) {
+                let path = match parent {
+                    Some(idx) => format!("{}{}{}", spans[idx].path, PATH_SEPARATOR, label),
+                    None => label,
+                };
+                spans.push(SymbolSpan {
+                    start: node.start_position().row as u32 + 1,
+                    end: node.end_position().row as u32 + 1,
+                    path,
+                });
+                scope = Some(spans.len() - 1);
+            }
+
+            let children: Vec<Node> = node.named_children(&mut cursor).collect();
+            // Reversed, so children are visited in source order
+            stack.extend(children.into_iter().rev().map(|child| (child, scope)));
+        }
+
+        Some(spans)
+    }
+
+    /// Label of a definition node, or `None` for nodes that don't define anything
+    fn label(node: Node, language: Language, source: &[u8]) -> Option<String> {
+        let field = |name: &str| {
+            node.child_by_field_name(name)
+                .and_then(|n| n.utf8_text(source).ok())
+                .map(str::to_string)
+        };
+
+        match (language, node.kind()) {
+            (Language::Rust, "impl_item") => {
+                let ty = field("type")?;
+                Some(match field("trait") {
+                    Some(tr) => format!("impl {} for {}", tr, ty),
+                    None => format!("impl {}", ty),
+                })
+            }
+            (
+                Language::Rust,
+                "function_item" | "function_signature_item" | "trait_item" | "struct_item" | "enum_item"
+                | "union_item" | "mod_item" | "macro_definition",
+            ) => field("name"),
+
+            (Language::Python, "function_definition" | "class_definition") => field("name"),
+
+            (
+                Language::JavaScript | Language::TypeScript,
+                "function_declaration" | "generator_function_declaration" | "class_declaration"
+                | "abstract_class_declaration" | "method_definition" | "interface_declaration"
+                | "enum_declaration" | "internal_module",
+            ) => field("name"),
+            // const handler = () => { ... }
+            (Language::JavaScript | Language::TypeScript, "variable_declarator") => {
+                let value = node.child_by_field_name("value")?;
+                matches!(value.kind(), "arrow_function" | "function_expression" | "function" | "class")
+                    .then(|| field("name"))
+                    .flatten()
+            }
+
+            (Language::Go, "function_declaration" | "type_spec") => field("name"),
+            (Language::Go, "method_declaration") => {
+                let name = field("name")?;
+                let receiver = node
+                    .child_by_field_name("receiver")
+                    .and_then(|r| r.named_child(0))
+                    .and_then(|param| param.child_by_field_name("type"))
+                    .and_then(|ty| ty.utf8_text(source).ok());
+                Some(match receiver {
+                    Some(ty) => format!("{}.{}", ty.trim_start_matches('*'), name),
+                    None => name,
+                })
+            }
+
+            (
+                Language::Java,
+                "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration"
+                | "method_declaration" | "constructor_declaration",
+            ) => field("name"),
+
+            (
+                Language::CSharp,
+                "namespace_declaration" | "file_scoped_namespace_declaration" | "class_declaration"
+                | "struct_declaration" | "interface_declaration" | "record_declaration" | "enum_declaration"
+                | "method_declaration" | "constructor_declaration" | "property_declaration",
+            ) => field("name"),
+
+            (Language::C | Language::Cpp, "function_definition") => {
+                declarator_name(node.child_by_field_name("declarator")?, source)
+            }
+            // Only definitions with a body; `struct foo *p;` merely mentions the type
+            (Language::C | Language::Cpp, "struct_specifier" | "union_specifier" | "enum_specifier")
+            | (Language::Cpp, "class_specifier") => {
+                node.child_by_field_name("body")?;
+                field("name")
+            }
+            (Language::Cpp, "namespace_definition") => field("name"),
+
+            (Language::Ruby, "class" | "module" | "method" | "singleton_method") => field("name"),
+
+            _ => None,
+        }
+    }
+
+    /// Name of the function a C/C++ declarator declares, looking through pointers and
+    /// parameter lists
+    fn declarator_name(mut node: Node, source: &[u8]) -> Option<String> {
+        loop {
+            match node.kind() {
+                "identifier" | "field_identifier" | "qualified_identifier" | "destructor_name"
+                | "operator_name" => return node.utf8_text(source).ok().map(str::to_string),
+                _ => {
+                    node = node
+                        .child_by_field_name("declarator")
+                        .or_else(|| node.named_child(0))?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "syntax"))]
+mod syntax {
+    use super::SymbolSpan;
+    use crate::diff::chunk::Language;
+    use std::path::Path;
+
+    pub fn definitions(_path: &Path, _language: Language, _source: &str) -> Option<Vec<SymbolSpan>> {
+        None
+    }
+}
@@ -0,0 +1,240 @@
+use crate::config::FormattingNormalization;
+use crate::diff::chunk::Language;
+use crate::diff::comments::CommentSyntax;
+
+/// Split source text into tokens, ignoring whitespace.
+/// Words (identifiers, keywords, numbers), string literals, line comments and the language's
+/// operators are single tokens, read longest first, so `a && b` and `a & &b` stay apart.
+/// Any other character is a token of its own. Line breaks are ignored too, except in
+/// languages where they can end a statement: there a break after a token that may end one,
+/// outside parentheses and brackets, is a "\n" token.
+pub fn tokenize(
+    text: &str,
+    language: Language,
+    normalization: FormattingNormalization,
+) -> Vec<String> {
+    let line_comments = CommentSyntax::for_language(language).map_or(&[][..], |syntax| syntax.line);
+    let operators = operators(language);
+    let mut tokens = TokenStream {
+        significant_breaks: language.ends_statements_at_line_breaks(),
+        ..Default::default()
+    };
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c == '\n' {
+            tokens.line_break();
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if line_comments.iter().any(|prefix| text[start..].starts_with(prefix)) {
+            // The rest of the line, so code joined onto a comment line no longer matches
+            let end = text[start..].find('\n').map_or(text.len(), |len| start + len);
+            while chars.next_if(|&(idx, _)| idx < end).is_some() {}
+            let words: Vec<&str> = text[start..end].split_whitespace().collect();
+            tokens.push_comment(words.join(" "));
+            continue;
+        }
+
+        if c == '\'' && language == Language::Rust && is_lifetime(&text[start + 1..]) {
+            // 'a in Rust is a lifetime or label, not the start of a char literal
+            tokens.push(c.to_string());
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(idx, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                end = idx + next.len_utf8();
+                chars.next();
+            }
+            tokens.push(text[start..end].to_string());
+        } else if matches!(c, '"' | '\'' | '`') {
+            // Read to the closing quote, keeping the literal's inner whitespace
+            let mut literal = String::new();
+            let mut escaped = false;
+            for (_, next) in chars.by_ref() {
+                if escaped {
+                    // An escaped quote of either kind means the same once quotes are normalized
+                    if !(normalization.quote_style && matches!(next, '"' | '\'')) {
+                        literal.push('\\');
+                    }
+                    literal.push(next);
+                    escaped = false;
+                } else if next == '\\' {
+                    escaped = true;
+                } else if next == c {
+                    break;
+                } else {
+                    literal.push(next);
+                }
+            }
+
+            let quote = if normalization.quote_style && c != '`' { '"' } else { c };
+            tokens.push(format!("{quote}{literal}{quote}"));
+        } else {
+            let len = operators
+                .iter()
+                .filter(|op| text[start..].starts_with(**op))
+                .map(|op| op.len())
+                .max()
+                .unwrap_or(c.len_utf8());
+            let end = start + len;
+            while chars.next_if(|&(idx, _)| idx < end).is_some() {}
+            tokens.push(text[start..end].to_string());
+        }
+    }
+
+    if normalization.trailing_commas {
+        tokens.remove_trailing_commas();
+    }
+
+    tokens.tokens
+}
+
+/// Tokens read so far, with what is needed to place line break tokens
+#[derive(Default)]
+struct TokenStream {
+    tokens: Vec<String>,
+    /// Whether a line break came before each token
+    after_break: Vec<bool>,
+    /// A line break was seen since the last token
+    pending_break: bool,
+    /// Nesting of parentheses and brackets, inside which line breaks never end a statement
+    depth: usize,
+    /// The last token other than a comment may end a statement
+    can_end_statement: bool,
+    /// Emit "\n" tokens for line breaks that can end a statement
+    significant_breaks: bool,
+}
+
+impl TokenStream {
+    fn line_break(&mut self) {
+        self.pending_break = true;
+    }
+
+    fn push(&mut self, token: String) {
+        // A line starting with "." continues a method chain
+        let continues_chain = matches!(token.as_str(), "." | "?.");
+        if self.pending_break && self.significant_breaks && self.depth == 0 && self.can_end_statement && !continues_chain {
+            self.add("\n".to_string());
+        }
+
+        match token.as_str() {
+            "(" | "[" => self.depth += 1,
+            ")" | "]" => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        // Go's rule for where a line break inserts a semicolon
+        self.can_end_statement = match token.as_str() {
+            ")" | "]" | "}" => true,
+            "++" | "--" => true,
+            _ => token.starts_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '"' | '\'' | '`')),
+        };
+        self.add(token);
+    }
+
+    /// A comment neither ends nor continues a statement
+    fn push_comment(&mut self, comment: String) {
+        let can_end_statement = self.can_end_statement;
+        self.add(comment);
+        self.can_end_statement = can_end_statement;
+    }
+
+    fn add(&mut self, token: String) {
+        self.tokens.push(token);
+        self.after_break.push(self.pending_break);
+        self.pending_break = false;
+    }
+
+    /// Drop commas directly before a closing bracket on a later line, as formatters add to
+    /// lists split over several lines. On one line the comma can matter, as in Rust's `(x,)`.
+    fn remove_trailing_commas(&mut self) {
+        let mut idx = 0;
+        while idx + 1 < self.tokens.len() {
+            if self.tokens[idx] == ","
+                && self.after_break[idx + 1]
+                && matches!(self.tokens[idx + 1].as_str(), ")" | "]" | "}")
+            {
+                self.tokens.remove(idx);
+                self.after_break.remove(idx);
+            } else {
+                idx += 1;
+            }
+        }
+    }
+}
+
+/// Operators of more than one character
+fn operators(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            "<<=", ">>=", "..=", "...", "==", "!=", "<=", ">=", "&&", "||", "<<", ">>", "+=", "-=", "*=", "/=",
+            "%=", "&=", "|=", "^=", "->", "=>", "::", "..",
+        ],
+        Language::JavaScript | Language::TypeScript => &[
+            ">>>=", "**=", "&&=", "||=", "??=", "<<=", ">>=", "===", "!==", ">>>", "...", "==", "!=", "<=", ">=",
+            "&&", "||", "??", "?.", "**", "<<", ">>", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "++", "--",
+            "=>",
+        ],
+        Language::Go => &[
+            "<<=", ">>=", "&^=", "...", "==", "!=", "<=", ">=", "&&", "||", "<<", ">>", "&^", "+=", "-=", "*=",
+            "/=", "%=", "&=", "|=", "^=", "++", "--", ":=", "<-",
+        ],
+        Language::Python => &[
+            "**=", "//=", "<<=", ">>=", "==", "!=", "<=", ">=", "**", "//", "<<", ">>", "+=", "-=", "*=", "/=",
+            "%=", "&=", "|=", "^=", "@=", "->", ":=",
+        ],
+        Language::Ruby => &[
+            "**=", "<<=", ">>=", "&&=", "||=", "<=>", "===", "...", "==", "!=", "<=", ">=", "&&", "||", "**",
+            "<<", ">>", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "=~", "!~", "=>", "->", "::", "..", "&.",
+        ],
+        Language::Shell => &["&&", "||", ";;", "<<", ">>", "<<<", "&>", "|&"],
+        _ => &[
+            ">>>=", "<<=", ">>=", "===", "!==", ">>>", "...", "..<", "==", "!=", "<=", ">=", "&&", "||", "??",
+            "?.", "?:", "!!", "<<", ">>", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "++", "--", "->", "=>",
+            "::", "..",
+        ],
+    }
+}
+
+/// Whether the text after a quote reads as a lifetime name rather than a char literal
+fn is_lifetime(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    let first = chars.next();
+    let second = chars.next();
+    first.is_some_and(|c| c.is_alphabetic() || c == '_') && second != Some('\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(language: Language, a: &str, b: &str) -> bool {
+        let normalization = FormattingNormalization::default();
+        tokenize(a, language, normalization) == tokenize(b, language, normalization)
+    }
+
+    #[test]
+    fn operators_keep_their_boundaries() {
+        for (a, b) in [("i++ + j", "i + ++j"), ("a & &b", "a && b"), ("x - -1", "x -- 1")] {
+            assert!(!same(Language::JavaScript, a, b), "{a:?} and {b:?} read the same");
+            assert!(!same(Language::Cpp, a, b), "{a:?} and {b:?} read the same");
+        }
+        assert!(!same(Language::Rust, "a & &b", "a && b"));
+        assert!(!same(Language::Go, "x := <-ch", "x : = < - ch"));
+    }
+
+    #[test]
+    fn spacing_around_operators_is_formatting() {
+        assert!(same(Language::JavaScript, "a&&b", "a && b"));
+        assert!(same(Language::Rust, "let x=a>>2;", "let x = a >> 2;"));
+        assert!(same(Language::Python, "x//=2", "x //= 2"));
+    }
+}
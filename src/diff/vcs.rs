@@ -0,0 +1,147 @@
+use crate::config::{DiffConfig, VcsBackend};
+use crate::diff::git::{CommitInfo, GitOperations};
+use crate::error::CraiResult;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Repository access needed to produce and describe a diff. Diffs are returned as
+/// `git diff` text (with C-quoted paths) so every backend shares one parser.
+#[async_trait]
+pub trait Vcs: Send + Sync {
+    /// Root of the working tree
+    fn repo_path(&self) -> &Path;
+
+    async fn verify_repository(&self) -> CraiResult<()>;
+
+    /// Fails with `BranchNotFound` unless `rev` resolves to an object
+    async fn verify_branch(&self, rev: &str) -> CraiResult<()>;
+
+    /// Short name of the branch HEAD is on; `None` when HEAD is detached
+    async fn current_branch(&self) -> CraiResult<Option<String>>;
+
+    /// Diff of two revisions, with renames and copies detected
+    async fn get_unified_diff(
+        &self,
+        base: &str,
+        compare: &str,
+        context_lines: u32,
+        ignore_whitespace: bool,
+    ) -> CraiResult<String>;
+
+    /// Working tree against HEAD, tracked files only
+    async fn get_unstaged_unified_diff(&self, context_lines: u32, ignore_whitespace: bool) -> CraiResult<String>;
+
+    /// Index against HEAD
+    async fn get_staged_unified_diff(&self, context_lines: u32, ignore_whitespace: bool) -> CraiResult<String>;
+
+    /// Best common ancestor of two revisions, the base a pull request is diffed against
+    async fn get_merge_base(&self, base: &str, compare: &str) -> CraiResult<String>;
+
+    /// The revision to diff a commit against: its first parent, or the empty tree for a root commit
+    async fn get_parent(&self, rev: &str) -> CraiResult<String>;
+
+    /// Untracked files that are not ignored, relative to the repository root
+    async fn get_untracked_files(&self) -> CraiResult<Vec<PathBuf>>;
+
+    /// Contents of a file at a revision; an empty revision reads the index.
+    /// `None` when the file does not exist there.
+    async fn get_file_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<String>>;
+
+    /// Contents of a file as staged in the index
+    async fn get_file_in_index(&self, file_path: &Path) -> CraiResult<Option<String>> {
+        self.get_file_at_ref("", file_path).await
+    }
+
+    /// Several files, each at a revision as for `get_file_at_ref`, in the order asked for.
+    /// Files larger than `max_size` bytes are measured but not read.
+    async fn get_files_at_refs(&self, files: &[(String, PathBuf)], max_size: u64) -> CraiResult<Vec<FileAtRef>> {
+        let mut result = Vec::with_capacity(files.len());
+        for (git_ref, path) in files {
+            result.push(file_at_ref(self, git_ref, path, max_size).await?);
+        }
+        Ok(result)
+    }
+
+    /// Size in bytes of a blob, `None` if it does not exist
+    async fn get_object_size(&self, blob: &str) -> CraiResult<Option<u64>>;
+
+    /// Size in bytes of a file at a revision; an empty revision reads the index
+    async fn get_file_size_at_ref(&self, git_ref: &str, file_path: &Path) -> CraiResult<Option<u64>>;
+
+    /// One-line summaries of the commits a submodule bump pulls in, newest first.
+    /// Returns an empty list when the submodule is not checked out or lacks the commits.
+    async fn get_submodule_commits(
+        &self,
+        submodule_path: &Path,
+        old_commit: &str,
+        new_commit: &str,
+    ) -> CraiResult<Vec<String>>;
+
+    /// Commits reachable from `compare` but not `base`, oldest first
+    async fn list_commits(&self, base: &str, compare: &str) -> CraiResult<Vec<CommitInfo>>;
+
+    /// Details of a single commit
+    async fn get_commit(&self, rev: &str) -> CraiResult<CommitInfo>;
+
+    /// Subjects of the commits a diff from `base` to `compare` is made of, newest first.
+    /// Pass the same base the diff used (the merge base in three-dot mode).
+    async fn get_commit_messages(&self, base: &str, compare: &str) -> CraiResult<Vec<String>>;
+}
+
+/// A file looked up at a revision by `Vcs::get_files_at_refs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileAtRef {
+    Missing,
+    /// Larger than the size limit, so left unread
+    Oversized(u64),
+    Content(String),
+}
+
+/// One file at a revision, read only when it is within `max_size` bytes
+pub(crate) async fn file_at_ref<V: Vcs + ?Sized>(
+    vcs: &V,
+    git_ref: &str,
+    path: &Path,
+    max_size: u64,
+) -> CraiResult<FileAtRef> {
+    Ok(match vcs.get_file_size_at_ref(git_ref, path).await? {
+        None => FileAtRef::Missing,
+        Some(size) if size > max_size => FileAtRef::Oversized(size),
+        Some(_) => vcs
+            .get_file_at_ref(git_ref, path)
+            .await?
+            .map_or(FileAtRef::Missing, FileAtRef::Content),
+    })
+}
+
+/// Factory for the configured repository backend
+pub struct VcsFactory;
+
+impl VcsFactory {
+    /// Open the repository at `repo_path` with the backend selected by `diff.vcs`
+    pub fn create(config: &DiffConfig, repo_path: &Path) -> CraiResult<Arc<dyn Vcs>> {
+        match config.vcs {
+            VcsBackend::Cli => Ok(Arc::new(GitOperations::new(repo_path.to_path_buf()))),
+            #[cfg(feature = "gitoxide")]
+            VcsBackend::Gitoxide => Ok(Arc::new(crate::diff::gitoxide::Gitoxide::open(repo_path)?)),
+            #[cfg(not(feature = "gitoxide"))]
+            VcsBackend::Gitoxide => Err(crate::error::CraiError::Config(
+                "diff.vcs = \"gitoxide\" needs crai built with the gitoxide feature".to_string(),
+            )),
+        }
+    }
+}
+
+/// Turn raw path bytes from git into a path without losing non-UTF-8 names
+pub(crate) fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        PathBuf::from(std::ffi::OsString::from_vec(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
@@ -1,16 +1,30 @@
-use clap::{Parser, Subcommand};
-use crai::ai::provider::{AiProviderFactory, ScoringContext, SummaryContext};
-use crai::ai::scoring::{ScoringOrchestrator, ScoringUpdate};
-use crai::config::{self, AiProviderType, Config};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use crai::ai::provider::{AiProvider, AiProviderFactory, ScoringContext, SummaryContext};
+use crai::ai::schema::SummaryResponse;
+use crai::ai::scoring::{ScoringOrchestrator, ScoringResult, ScoringUpdate};
+use crai::config::{
+    self, AiProviderType, Config, ConfigLoader, ConfigOrigin, DiffBackend, DiffMode, LoadedConfig, ResumeSession,
+    VcsBackend,
+};
+use crai::diff::category::untested_source;
 use crai::diff::filter::ChunkFilter;
-use crai::diff::git::GitOperations;
+use crai::diff::git::CommitInfo;
+use crai::diff::interdiff::Interdiff;
+use crai::diff::DiffResult;
 use crai::diff::parser::DiffParser;
-use crai::error::CraiResult;
-use crai::tui::event::{Action, Event, EventHandler};
+use crai::diff::patch::parse_patch;
+use crai::diff::vcs::{Vcs, VcsFactory};
+use crai::error::{CraiError, CraiResult};
+use crai::tui::event::{Event, EventHandler};
 use crai::tui::layout::LayoutManager;
+use crai::review::store::SavedSession;
+use crai::review::SessionStore;
+use crai::tui::app::{CommitStep, MessageLevel};
+use crai::tui::views::notes::format_age;
 use crai::tui::{self, App};
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -26,6 +40,10 @@ struct Cli {
     #[arg(short, long)]
     config: Option<PathBuf>,
 
+    /// Apply the [profiles.<NAME>] table from the config (overrides general.default_profiles)
+    #[arg(short, long, value_name = "NAME")]
+    profile: Option<String>,
+
     /// Base branch to compare against
     #[arg(short, long)]
     base: Option<String>,
@@ -34,6 +52,10 @@ struct Cli {
     #[arg(short = 'C', long)]
     compare: Option<String>,
 
+    /// Diff the branch tips directly (base..compare) instead of from their merge base
+    #[arg(long)]
+    no_merge_base: bool,
+
     /// Compare staged changes against HEAD
     #[arg(long, conflicts_with_all = ["base", "compare", "unstaged"])]
     staged: bool,
@@ -42,6 +64,31 @@ struct Cli {
     #[arg(long, conflicts_with_all = ["base", "compare", "staged"])]
     unstaged: bool,
 
+    /// Review a single commit against its parent
+    #[arg(long, value_name = "SHA", conflicts_with_all = ["base", "compare", "staged", "unstaged", "range"])]
+    commit: Option<String>,
+
+    /// Review a commit range: a..b diffs the tips, a...b diffs from the merge base
+    #[arg(long, value_name = "A..B", conflicts_with_all = ["base", "compare", "staged", "unstaged"])]
+    range: Option<String>,
+
+    /// With --range, review each commit separately and step between them
+    #[arg(long, requires = "range")]
+    per_commit: bool,
+
+    /// Branch tip reviewed previously; only hunks that are new or changed since then are
+    /// reviewed (e.g. after a force-push)
+    #[arg(long, value_name = "OLD-TIP", conflicts_with_all = ["staged", "unstaged", "commit", "range"])]
+    since: Option<String>,
+
+    /// Include untracked files in unstaged reviews (overrides diff.include_untracked)
+    #[arg(long, conflicts_with_all = ["no_untracked", "staged"])]
+    untracked: bool,
+
+    /// Exclude untracked files from unstaged reviews (overrides diff.include_untracked)
+    #[arg(long, conflicts_with = "staged")]
+    no_untracked: bool,
+
     /// Repository path
     #[arg(short, long, default_value = ".")]
     repo: PathBuf,
@@ -67,8 +114,33 @@ enum Commands {
     /// Check configuration and dependencies
     Doctor,
 
+    /// Inspect the effective configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
     /// Show summary only (non-interactive)
     Summary,
+
+    /// Review interactively; the same as running without a subcommand unless a patch is given
+    Review {
+        /// Review a unified diff, `git format-patch` mbox or `git show` output instead of
+        /// the repository ("-" reads standard input)
+        #[arg(long, value_name = "FILE|-")]
+        patch: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print every effective config value
+    Show {
+        /// Note where each value came from: default, user or repository config, environment
+        /// variable or flag
+        #[arg(long)]
+        origin: bool,
+    },
 }
 
 #[tokio::main]
@@ -81,7 +153,9 @@ async fn main() -> CraiResult<()> {
         )
         .init();
 
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let repo_flag = matches.value_source("repo") == Some(ValueSource::CommandLine);
 
     // Handle Init command before loading config
     if let Some(Commands::Init { output }) = &cli.command {
@@ -99,36 +173,93 @@ async fn main() -> CraiResult<()> {
         return Ok(());
     }
 
-    // Determine config path: CLI arg > user config dir > default
+    // User config: CLI arg > user config dir
     let config_path = cli.config.clone()
         .or_else(config::default_config_path);
 
-    // Load config or run first-time setup
-    let config = match &config_path {
-        Some(path) if path.exists() => config::load_config(path)?,
-        Some(path) => {
-            // Config path specified but doesn't exist - run setup
+    // First run: set up a user config, unless the repository brings its own
+    let has_repository_config = config::find_repository_config(&cli.repo).is_some();
+    match &config_path {
+        Some(path) if path.exists() || has_repository_config => {}
+        Some(path) if !matches!(cli.command, Some(Commands::Config { .. })) => {
             println!("Welcome to CRAI - AI-powered Code Review\n");
             println!("No configuration found. Let's set up your config.\n");
 
             let provider = prompt_for_provider()?;
             config::create_config_with_provider(path, provider)?;
             println!("\nConfig created at {}\n", path.display());
+        }
+        Some(_) => {}
+        None => eprintln!("Warning: Could not determine config directory, using defaults"),
+    }
 
-            config::load_config(path)?
+    // Defaults < user config < repository config < CRAI_* environment < flags
+    let mut loader = ConfigLoader::new()
+        .with_user_file(config_path)
+        .with_repository(repo_flag.then(|| cli.repo.clone()))
+        .with_profile(cli.profile.clone())
+        .with_mode(diff_mode(&cli))
+        .with_env(std::env::vars());
+    if cli.no_merge_base {
+        loader = loader.with_cli_override("diff.merge_base", false, "--no-merge-base");
+    }
+    if cli.untracked {
+        loader = loader.with_cli_override("diff.include_untracked", true, "--untracked");
+    } else if cli.no_untracked {
+        loader = loader.with_cli_override("diff.include_untracked", false, "--no-untracked");
+    }
+    let loaded = loader.load()?;
+    let config = loaded.config.clone();
+    cli.repo = config.general.repository_path.clone();
+    cli.profile = loaded.profile.clone();
+
+    // Doctor and config show list the findings themselves, and still run with errors
+    // so the config can be inspected
+    let inspecting = matches!(cli.command, Some(Commands::Doctor | Commands::Config { .. }));
+    if !inspecting {
+        for issue in &loaded.issues {
+            eprintln!("{}", issue);
         }
-        None => {
-            // No config path available - use defaults
-            eprintln!("Warning: Could not determine config directory, using defaults");
-            Config::default()
+        let errors = loaded.issues.iter().filter(|i| i.is_error()).count();
+        if errors > 0 {
+            return Err(CraiError::Config(format!(
+                "{} invalid value(s); run `crai doctor` for details",
+                errors
+            )));
         }
-    };
+    }
 
     match cli.command {
         Some(Commands::Init { .. }) => unreachable!(), // Already handled above
-        Some(Commands::Doctor) => run_doctor(&config).await,
+        Some(Commands::Doctor) => run_doctor(&cli, &loaded).await,
+        Some(Commands::Config {
+            command: ConfigCommand::Show { origin },
+        }) => {
+            show_config(&loaded, origin);
+            Ok(())
+        }
         Some(Commands::Summary) => run_summary(&cli, &config).await,
-        None => run_interactive(&cli, &config).await,
+        Some(Commands::Review { patch: Some(ref patch) }) => {
+            run_patch_review(&cli, &config, patch).await
+        }
+        Some(Commands::Review { patch: None }) | None => run_interactive(&cli, &config).await,
+    }
+}
+
+/// The kind of review the flags select, for choosing a default profile
+fn diff_mode(cli: &Cli) -> DiffMode {
+    if matches!(cli.command, Some(Commands::Review { patch: Some(_) })) {
+        DiffMode::Patch
+    } else if cli.commit.is_some() {
+        DiffMode::Commit
+    } else if cli.range.is_some() {
+        DiffMode::Range
+    } else if cli.staged {
+        DiffMode::Staged
+    } else if cli.unstaged || (cli.base.is_none() && cli.compare.is_none() && cli.since.is_none()) {
+        DiffMode::Unstaged
+    } else {
+        DiffMode::Branches
     }
 }
 
@@ -165,7 +296,22 @@ fn prompt_for_provider() -> CraiResult<AiProviderType> {
     Ok(provider)
 }
 
-async fn run_doctor(config: &Config) -> CraiResult<()> {
+/// Print the effective config as dotted `key = value` lines, and validation findings on stderr
+fn show_config(loaded: &LoadedConfig, with_origin: bool) {
+    for (key, value, origin) in loaded.entries() {
+        if with_origin {
+            println!("{} = {}  # {}", key, value, origin);
+        } else {
+            println!("{} = {}", key, value);
+        }
+    }
+    for issue in &loaded.issues {
+        eprintln!("{}", issue);
+    }
+}
+
+async fn run_doctor(cli: &Cli, loaded: &LoadedConfig) -> CraiResult<()> {
+    let config = &loaded.config;
     println!("CRAI - Code Review AI - Dependency Check\n");
 
     // Check git
@@ -178,8 +324,12 @@ async fn run_doctor(config: &Config) -> CraiResult<()> {
         Ok(output) if output.status.success() => {
             println!("OK ({})", String::from_utf8_lossy(&output.stdout).trim());
         }
+        _ if config.diff.vcs == VcsBackend::Gitoxide => {
+            println!("NOT FOUND (not needed, diff.vcs is gitoxide)")
+        }
         _ => println!("NOT FOUND"),
     }
+    println!("  Git backend: {:?}", config.diff.vcs);
 
     // Check difft
     print!("difftastic: ");
@@ -191,12 +341,15 @@ async fn run_doctor(config: &Config) -> CraiResult<()> {
         Ok(output) if output.status.success() => {
             println!("OK ({})", String::from_utf8_lossy(&output.stdout).trim());
         }
+        _ if config.diff.backend == DiffBackend::Difftastic => {
+            println!("NOT FOUND (diff.backend is difftastic; git diff will be used instead)")
+        }
         _ => println!("NOT FOUND (optional, for semantic diffs)"),
     }
 
     // Check AI provider
     print!("AI provider ({:?}): ", config.ai.provider);
-    match AiProviderFactory::create(&config.ai) {
+    match AiProviderFactory::create(&config.ai, &cli.repo) {
         Ok(provider) => match provider.health_check().await {
             Ok(health) if health.is_available => {
                 println!(
@@ -211,35 +364,210 @@ async fn run_doctor(config: &Config) -> CraiResult<()> {
     }
 
     println!("\nConfiguration:");
-    let config_path = config::default_config_path();
-    let config_status = match &config_path {
-        Some(p) if p.exists() => format!("Found at {}", p.display()),
-        Some(p) => format!("Not found (expected at {})", p.display()),
-        None => "Could not determine config directory".to_string(),
-    };
-    println!("  Config file: {}", config_status);
+    if loaded.files.is_empty() {
+        let expected = cli.config.clone().or_else(config::default_config_path);
+        match expected {
+            Some(p) => println!("  Config files: none, using defaults (user config expected at {})", p.display()),
+            None => println!("  Config files: none, using defaults"),
+        }
+    } else {
+        println!("  Config files:");
+        for file in &loaded.files {
+            println!("    {}", file);
+        }
+    }
+    let overrides: Vec<String> = loaded
+        .origins
+        .iter()
+        .filter(|(_, origin)| matches!(origin, ConfigOrigin::Environment(_) | ConfigOrigin::Cli(_)))
+        .map(|(key, origin)| format!("{} ({})", key, origin))
+        .collect();
+    if !overrides.is_empty() {
+        println!("  Overrides: {}", overrides.join(", "));
+    }
+    match &loaded.profile {
+        Some(profile) => println!("  Profile: {}", profile),
+        None => println!("  Profile: none"),
+    }
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        println!("  Available profiles: {}", names.join(", "));
+    }
     println!("  AI provider: {:?}", config.ai.provider);
+    println!("  Diff backend: {:?}", config.diff.backend);
     println!("  Controversiality threshold: {}", config.filters.controversiality_threshold);
     println!("  Concurrent AI requests: {}", config.ai.concurrent_requests);
 
+    let sandbox = &config.ai.sandbox;
+    let tools = if sandbox.allow_repository_read || !sandbox.allowed_tools.is_empty() {
+        let mut tools = sandbox.allowed_tools.clone();
+        if sandbox.allow_repository_read {
+            tools.push("repository read".to_string());
+        }
+        format!("{} (confined to the working directory and repository)", tools.join(", "))
+    } else {
+        "none".to_string()
+    };
+    if config.ai.provider == AiProviderType::Kiro {
+        println!("  AI tool access: not sandboxed (kiro-cli applies its own tool permissions)");
+    } else {
+        println!("  AI tool access: {}", tools);
+    }
+    println!(
+        "  AI working directory: {}",
+        match (&sandbox.working_directory, sandbox.allow_repository_read) {
+            (Some(dir), _) => dir.display().to_string(),
+            (None, true) => format!("{} (repository)", cli.repo.display()),
+            (None, false) => "empty temporary directory".to_string(),
+        }
+    );
+
+    println!("\nValidation:");
+    if loaded.issues.is_empty() {
+        println!("  OK");
+    }
+    for issue in &loaded.issues {
+        println!("  {}", issue);
+    }
+
     Ok(())
 }
 
-async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
-    let git = GitOperations::new(cli.repo.clone());
-    git.verify_repository().await?;
+/// A diff to review, with its commit when reviewing commit by commit
+struct ReviewTarget {
+    commit: Option<CommitInfo>,
+    diff_result: DiffResult,
+    /// Subjects of the commits that make up the diff, for branch and range comparisons
+    commit_messages: Vec<String>,
+    /// The diff as reviewed at the `--since` revision
+    previous: Option<DiffResult>,
+}
+
+impl ReviewTarget {
+    fn new(diff_result: DiffResult) -> Self {
+        Self {
+            commit: None,
+            diff_result,
+            commit_messages: Vec::new(),
+            previous: None,
+        }
+    }
 
-    let parser = DiffParser::new(cli.repo.clone(), config.diff.context_lines);
+    fn commit_messages(&self) -> Vec<String> {
+        match &self.commit {
+            Some(commit) => vec![commit.message.clone()],
+            None => self.commit_messages.clone(),
+        }
+    }
 
-    let diff_result = if cli.staged {
-        let result = parser.parse_staged().await?;
-        println!("CRAI Summary: HEAD -> (staged)\n");
-        result
-    } else if cli.unstaged || (cli.base.is_none() && cli.compare.is_none()) {
+    fn scoring_context(&self) -> ScoringContext {
+        ScoringContext {
+            commit_messages: self.commit_messages(),
+            authors: self
+                .commit
+                .iter()
+                .filter(|c| !c.author.is_empty())
+                .map(|c| c.author.clone())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn summary_context(&self) -> SummaryContext {
+        SummaryContext {
+            commit_messages: self.commit_messages(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Diff two revisions, from their merge base when `merge_base` is set, along with the
+/// subjects of the commits the diff covers
+async fn compare_revisions(
+    vcs: &dyn Vcs,
+    parser: &DiffParser,
+    base: &str,
+    compare: &str,
+    merge_base: bool,
+) -> CraiResult<ReviewTarget> {
+    vcs.verify_branch(base).await?;
+    vcs.verify_branch(compare).await?;
+
+    let diff_result = if merge_base {
+        parser.parse_merge_base(base, compare).await?
+    } else {
+        parser.parse_branches(base, compare).await?
+    };
+
+    // Log from the same base the diff used so messages and changes line up
+    let log_base = diff_result.merge_base.as_deref().unwrap_or(base);
+    let commit_messages = vcs.get_commit_messages(log_base, compare).await?;
+
+    Ok(ReviewTarget {
+        commit: None,
+        diff_result,
+        commit_messages,
+        previous: None,
+    })
+}
+
+/// Resolve the diff selection flags into the diffs to review
+async fn load_review_targets(
+    cli: &Cli,
+    config: &Config,
+    vcs: &dyn Vcs,
+    parser: &DiffParser,
+) -> CraiResult<Vec<ReviewTarget>> {
+    if let Some(commit) = &cli.commit {
+        vcs.verify_branch(commit).await?;
+        let info = vcs.get_commit(commit).await?;
+        let diff_result = parser.parse_commit(&info.sha).await?;
+        return Ok(vec![ReviewTarget {
+            commit: Some(info),
+            ..ReviewTarget::new(diff_result)
+        }]);
+    }
+
+    if let Some(range) = &cli.range {
+        // Same meaning as git: "a...b" diffs from the merge base, "a..b" diffs the tips
+        let (base, compare, merge_base) = if let Some((base, compare)) = range.split_once("...") {
+            (base, compare, true)
+        } else if let Some((base, compare)) = range.split_once("..") {
+            (base, compare, false)
+        } else {
+            return Err(CraiError::Config(format!(
+                "Invalid range '{}', expected <base>..<compare> or <base>...<compare>",
+                range
+            )));
+        };
+        let compare = if compare.is_empty() { "HEAD" } else { compare };
+
+        if !cli.per_commit {
+            let target = compare_revisions(vcs, parser, base, compare, merge_base).await?;
+            return Ok(vec![target]);
+        }
+
+        vcs.verify_branch(base).await?;
+        vcs.verify_branch(compare).await?;
+
+        let mut targets = Vec::new();
+        for info in vcs.list_commits(base, compare).await? {
+            let diff_result = parser.parse_commit(&info.sha).await?;
+            targets.push(ReviewTarget {
+                commit: Some(info),
+                ..ReviewTarget::new(diff_result)
+            });
+        }
+        return Ok(targets);
+    }
+
+    let target = if cli.staged {
+        ReviewTarget::new(parser.parse_staged().await?)
+    } else if cli.unstaged
+        || (cli.base.is_none() && cli.compare.is_none() && cli.since.is_none())
+    {
         // Default to unstaged if no flags or branches specified
-        let result = parser.parse_unstaged().await?;
-        println!("CRAI Summary: HEAD -> (working directory)\n");
-        result
+        ReviewTarget::new(parser.parse_unstaged().await?)
     } else {
         let base = cli
             .base
@@ -247,21 +575,149 @@ async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
             .unwrap_or(&config.general.default_base_branch);
         let compare = cli.compare.as_deref().unwrap_or("HEAD");
 
-        git.verify_branch(base).await?;
-        git.verify_branch(compare).await?;
+        let mut target =
+            compare_revisions(vcs, parser, base, compare, config.diff.merge_base).await?;
+
+        if let Some(since) = &cli.since {
+            // The branch as it was reviewed, diffed the same way as the new tip
+            let previous = compare_revisions(vcs, parser, base, since, config.diff.merge_base).await?;
+            target.diff_result.interdiff = Some(Interdiff::compute(
+                since,
+                &previous.diff_result,
+                &target.diff_result,
+            ));
+            target.previous = Some(previous.diff_result);
+        }
 
-        let result = parser.parse_branches(base, compare).await?;
-        println!("CRAI Summary: {} -> {}\n", base, compare);
-        result
+        target
     };
+
+    Ok(vec![target])
+}
+
+async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
+    let vcs = VcsFactory::create(&config.diff, &cli.repo)?;
+    vcs.verify_repository().await?;
+
+    let parser = DiffParser::with_vcs(Arc::clone(&vcs), &config.diff);
+
+    let targets = load_review_targets(cli, config, vcs.as_ref(), &parser).await?;
+    if targets.is_empty() {
+        println!("No commits to review");
+    }
+
+    for (idx, target) in targets.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        match &target.commit {
+            Some(commit) => println!(
+                "CRAI Summary: commit {} {} ({})\n",
+                commit.short_sha(),
+                commit.summary,
+                commit.author
+            ),
+            None => println!(
+                "CRAI Summary: {} -> {}\n",
+                target.diff_result.base_label(),
+                target.diff_result.compare_branch
+            ),
+        }
+        print_summary(cli, config, target).await?;
+    }
+
+    Ok(())
+}
+
+async fn print_summary(cli: &Cli, config: &Config, target: &ReviewTarget) -> CraiResult<()> {
+    let diff_result = &target.diff_result;
     println!("Files changed: {}", diff_result.files.len());
 
     let total_chunks: usize = diff_result.files.iter().map(|f| f.chunks.len()).sum();
     println!("Total chunks: {}", total_chunks);
 
+    if let Some(interdiff) = &diff_result.interdiff {
+        println!(
+            "Since {}: {} new, {} changed, {} unchanged, {} disappeared",
+            interdiff.since,
+            interdiff.new_count(),
+            interdiff.changed_count(),
+            interdiff.unchanged_count(),
+            interdiff.removed.len()
+        );
+        for hunk in &interdiff.removed {
+            println!(
+                "  disappeared: {}:{} {}",
+                hunk.path.display(),
+                hunk.chunk.new_range.start,
+                hunk.chunk.header
+            );
+        }
+    }
+
+    // Files per owner, in order of first appearance
+    let mut owners: Vec<(&str, usize)> = Vec::new();
+    for owner in diff_result.files.iter().flat_map(|f| &f.owners) {
+        match owners.iter_mut().find(|(o, _)| o == owner) {
+            Some((_, count)) => *count += 1,
+            None => owners.push((owner, 1)),
+        }
+    }
+    if !owners.is_empty() {
+        println!(
+            "Owners: {}",
+            owners
+                .iter()
+                .map(|(owner, count)| format!("{} ({})", owner, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let oversized: Vec<_> = diff_result.files.iter().filter(|f| f.oversized.is_some()).collect();
+    if !oversized.is_empty() {
+        println!(
+            "Skipped (over {} bytes): {}",
+            config.diff.max_file_size_bytes,
+            oversized
+                .iter()
+                .map(|f| f.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    if let Some(deps) = &diff_result.dependencies {
+        println!("Dependencies: {}", deps.describe());
+        for change in deps.flagged() {
+            println!(
+                "  {} [{}] in {}",
+                change.describe(),
+                change.flags(),
+                change.manifest.display()
+            );
+        }
+        for error in &deps.errors {
+            println!("  {}", error.describe());
+        }
+    }
+
+    let untested = untested_source(&diff_result.files);
+    if !untested.is_empty() {
+        println!(
+            "Concern: source changed but no tests changed: {}",
+            untested
+                .iter()
+                .map(|f| f.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
     if !cli.no_ai {
-        let provider = AiProviderFactory::create(&config.ai)?;
-        let filter = ChunkFilter::new(config.filters.clone())?;
+        let provider = AiProviderFactory::create(&config.ai, &cli.repo)?;
+        let filter = ChunkFilter::new(config.filters.clone())?
+            .with_ignore_comments(config.diff.ignore_comments);
         let orchestrator = ScoringOrchestrator::new(
             provider.clone(),
             filter,
@@ -271,7 +727,7 @@ async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
         println!("\nRunning AI analysis...");
 
         let result = orchestrator
-            .score_all(&diff_result.files, &ScoringContext::default(), |update: ScoringUpdate| {
+            .score_all(diff_result, &target.scoring_context(), |update: ScoringUpdate| {
                 eprint!("\rScoring: {}/{}", update.progress.completed, update.progress.total);
             })
             .await?;
@@ -305,11 +761,17 @@ async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
             for score in high_scores.iter().take(10) {
                 if let Some(resp) = &score.response {
                     let file = &diff_result.files[score.file_index];
+                    let owners = if file.owners.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", file.owners.join(" "))
+                    };
                     println!(
-                        "  [{:.0}%] {} - {} - {}",
+                        "  [{:.0}%] {} - {}{} - {}",
                         resp.score * 100.0,
                         resp.classification,
                         file.path.display(),
+                        owners,
                         resp.reasoning.chars().take(60).collect::<String>()
                     );
                 }
@@ -320,46 +782,206 @@ async fn run_summary(cli: &Cli, config: &Config) -> CraiResult<()> {
     Ok(())
 }
 
-async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
-    let git = GitOperations::new(cli.repo.clone());
-    git.verify_repository().await?;
+/// Score a diff and summarise it, printing progress to the terminal.
+/// Returns `None` if the user cancelled with Ctrl+C.
+async fn analyze_target(
+    orchestrator: &ScoringOrchestrator,
+    provider: &Arc<dyn AiProvider>,
+    target: &ReviewTarget,
+    cancelled: &Arc<AtomicBool>,
+) -> CraiResult<Option<(Option<ScoringResult>, Option<SummaryResponse>)>> {
+    use std::io::Write;
+
+    let files = &target.diff_result.files;
+    let total_chunks: usize = files.iter().map(|f| f.chunks.len()).sum();
+
+    println!("Analyzing {} files with {} chunks... (Ctrl+C to cancel)", files.len(), total_chunks);
+    print!("  Applying heuristic filters... ");
+    let _ = std::io::stdout().flush();
+
+    // Run scoring with real-time progress and findings display
+    let mut first_progress = true;
+    let mut highlights_found = 0usize;
+    let cancelled_check = cancelled.clone();
+    let result = orchestrator
+        .score_all(&target.diff_result, &target.scoring_context(), |update: ScoringUpdate| {
+            // Check if cancelled
+            if cancelled_check.load(Ordering::SeqCst) {
+                return;
+            }
+
+            if first_progress {
+                println!("done");
+                println!("  AI scoring chunks...\n");
+                first_progress = false;
+            }
 
-    let parser = DiffParser::new(cli.repo.clone(), config.diff.context_lines);
+            let progress = &update.progress;
+
+            // Show finding details if we have one
+            if let Some(finding) = &update.finding {
+                // Clear the progress line and show finding
+                let status = if finding.is_filtered {
+                    "\x1b[90m[filtered]\x1b[0m"
+                } else {
+                    highlights_found += 1;
+                    match finding.score {
+                        s if s >= 0.7 => "\x1b[91m[review]\x1b[0m  ",
+                        s if s >= 0.5 => "\x1b[93m[notable]\x1b[0m ",
+                        _ => "\x1b[92m[routine]\x1b[0m ",
+                    }
+                };
+
+                // Truncate reasoning to fit on one line (char-aware for UTF-8)
+                let reasoning = finding.reasoning.replace('\n', " ");
+                let max_reason_chars = 60;
+                let truncated_reason: String = if reasoning.chars().count() > max_reason_chars {
+                    format!("{}...", reasoning.chars().take(max_reason_chars).collect::<String>())
+                } else {
+                    reasoning
+                };
+
+                // Truncate file path if too long (char-aware for UTF-8)
+                let max_path_chars = 40;
+                let file_display = if finding.file_path.chars().count() > max_path_chars {
+                    let skip = finding.file_path.chars().count() - max_path_chars + 3;
+                    format!("...{}", finding.file_path.chars().skip(skip).collect::<String>())
+                } else {
+                    finding.file_path.clone()
+                };
 
-    let diff_result = if cli.staged {
-        parser.parse_staged().await?
-    } else if cli.unstaged || (cli.base.is_none() && cli.compare.is_none()) {
-        // Default to unstaged if no flags or branches specified
-        parser.parse_unstaged().await?
+                println!(
+                    "  {} {:>3.0}% {} {}",
+                    status,
+                    finding.score * 100.0,
+                    file_display,
+                    truncated_reason
+                );
+            }
+
+            // Show progress bar
+            let bar_width = 30;
+            let filled = (progress.percentage() / 100.0 * bar_width as f64) as usize;
+            let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
+            eprint!(
+                "\r  Progress: [{}] {}/{} ({:.0}%)   ",
+                bar,
+                progress.completed,
+                progress.total,
+                progress.percentage()
+            );
+            let _ = std::io::stderr().flush();
+        })
+        .await;
+
+    // Check if user cancelled
+    if cancelled.load(Ordering::SeqCst) {
+        return Ok(None);
+    }
+
+    // Handle scoring result
+    let result = result?;
+
+    if first_progress {
+        // No AI scoring was done (all filtered)
+        println!("done (all chunks filtered)");
     } else {
-        let base = cli
-            .base
-            .as_deref()
-            .unwrap_or(&config.general.default_base_branch);
-        let compare = cli.compare.as_deref().unwrap_or("HEAD");
+        eprintln!(); // Clear progress line
+        println!("\n  Scoring complete: {} highlights found", highlights_found);
+    }
 
-        git.verify_branch(base).await?;
-        git.verify_branch(compare).await?;
+    println!(
+        "  Result: {} reviewable | {} filtered ({:.1}%)",
+        result.reviewable_count(),
+        result.stats.filtered_lines,
+        result.stats.filter_percentage()
+    );
 
-        parser.parse_branches(base, compare).await?
+    // Generate AI summary
+    print!("  Generating summary... ");
+    let _ = std::io::stdout().flush();
+
+    let summary = match provider
+        .generate_summary(files, &target.summary_context())
+        .await
+    {
+        Ok(summary) => {
+            println!("done");
+            Some(summary)
+        }
+        Err(e) => {
+            println!("failed: {}", e);
+            None
+        }
     };
 
-    if diff_result.files.is_empty() {
+    Ok(Some((Some(result), summary)))
+}
+
+async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
+    let vcs = VcsFactory::create(&config.diff, &cli.repo)?;
+    vcs.verify_repository().await?;
+
+    let parser = DiffParser::with_vcs(Arc::clone(&vcs), &config.diff);
+
+    let targets = load_review_targets(cli, config, vcs.as_ref(), &parser).await?;
+
+    if targets.is_empty() {
+        println!("No commits found in {}", cli.range.as_deref().unwrap_or("range"));
+        return Ok(());
+    }
+
+    review_targets(cli, config, targets).await
+}
+
+/// Review a patch file or standard input; the repository is not read
+async fn run_patch_review(cli: &Cli, config: &Config, patch: &Path) -> CraiResult<()> {
+    let (input, source) = if patch == Path::new("-") {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        (input, "stdin".to_string())
+    } else {
+        let input = std::fs::read(patch).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => CraiError::FileNotFound(patch.to_path_buf()),
+            _ => CraiError::Diff(format!("Failed to read patch {}: {}", patch.display(), e)),
+        })?;
+        (input, patch.display().to_string())
+    };
+
+    let targets: Vec<ReviewTarget> = parse_patch(&String::from_utf8_lossy(&input), &source)?
+        .into_iter()
+        .map(|patch| ReviewTarget {
+            commit: patch.commit,
+            ..ReviewTarget::new(patch.diff_result)
+        })
+        .collect();
+
+    if targets.is_empty() {
+        println!("No patches found in {}", source);
+        return Ok(());
+    }
+
+    review_targets(cli, config, targets).await
+}
+
+/// Analyse the targets and run the TUI over them
+async fn review_targets(cli: &Cli, config: &Config, targets: Vec<ReviewTarget>) -> CraiResult<()> {
+    if targets.iter().all(|t| t.diff_result.files.is_empty()) {
+        let first = &targets[0].diff_result;
         println!(
             "No changes found between {} and {}",
-            diff_result.base_branch, diff_result.compare_branch
+            first.base_label(),
+            first.compare_branch
         );
         return Ok(());
     }
 
-    // Create app (terminal initialized later, after AI scoring)
-    let mut app = App::new(config.clone(), diff_result);
-
     // Run AI scoring before entering TUI (show progress in terminal)
     // Terminal is NOT in raw mode here, so Ctrl+C works normally
-    if !cli.no_ai {
-        use std::io::Write;
+    let mut analyses: Vec<(Option<ScoringResult>, Option<SummaryResponse>)> =
+        targets.iter().map(|_| (None, None)).collect();
 
+    if !cli.no_ai {
         // Set up Ctrl+C handler
         let cancelled = Arc::new(AtomicBool::new(false));
         let cancelled_clone = cancelled.clone();
@@ -370,143 +992,75 @@ async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
             }
         });
 
-        let provider = AiProviderFactory::create(&config.ai)?;
-        let filter = ChunkFilter::new(config.filters.clone())?;
-
-        // Clone what we need for scoring
-        let files = app.diff_result.files.clone();
-        let total_chunks: usize = files.iter().map(|f| f.chunks.len()).sum();
-
-        println!("Analyzing {} files with {} chunks... (Ctrl+C to cancel)", files.len(), total_chunks);
-        print!("  Applying heuristic filters... ");
-        let _ = std::io::stdout().flush();
-
+        let provider = AiProviderFactory::create(&config.ai, &cli.repo)?;
+        let filter = ChunkFilter::new(config.filters.clone())?
+            .with_ignore_comments(config.diff.ignore_comments);
         let orchestrator = ScoringOrchestrator::new(
             provider.clone(),
             filter,
             config.ai.concurrent_requests,
         );
 
-        // Run scoring with real-time progress and findings display
-        let mut first_progress = true;
-        let mut highlights_found = 0usize;
-        let cancelled_check = cancelled.clone();
-        let result = orchestrator
-            .score_all(&files, &ScoringContext::default(), |update: ScoringUpdate| {
-                // Check if cancelled
-                if cancelled_check.load(Ordering::SeqCst) {
-                    return;
-                }
+        for (idx, target) in targets.iter().enumerate() {
+            if let Some(commit) = &target.commit {
+                println!(
+                    "\nCommit {}/{} {} {}",
+                    idx + 1,
+                    targets.len(),
+                    commit.short_sha(),
+                    commit.summary
+                );
+            }
 
-                if first_progress {
-                    println!("done");
-                    println!("  AI scoring chunks...\n");
-                    first_progress = false;
+            let analysis = analyze_target(&orchestrator, &provider, target, &cancelled).await;
+            match analysis {
+                Ok(Some(analysis)) => analyses[idx] = analysis,
+                // Cancelled by the user
+                Ok(None) => {
+                    ctrlc_handler.abort();
+                    return Ok(());
                 }
-
-                let progress = &update.progress;
-
-                // Show finding details if we have one
-                if let Some(finding) = &update.finding {
-                    // Clear the progress line and show finding
-                    let status = if finding.is_filtered {
-                        "\x1b[90m[filtered]\x1b[0m"
-                    } else {
-                        highlights_found += 1;
-                        match finding.score {
-                            s if s >= 0.7 => "\x1b[91m[review]\x1b[0m  ",
-                            s if s >= 0.5 => "\x1b[93m[notable]\x1b[0m ",
-                            _ => "\x1b[92m[routine]\x1b[0m ",
-                        }
-                    };
-
-                    // Truncate reasoning to fit on one line (char-aware for UTF-8)
-                    let reasoning = finding.reasoning.replace('\n', " ");
-                    let max_reason_chars = 60;
-                    let truncated_reason: String = if reasoning.chars().count() > max_reason_chars {
-                        format!("{}...", reasoning.chars().take(max_reason_chars).collect::<String>())
-                    } else {
-                        reasoning
-                    };
-
-                    // Truncate file path if too long (char-aware for UTF-8)
-                    let max_path_chars = 40;
-                    let file_display = if finding.file_path.chars().count() > max_path_chars {
-                        let skip = finding.file_path.chars().count() - max_path_chars + 3;
-                        format!("...{}", finding.file_path.chars().skip(skip).collect::<String>())
-                    } else {
-                        finding.file_path.clone()
-                    };
-
-                    println!(
-                        "  {} {:>3.0}% {} {}",
-                        status,
-                        finding.score * 100.0,
-                        file_display,
-                        truncated_reason
-                    );
+                Err(e) => {
+                    ctrlc_handler.abort();
+                    return Err(e);
                 }
-
-                // Show progress bar
-                let bar_width = 30;
-                let filled = (progress.percentage() / 100.0 * bar_width as f64) as usize;
-                let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
-                eprint!(
-                    "\r  Progress: [{}] {}/{} ({:.0}%)   ",
-                    bar,
-                    progress.completed,
-                    progress.total,
-                    progress.percentage()
-                );
-                let _ = std::io::stderr().flush();
-            })
-            .await;
+            }
+        }
 
         // Cancel the Ctrl+C handler
         ctrlc_handler.abort();
+    }
 
-        // Check if user cancelled
-        if cancelled.load(Ordering::SeqCst) {
-            return Ok(());
-        }
-
-        // Handle scoring result
-        let result = result?;
+    let store = SessionStore::new(&config.general.cache_directory, &cli.repo);
 
-        if first_progress {
-            // No AI scoring was done (all filtered)
-            println!("done (all chunks filtered)");
-        } else {
-            eprintln!(); // Clear progress line
-            println!("\n  Scoring complete: {} highlights found", highlights_found);
+    // Create app; commits get their own steps so the TUI can move between them
+    let mut app = if targets.iter().any(|t| t.commit.is_some()) {
+        let steps = targets
+            .into_iter()
+            .zip(analyses)
+            .filter_map(|(target, (scoring_result, summary))| {
+                Some(CommitStep::new(target.commit?, target.diff_result, scoring_result, summary))
+            })
+            .collect();
+        App::with_commits(config.clone(), steps)
+    } else {
+        let target = targets.into_iter().next().expect("at least one review target");
+        let (scoring_result, summary) = analyses.into_iter().next().unwrap_or_default();
+        let mut app = App::new(config.clone(), target.diff_result);
+        if let Some(result) = scoring_result {
+            app.set_scoring_result(result);
         }
-
-        println!(
-            "  Result: {} reviewable | {} filtered ({:.1}%)",
-            result.reviewable_count(),
-            result.stats.filtered_lines,
-            result.stats.filter_percentage()
-        );
-
-        app.set_scoring_result(result);
-
-        // Generate AI summary
-        print!("  Generating summary... ");
-        let _ = std::io::stdout().flush();
-
-        match provider
-            .generate_summary(&files, &SummaryContext::default())
-            .await
-        {
-            Ok(summary) => {
-                println!("done");
-                app.set_summary(summary);
-            }
-            Err(e) => {
-                println!("failed: {}", e);
-            }
+        if let Some(summary) = summary {
+            app.set_summary(summary);
         }
-    }
+        if let Some(previous) = target.previous {
+            carry_over_since(&mut app, &store, &previous);
+        }
+        app
+    };
+    app.profile = cli.profile.clone();
+
+    offer_resume(&mut app, &store, config.tui.resume_session)?;
 
     // Now initialize terminal for TUI (after AI scoring completes)
     let mut terminal = tui::init_terminal()?;
@@ -524,8 +1078,10 @@ async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
         // Handle events
         match events.next()? {
             Event::Key(key) => {
-                let action = Action::from_key(key);
-                app.handle_action(action)?;
+                app.handle_key(key)?;
+            }
+            Event::Paste(text) => {
+                app.handle_paste(&text);
             }
             Event::Resize(_, _) => {
                 // Clear and force full redraw on terminal resize
@@ -536,6 +1092,15 @@ async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
             }
         }
 
+        // Leave the TUI while $EDITOR has the terminal
+        if let Some(text) = app.take_external_edit() {
+            tui::restore_terminal()?;
+            let result = tui::editor::edit_externally(&text);
+            terminal = tui::init_terminal()?;
+            terminal.clear()?;
+            app.finish_external_edit(result);
+        }
+
         if app.should_quit {
             break;
         }
@@ -544,5 +1109,72 @@ async fn run_interactive(cli: &Cli, config: &Config) -> CraiResult<()> {
     // Restore terminal
     tui::restore_terminal()?;
 
+    for session in app.sessions() {
+        if let Err(e) = store.save(session) {
+            eprintln!("Warning: failed to save the review session: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Take over the review saved for the `--since` revision for hunks that did not change
+fn carry_over_since(app: &mut App, store: &SessionStore, previous: &DiffResult) {
+    match store.load_previous(previous) {
+        Ok(Some(previous)) => app.carry_over(&previous),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: {}", e),
+    }
+}
+
+/// Pick up the sessions saved for these diffs by an earlier run, asking first unless
+/// configured otherwise
+fn offer_resume(app: &mut App, store: &SessionStore, mode: ResumeSession) -> CraiResult<()> {
+    if mode == ResumeSession::Never {
+        return Ok(());
+    }
+
+    let saved: Vec<Option<SavedSession>> = app
+        .sessions()
+        .into_iter()
+        .map(|session| {
+            store.load(session).unwrap_or_else(|e| {
+                eprintln!("Warning: {}", e);
+                None
+            })
+        })
+        .collect();
+    let Some(latest) = saved.iter().flatten().max_by_key(|s| s.saved_at) else {
+        return Ok(());
+    };
+
+    if mode == ResumeSession::Ask {
+        // Nobody to ask when the input is piped, e.g. a patch on stdin
+        if !io::stdin().is_terminal() {
+            return Ok(());
+        }
+        let marked: usize = saved.iter().flatten().map(|s| s.marked_count()).sum();
+        let notes: usize = saved.iter().flatten().map(|s| s.note_count()).sum();
+        print!(
+            "Resume the review saved {} ({} marked, {} notes)? [Y/n] ",
+            format_age(latest.saved_at),
+            marked,
+            notes
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "" | "y" | "yes") {
+            return Ok(());
+        }
+    }
+
+    let stats = app.resume(&saved);
+    let mut text = format!("Resumed review: {} chunks restored", stats.restored);
+    if stats.changed > 0 {
+        text.push_str(&format!(", {} changed since and need re-review", stats.changed));
+    }
+    app.set_status(&text, MessageLevel::Info);
     Ok(())
 }
@@ -1,5 +1,7 @@
 pub mod session;
+pub mod store;
 pub mod subagent;
 
 pub use session::ReviewSession;
+pub use store::SessionStore;
 pub use subagent::SubagentRunner;
@@ -1,9 +1,12 @@
 use crate::ai::schema::{ControversialityResponse, SubagentReviewResponse, SummaryResponse};
-use crate::ai::scoring::ScoringResult;
+use crate::ai::scoring::{ChunkScore, ScoringResult};
 use crate::diff::chunk::ChunkId;
+use crate::diff::interdiff::HunkChange;
+use crate::diff::filter::FilterStats;
 use crate::diff::DiffResult;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -11,7 +14,7 @@ pub struct ReviewSession {
     pub id: SessionId,
     pub base_branch: String,
     pub compare_branch: String,
-    pub started_at: Instant,
+    pub started_at: SystemTime,
     pub diff_result: DiffResult,
     pub scoring_result: Option<ScoringResult>,
     pub summary: Option<SummaryResponse>,
@@ -53,7 +56,7 @@ impl ReviewSession {
             id: SessionId::new(),
             base_branch: diff_result.base_branch.clone(),
             compare_branch: diff_result.compare_branch.clone(),
-            started_at: Instant::now(),
+            started_at: SystemTime::now(),
             diff_result,
             scoring_result: None,
             summary: None,
@@ -80,6 +83,89 @@ impl ReviewSession {
         self.scoring_result = Some(result);
     }
 
+    /// Take over scores, review status and notes from the review of an earlier revision for
+    /// hunks the interdiff found unchanged, each from the hunk it was paired with, giving
+    /// their entries in the scoring result the earlier score. Call after
+    /// `set_scoring_result`, which resets them.
+    pub fn carry_over(&mut self, previous: &ReviewSession) {
+        let Some(interdiff) = &self.diff_result.interdiff else {
+            return;
+        };
+
+        let previous_files: HashMap<ChunkId, usize> = previous
+            .diff_result
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_idx, file)| file.chunks.iter().map(move |chunk| (chunk.id, file_idx)))
+            .collect();
+
+        for (file_idx, file) in self.diff_result.files.iter().enumerate() {
+            for chunk in &file.chunks {
+                let HunkChange::Unchanged { old } = interdiff.change(chunk.id) else {
+                    continue;
+                };
+                let Some(state) = previous_files
+                    .get(&old)
+                    .and_then(|&old_file_idx| previous.chunk_state(old_file_idx, old))
+                else {
+                    continue;
+                };
+                self.file_states
+                    .entry(file_idx)
+                    .or_default()
+                    .chunk_states
+                    .insert(chunk.id, state.clone());
+
+                let score = self
+                    .scoring_result
+                    .as_mut()
+                    .and_then(|r| r.scores.iter_mut().find(|s| s.chunk_id == chunk.id));
+                if let Some(score) = score.filter(|s| s.response.is_none()) {
+                    score.response = state.score.clone();
+                }
+            }
+        }
+
+        for file_idx in 0..self.diff_result.files.len() {
+            self.refresh_file_status(file_idx);
+        }
+    }
+
+    /// Give scores held in chunk states, like those restored from a saved session, to the
+    /// chunks of the scoring result that have none. Without a scoring result, as with
+    /// `--no-ai`, one is built from the diff's chunks.
+    pub fn fill_scores_from_states(&mut self) {
+        let diff_result = &self.diff_result;
+        let scoring = self.scoring_result.get_or_insert_with(|| ScoringResult {
+            scores: diff_result
+                .files
+                .iter()
+                .enumerate()
+                .flat_map(|(file_index, file)| {
+                    file.chunks.iter().enumerate().map(move |(chunk_index, chunk)| ChunkScore {
+                        file_index,
+                        chunk_index,
+                        chunk_id: chunk.id,
+                        response: None,
+                        filter_result: None,
+                        integrity_flags: Vec::new(),
+                        unweighted_score: None,
+                    })
+                })
+                .collect(),
+            stats: FilterStats::default(),
+        });
+
+        for score in scoring.scores.iter_mut().filter(|s| s.response.is_none()) {
+            score.response = self
+                .file_states
+                .get(&score.file_index)
+                .and_then(|fs| fs.chunk_states.get(&score.chunk_id))
+                .and_then(|state| state.score.clone());
+        }
+    }
+
     pub fn set_summary(&mut self, summary: SummaryResponse) {
         self.summary = Some(summary);
     }
@@ -90,16 +176,124 @@ impl ReviewSession {
                 chunk_state.user_status = status;
             }
         }
+        self.refresh_file_status(file_idx);
     }
 
-    pub fn add_note(&mut self, file_idx: usize, chunk_id: ChunkId, note: String) {
-        if let Some(file_state) = self.file_states.get_mut(&file_idx) {
-            if let Some(chunk_state) = file_state.chunk_states.get_mut(&chunk_id) {
-                chunk_state.notes.push(UserNote {
-                    text: note,
-                    created_at: Instant::now(),
+    pub fn chunk_status(&self, file_idx: usize, chunk_id: ChunkId) -> UserChunkStatus {
+        self.chunk_state(file_idx, chunk_id)
+            .map(|s| s.user_status)
+            .unwrap_or_default()
+    }
+
+    pub fn chunk_state(&self, file_idx: usize, chunk_id: ChunkId) -> Option<&ChunkReviewState> {
+        self.file_states.get(&file_idx)?.chunk_states.get(&chunk_id)
+    }
+
+    /// A file is completed once every reviewable chunk in it has a status, and in progress
+    /// once any has. Files without reviewable chunks keep their status.
+    pub(crate) fn refresh_file_status(&mut self, file_idx: usize) {
+        let Some(scoring) = &self.scoring_result else {
+            return;
+        };
+        let statuses: Vec<UserChunkStatus> = scoring
+            .scores
+            .iter()
+            .filter(|s| s.file_index == file_idx && !s.is_filtered())
+            .map(|s| self.chunk_status(file_idx, s.chunk_id))
+            .collect();
+        if statuses.is_empty() {
+            return;
+        }
+
+        let reviewed = statuses.iter().filter(|s| s.is_reviewed()).count();
+        let status = if reviewed == statuses.len() {
+            FileReviewStatus::Completed
+        } else if reviewed > 0 {
+            FileReviewStatus::InProgress
+        } else {
+            FileReviewStatus::Pending
+        };
+        self.set_file_status(file_idx, status);
+    }
+
+    /// Attach a note to a file, one of its chunks or a line in a chunk
+    pub fn add_note(&mut self, file_idx: usize, target: NoteTarget, text: String) {
+        let file_state = self.file_states.entry(file_idx).or_default();
+        let (notes, line) = match target {
+            NoteTarget::File => (&mut file_state.notes, None),
+            NoteTarget::Chunk(chunk_id) => (&mut file_state.chunk_states.entry(chunk_id).or_default().notes, None),
+            NoteTarget::Line(chunk_id, line) => {
+                (&mut file_state.chunk_states.entry(chunk_id).or_default().notes, Some(line))
+            }
+        };
+        notes.push(UserNote {
+            text,
+            created_at: SystemTime::now(),
+            line,
+        });
+    }
+
+    /// Every note in diff order: per file, the file's own notes, then its chunks' notes
+    pub fn notes(&self) -> Vec<NoteEntry<'_>> {
+        let mut entries = Vec::new();
+        for (file_idx, file) in self.diff_result.files.iter().enumerate() {
+            let Some(file_state) = self.file_states.get(&file_idx) else {
+                continue;
+            };
+            for (index, note) in file_state.notes.iter().enumerate() {
+                entries.push(NoteEntry {
+                    note_ref: NoteRef { file_index: file_idx, chunk_id: None, index },
+                    target: NoteTarget::File,
+                    note,
                 });
             }
+            for chunk in &file.chunks {
+                let Some(chunk_state) = file_state.chunk_states.get(&chunk.id) else {
+                    continue;
+                };
+                for (index, note) in chunk_state.notes.iter().enumerate() {
+                    entries.push(NoteEntry {
+                        note_ref: NoteRef { file_index: file_idx, chunk_id: Some(chunk.id), index },
+                        target: match note.line {
+                            Some(line) => NoteTarget::Line(chunk.id, line),
+                            None => NoteTarget::Chunk(chunk.id),
+                        },
+                        note,
+                    });
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn note(&self, note_ref: NoteRef) -> Option<&UserNote> {
+        let file_state = self.file_states.get(&note_ref.file_index)?;
+        match note_ref.chunk_id {
+            Some(chunk_id) => file_state.chunk_states.get(&chunk_id)?.notes.get(note_ref.index),
+            None => file_state.notes.get(note_ref.index),
+        }
+    }
+
+    fn notes_mut(&mut self, note_ref: NoteRef) -> Option<&mut Vec<UserNote>> {
+        let file_state = self.file_states.get_mut(&note_ref.file_index)?;
+        match note_ref.chunk_id {
+            Some(chunk_id) => Some(&mut file_state.chunk_states.get_mut(&chunk_id)?.notes),
+            None => Some(&mut file_state.notes),
+        }
+    }
+
+    /// Replace the text of a note, keeping where it is attached and when it was written
+    pub fn update_note(&mut self, note_ref: NoteRef, text: String) {
+        if let Some(note) = self.notes_mut(note_ref).and_then(|notes| notes.get_mut(note_ref.index)) {
+            note.text = text;
+        }
+    }
+
+    pub fn remove_note(&mut self, note_ref: NoteRef) {
+        if let Some(notes) = self.notes_mut(note_ref) {
+            if note_ref.index < notes.len() {
+                notes.remove(note_ref.index);
+            }
         }
     }
 
@@ -126,11 +320,11 @@ impl ReviewSession {
 
         let (total_chunks, reviewed_chunks) = if let Some(ref scoring) = self.scoring_result {
             let total = scoring.reviewable_count();
-            let reviewed = self
-                .file_states
-                .values()
-                .flat_map(|fs| fs.chunk_states.values())
-                .filter(|cs| cs.user_status != UserChunkStatus::Unreviewed)
+            let reviewed = scoring
+                .scores
+                .iter()
+                .filter(|s| !s.is_filtered())
+                .filter(|s| self.chunk_status(s.file_index, s.chunk_id).is_reviewed())
                 .count();
             (total, reviewed)
         } else {
@@ -145,8 +339,8 @@ impl ReviewSession {
         }
     }
 
-    pub fn elapsed(&self) -> std::time::Duration {
-        self.started_at.elapsed()
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed().unwrap_or_default()
     }
 }
 
@@ -154,6 +348,8 @@ impl ReviewSession {
 pub struct FileReviewState {
     pub status: FileReviewStatus,
     pub chunk_states: HashMap<ChunkId, ChunkReviewState>,
+    /// Notes on the file as a whole
+    pub notes: Vec<UserNote>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -165,14 +361,26 @@ pub enum FileReviewStatus {
     Skipped,
 }
 
-#[derive(Debug, Clone)]
+impl FileReviewStatus {
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Pending => ' ',
+            Self::InProgress => '~',
+            Self::Completed => '+',
+            Self::Skipped => '-',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ChunkReviewState {
     pub score: Option<ControversialityResponse>,
     pub user_status: UserChunkStatus,
     pub notes: Vec<UserNote>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum UserChunkStatus {
     #[default]
     Unreviewed,
@@ -180,9 +388,27 @@ pub enum UserChunkStatus {
     Approved,
     NeedsDiscussion,
     RequestedChanges,
+    /// Marked in a saved session, but the chunk's content has changed since
+    NeedsReReview,
 }
 
 impl UserChunkStatus {
+    /// Whether the reviewer has dealt with the chunk in its current form
+    pub fn is_reviewed(&self) -> bool {
+        !matches!(self, Self::Unreviewed | Self::NeedsReReview)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Unreviewed => "unreviewed",
+            Self::Viewed => "viewed",
+            Self::Approved => "approved",
+            Self::NeedsDiscussion => "needs discussion",
+            Self::RequestedChanges => "changes requested",
+            Self::NeedsReReview => "changed since review",
+        }
+    }
+
     pub fn symbol(&self) -> char {
         match self {
             Self::Unreviewed => ' ',
@@ -190,17 +416,56 @@ impl UserChunkStatus {
             Self::Approved => '+',
             Self::NeedsDiscussion => '?',
             Self::RequestedChanges => '!',
+            Self::NeedsReReview => '*',
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserNote {
     pub text: String,
-    pub created_at: Instant,
+    pub created_at: SystemTime,
+    /// Line of the new file the note is about, for notes on a line rather than the chunk
+    #[serde(default)]
+    pub line: Option<u32>,
 }
 
-#[derive(Debug, Clone, Default)]
+/// What a note is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteTarget {
+    File,
+    Chunk(ChunkId),
+    /// A line of the new file, within a chunk
+    Line(ChunkId, u32),
+}
+
+impl NoteTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Chunk(_) => "chunk",
+            Self::Line(..) => "line",
+        }
+    }
+}
+
+/// Where a note is stored: a file's own notes, or a chunk's, and its position there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteRef {
+    pub file_index: usize,
+    pub chunk_id: Option<ChunkId>,
+    pub index: usize,
+}
+
+/// A note with where it is stored and what it is attached to
+#[derive(Debug, Clone, Copy)]
+pub struct NoteEntry<'a> {
+    pub note_ref: NoteRef,
+    pub target: NoteTarget,
+    pub note: &'a UserNote,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct SubagentReviews {
     pub security: Option<SubagentReviewResponse>,
     pub performance: Option<SubagentReviewResponse>,
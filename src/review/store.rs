@@ -0,0 +1,372 @@
+use crate::ai::schema::{ControversialityResponse, SummaryResponse};
+use crate::diff::chunk::{DiffResult, LineRange, ReviewedHead};
+use crate::error::{CraiError, CraiResult};
+use crate::review::session::{ReviewSession, SessionId, SubagentReviews, UserChunkStatus, UserNote};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// Bumped when the saved format changes incompatibly; older sessions are ignored
+const FORMAT_VERSION: u32 = 1;
+
+/// Review sessions saved between runs under `<cache_directory>/sessions`: one file per
+/// repository, base and compare branch, plus a copy per reviewed tip for `--since`
+pub struct SessionStore {
+    dir: PathBuf,
+    repository: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(cache_directory: &Path, repository: &Path) -> Self {
+        Self {
+            dir: cache_directory.join("sessions"),
+            repository: repository
+                .canonicalize()
+                .unwrap_or_else(|_| repository.to_path_buf()),
+        }
+    }
+
+    /// File holding the session for a base and compare in this repository
+    pub fn path_for(&self, base_branch: &str, compare_branch: &str) -> PathBuf {
+        // FNV-1a, like chunk fingerprints, so the name stays stable across builds
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let key = format!(
+            "{}\0{}\0{}",
+            self.repository.to_string_lossy(),
+            base_branch,
+            compare_branch
+        );
+        for b in key.bytes() {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        self.dir.join(format!("{:016x}.json", hash))
+    }
+
+    /// The session saved for the same repository, base and compare branch, if there is one.
+    /// Sessions are kept by branch name with `HEAD` resolved, and one saved for another
+    /// branch is never returned.
+    pub fn load(&self, session: &ReviewSession) -> CraiResult<Option<SavedSession>> {
+        let path = self.path_for(&session.base_branch, compare_key(session));
+        let Some(saved) = self.read(&path)? else {
+            return Ok(None);
+        };
+        let saved_key = saved.head.as_ref().map_or(&saved.compare_branch, |h| &h.name);
+        let same_target = saved.base_branch == session.base_branch && saved_key == compare_key(session);
+        Ok(same_target.then_some(saved))
+    }
+
+    /// The session last saved while the compare side was at commit `sha`, as needed to
+    /// carry a review over with `--since`
+    pub fn load_at(&self, base_branch: &str, sha: &str) -> CraiResult<Option<SavedSession>> {
+        let Some(saved) = self.read(&self.path_for(base_branch, sha))? else {
+            return Ok(None);
+        };
+        let same_tip = saved.base_branch == base_branch
+            && saved.head.as_ref().and_then(|h| h.sha.as_deref()) == Some(sha);
+        Ok(same_tip.then_some(saved))
+    }
+
+    /// The review of the diff as it stood at an earlier tip, rebuilt from the session saved
+    /// then, for carrying over to the hunks that did not change since
+    pub fn load_previous(&self, previous: &DiffResult) -> CraiResult<Option<ReviewSession>> {
+        let Some(sha) = previous.head.as_ref().and_then(|h| h.sha.as_deref()) else {
+            return Ok(None);
+        };
+        let Some(saved) = self.load_at(&previous.base_branch, sha)? else {
+            return Ok(None);
+        };
+        let mut session = ReviewSession::new(previous.clone());
+        saved.apply(&mut session);
+        Ok(Some(session))
+    }
+
+    /// Write the session, replacing any saved before, and keep a copy under its tip commit.
+    /// Sessions with nothing worth resuming are not written.
+    pub fn save(&self, session: &ReviewSession) -> CraiResult<()> {
+        let saved = SavedSession::from_session(session, &self.repository);
+        if saved.is_empty() {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|e| CraiError::Serialization(e.to_string()))?;
+
+        let mut paths = vec![self.path_for(&session.base_branch, compare_key(session))];
+        if let Some(sha) = session.diff_result.head.as_ref().and_then(|h| h.sha.as_deref()) {
+            let tip_path = self.path_for(&session.base_branch, sha);
+            if !paths.contains(&tip_path) {
+                paths.push(tip_path);
+            }
+        }
+
+        for path in paths {
+            // Write then rename, so an interrupted save leaves the previous session intact
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, &json)?;
+            std::fs::rename(&tmp, &path)?;
+        }
+        Ok(())
+    }
+
+    /// A saved session of this repository in the current format, if the file exists
+    fn read(&self, path: &Path) -> CraiResult<Option<SavedSession>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let saved: SavedSession = serde_json::from_str(&content).map_err(|e| {
+            CraiError::Session(format!("Failed to read saved session {}: {}", path.display(), e))
+        })?;
+        if saved.version != FORMAT_VERSION || saved.repository != self.repository {
+            return Ok(None);
+        }
+        Ok(Some(saved))
+    }
+}
+
+/// What a session is saved under besides its base: the resolved branch when known
+fn compare_key(session: &ReviewSession) -> &str {
+    session
+        .diff_result
+        .head
+        .as_ref()
+        .map_or(&session.compare_branch, |h| &h.name)
+}
+
+/// A review session as saved to disk. Chunks are identified by content fingerprint, since
+/// chunk ids and file indices don't survive a new diff.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedSession {
+    pub version: u32,
+    pub id: String,
+    pub repository: PathBuf,
+    pub base_branch: String,
+    pub compare_branch: String,
+    /// Branch and commit the review was of; sessions saved for another branch are ignored
+    #[serde(default)]
+    pub head: Option<ReviewedHead>,
+    pub started_at: SystemTime,
+    pub saved_at: SystemTime,
+    pub summary: Option<SummaryResponse>,
+    pub subagent_reviews: SubagentReviews,
+    pub chunks: Vec<SavedChunk>,
+    /// Notes on whole files
+    #[serde(default)]
+    pub file_notes: Vec<SavedFileNotes>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedFileNotes {
+    pub path: PathBuf,
+    pub notes: Vec<UserNote>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedChunk {
+    pub path: PathBuf,
+    pub fingerprint: u64,
+    /// Lines of the new file the chunk covered, to find its successor once it has changed
+    pub new_start: u32,
+    pub new_count: u32,
+    pub status: UserChunkStatus,
+    pub notes: Vec<UserNote>,
+    pub score: Option<ControversialityResponse>,
+}
+
+impl SavedChunk {
+    fn overlaps(&self, range: &LineRange) -> bool {
+        let end = self.new_start + self.new_count.saturating_sub(1);
+        self.new_start <= range.end() && range.start <= end
+    }
+
+    fn has_review(&self) -> bool {
+        self.status != UserChunkStatus::Unreviewed || !self.notes.is_empty()
+    }
+}
+
+/// What resuming a saved session restored
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResumeStats {
+    /// Chunks whose status, notes or score were taken over unchanged
+    pub restored: usize,
+    /// Chunks reviewed before whose content has changed since
+    pub changed: usize,
+}
+
+impl SavedSession {
+    pub fn from_session(session: &ReviewSession, repository: &Path) -> Self {
+        let mut chunks = Vec::new();
+        let mut file_notes = Vec::new();
+        for (file_idx, file) in session.diff_result.files.iter().enumerate() {
+            let Some(file_state) = session.file_states.get(&file_idx) else {
+                continue;
+            };
+            if !file_state.notes.is_empty() {
+                file_notes.push(SavedFileNotes {
+                    path: file.path.clone(),
+                    notes: file_state.notes.clone(),
+                });
+            }
+            for chunk in &file.chunks {
+                let Some(state) = file_state.chunk_states.get(&chunk.id) else {
+                    continue;
+                };
+                if state.user_status == UserChunkStatus::Unreviewed
+                    && state.notes.is_empty()
+                    && state.score.is_none()
+                {
+                    continue;
+                }
+                chunks.push(SavedChunk {
+                    path: file.path.clone(),
+                    fingerprint: chunk.fingerprint(&file.path).0,
+                    new_start: chunk.new_range.start,
+                    new_count: chunk.new_range.count,
+                    status: state.user_status,
+                    notes: state.notes.clone(),
+                    score: state.score.clone(),
+                });
+            }
+        }
+
+        Self {
+            version: FORMAT_VERSION,
+            id: session.id.to_string(),
+            repository: repository.to_path_buf(),
+            base_branch: session.base_branch.clone(),
+            compare_branch: session.compare_branch.clone(),
+            head: session.diff_result.head.clone(),
+            started_at: session.started_at,
+            saved_at: SystemTime::now(),
+            summary: session.summary.clone(),
+            subagent_reviews: session.subagent_reviews.clone(),
+            chunks,
+            file_notes,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        let reviews = &self.subagent_reviews;
+        self.chunks.is_empty()
+            && self.file_notes.is_empty()
+            && self.summary.is_none()
+            && reviews.security.is_none()
+            && reviews.performance.is_none()
+            && reviews.usability.is_none()
+    }
+
+    /// Chunks with a status other than unreviewed
+    pub fn marked_count(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|c| c.status != UserChunkStatus::Unreviewed)
+            .count()
+    }
+
+    pub fn note_count(&self) -> usize {
+        let chunk_notes: usize = self.chunks.iter().map(|c| c.notes.len()).sum();
+        chunk_notes + self.file_notes.iter().map(|f| f.notes.len()).sum::<usize>()
+    }
+
+    /// Carry the saved review over to `session`, whose scoring must already be set.
+    /// Chunks with the same content get back their status, notes and score; chunks that
+    /// replaced a reviewed chunk at the same place are marked as needing re-review and keep
+    /// its notes. File notes follow the path. Results the current run didn't produce, like
+    /// the summary, are filled in.
+    pub fn apply(&self, session: &mut ReviewSession) -> ResumeStats {
+        let mut stats = ResumeStats::default();
+
+        if let Ok(uuid) = Uuid::parse_str(&self.id) {
+            session.id = SessionId(uuid);
+        }
+        session.started_at = self.started_at;
+        if session.summary.is_none() {
+            session.summary = self.summary.clone();
+        }
+        let reviews = &mut session.subagent_reviews;
+        reviews.security = reviews.security.take().or_else(|| self.subagent_reviews.security.clone());
+        reviews.performance = reviews.performance.take().or_else(|| self.subagent_reviews.performance.clone());
+        reviews.usability = reviews.usability.take().or_else(|| self.subagent_reviews.usability.clone());
+
+        // Identical hunks share a fingerprint and are paired in order
+        let mut by_fingerprint: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (idx, chunk) in self.chunks.iter().enumerate() {
+            by_fingerprint.entry(chunk.fingerprint).or_default().push(idx);
+        }
+        let mut matched = HashSet::new();
+        let mut unmatched_chunks = Vec::new();
+
+        for (file_idx, file) in session.diff_result.files.iter().enumerate() {
+            if let Some(saved) = self.file_notes.iter().find(|f| f.path == file.path) {
+                session.file_states.entry(file_idx).or_default().notes = saved.notes.clone();
+            }
+            for chunk in &file.chunks {
+                let fingerprint = chunk.fingerprint(&file.path).0;
+                let saved_idx = by_fingerprint
+                    .get_mut(&fingerprint)
+                    .filter(|candidates| !candidates.is_empty())
+                    .map(|candidates| candidates.remove(0));
+                let Some(saved_idx) = saved_idx else {
+                    unmatched_chunks.push((file_idx, chunk.id, &file.path, chunk.new_range));
+                    continue;
+                };
+                matched.insert(saved_idx);
+
+                let saved = &self.chunks[saved_idx];
+                let state = session
+                    .file_states
+                    .entry(file_idx)
+                    .or_default()
+                    .chunk_states
+                    .entry(chunk.id)
+                    .or_default();
+                state.user_status = saved.status;
+                state.notes = saved.notes.clone();
+                if state.score.is_none() {
+                    state.score = saved.score.clone();
+                }
+                stats.restored += 1;
+            }
+        }
+
+        for (file_idx, chunk_id, path, range) in unmatched_chunks {
+            let predecessors: Vec<&SavedChunk> = self
+                .chunks
+                .iter()
+                .enumerate()
+                .filter(|(idx, saved)| {
+                    !matched.contains(idx) && saved.has_review() && saved.path == *path && saved.overlaps(&range)
+                })
+                .map(|(_, saved)| saved)
+                .collect();
+            if predecessors.is_empty() {
+                continue;
+            }
+
+            let Some(state) = session
+                .file_states
+                .get_mut(&file_idx)
+                .and_then(|fs| fs.chunk_states.get_mut(&chunk_id))
+            else {
+                continue;
+            };
+            state.user_status = UserChunkStatus::NeedsReReview;
+            state
+                .notes
+                .extend(predecessors.iter().flat_map(|saved| saved.notes.iter().cloned()));
+            stats.changed += 1;
+        }
+
+        for file_idx in 0..session.diff_result.files.len() {
+            session.refresh_file_status(file_idx);
+        }
+
+        stats
+    }
+}
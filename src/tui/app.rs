@@ -1,11 +1,21 @@
 use crate::ai::schema::{ControversialityResponse, SummaryResponse};
 use crate::ai::scoring::{ChunkScore, ScoringResult};
 use crate::config::Config;
-use crate::diff::{DiffResult, FileDiff};
+use crate::diff::category::untested_source;
+use crate::diff::git::CommitInfo;
+use crate::diff::moves::MovedCode;
+use crate::diff::chunk::{ChunkId, DiffLine, LineKind};
+use crate::diff::{DiffChunk, DiffResult, FileDiff};
 use crate::error::CraiResult;
+use crate::review::session::{NoteRef, NoteTarget, UserChunkStatus};
+use crate::review::store::{ResumeStats, SavedSession};
+use crate::review::ReviewSession;
+use crate::tui::editor::TextArea;
 use crate::tui::event::{Action, Direction, StreamSortMode, SubagentAction};
 use crate::tui::views::stream::{calculate_stream_total_lines, get_sorted_highlights};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 /// Precomputed index for efficient stream navigation
 #[derive(Debug, Clone)]
@@ -115,6 +125,56 @@ enum NavContext {
     Stream { max_scroll: usize },
 }
 
+/// One commit of a commit-by-commit review.
+/// The results of the commit being shown live in `App`; the others are parked here.
+#[derive(Debug, Clone)]
+pub struct CommitStep {
+    pub commit: CommitInfo,
+    pub diff_result: DiffResult,
+    pub scoring_result: Option<ScoringResult>,
+    pub summary: Option<SummaryResponse>,
+    pub session: ReviewSession,
+}
+
+impl CommitStep {
+    pub fn new(
+        commit: CommitInfo,
+        diff_result: DiffResult,
+        scoring_result: Option<ScoringResult>,
+        summary: Option<SummaryResponse>,
+    ) -> Self {
+        let mut session = ReviewSession::new(diff_result.clone());
+        if let Some(result) = &scoring_result {
+            session.set_scoring_result(result.clone());
+        }
+        if let Some(summary) = &summary {
+            session.set_summary(summary.clone());
+        }
+        Self {
+            commit,
+            diff_result,
+            scoring_result,
+            summary,
+            session,
+        }
+    }
+}
+
+/// A note being written or edited
+#[derive(Debug, Clone)]
+pub struct NoteEditor {
+    pub file_index: usize,
+    pub target: NoteTarget,
+    /// Chunk the editor was opened on, which a new note can be moved back to after
+    /// retargeting it to the file
+    pub chunk_id: Option<ChunkId>,
+    /// The note being edited, or None for a new one
+    pub editing: Option<NoteRef>,
+    pub text: TextArea,
+    /// Set when the text should be handed to `$EDITOR`; see [`App::take_external_edit`]
+    external: bool,
+}
+
 pub struct App {
     pub config: Config,
     pub diff_result: DiffResult,
@@ -125,6 +185,18 @@ pub struct App {
     pub status_message: Option<StatusMessage>,
     pub progress: Option<Progress>,
     pub stream_index: StreamIndex,
+    /// Source files changed while no tests changed, for the "no tests changed" concern
+    pub untested_source: Vec<PathBuf>,
+    /// Commits being reviewed one at a time (empty when reviewing a single diff)
+    pub commit_steps: Vec<CommitStep>,
+    /// Index into `commit_steps` of the commit currently shown
+    pub current_commit: usize,
+    /// Config profile in effect, shown in the header
+    pub profile: Option<String>,
+    /// Review status and notes for the diff being shown
+    pub session: ReviewSession,
+    /// Note being written, which takes all key presses until saved or cancelled
+    pub note_editor: Option<NoteEditor>,
 }
 
 #[derive(Debug, Clone)]
@@ -152,6 +224,11 @@ pub enum View {
     Stats,
     Help,
     QuitConfirm,
+    /// Every note in the review, for reading, editing and deleting
+    Notes {
+        /// Selected entry of `ReviewSession::notes`
+        selected: usize,
+    },
 }
 
 impl Default for View {
@@ -190,9 +267,18 @@ impl Progress {
     }
 }
 
+fn untested_paths(diff_result: &DiffResult) -> Vec<PathBuf> {
+    untested_source(&diff_result.files)
+        .into_iter()
+        .map(|f| f.path.clone())
+        .collect()
+}
+
 impl App {
     pub fn new(config: Config, diff_result: DiffResult) -> Self {
         let stream_index = StreamIndex::build(&diff_result);
+        let untested_source = untested_paths(&diff_result);
+        let session = ReviewSession::new(diff_result.clone());
         Self {
             config,
             diff_result,
@@ -203,14 +289,157 @@ impl App {
             status_message: None,
             progress: None,
             stream_index,
+            untested_source,
+            commit_steps: Vec::new(),
+            current_commit: 0,
+            profile: None,
+            session,
+            note_editor: None,
+        }
+    }
+
+    /// Create an app that steps through commits, starting with the first one
+    pub fn with_commits(config: Config, commit_steps: Vec<CommitStep>) -> Self {
+        let mut app = Self::new(config, DiffResult::default());
+        app.commit_steps = commit_steps;
+        app.swap_commit(0);
+        app.stream_index = StreamIndex::build(&app.diff_result);
+        app.untested_source = untested_paths(&app.diff_result);
+        app
+    }
+
+    /// The commit currently shown, when stepping through commits
+    pub fn current_commit(&self) -> Option<&CommitInfo> {
+        self.commit_steps.get(self.current_commit).map(|s| &s.commit)
+    }
+
+    /// Exchange the app's active results with those parked in a commit step
+    fn swap_commit(&mut self, index: usize) {
+        if let Some(step) = self.commit_steps.get_mut(index) {
+            std::mem::swap(&mut self.diff_result, &mut step.diff_result);
+            std::mem::swap(&mut self.scoring_result, &mut step.scoring_result);
+            std::mem::swap(&mut self.summary, &mut step.summary);
+            std::mem::swap(&mut self.session, &mut step.session);
+        }
+    }
+
+    /// Review sessions of every commit in order, or of the single diff
+    pub fn sessions(&self) -> Vec<&ReviewSession> {
+        if self.commit_steps.is_empty() {
+            return vec![&self.session];
+        }
+        self.commit_steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| if idx == self.current_commit { &self.session } else { &step.session })
+            .collect()
+    }
+
+    fn sessions_mut(&mut self) -> Vec<&mut ReviewSession> {
+        let mut current = Some(&mut self.session);
+        let mut sessions = Vec::new();
+        for (idx, step) in self.commit_steps.iter_mut().enumerate() {
+            if idx == self.current_commit {
+                sessions.extend(current.take());
+            } else {
+                sessions.push(&mut step.session);
+            }
+        }
+        sessions.extend(current);
+        sessions
+    }
+
+    /// Carry saved sessions, in the order of [`App::sessions`], over to the review
+    pub fn resume(&mut self, saved: &[Option<SavedSession>]) -> ResumeStats {
+        let mut stats = ResumeStats::default();
+        for (session, saved) in self.sessions_mut().into_iter().zip(saved) {
+            if let Some(saved) = saved {
+                let resumed = saved.apply(session);
+                session.fill_scores_from_states();
+                stats.restored += resumed.restored;
+                stats.changed += resumed.changed;
+            }
+        }
+
+        // Restored scores show up like this run's, and a summary from the saved session
+        // stands in for one this run didn't produce
+        self.scoring_result = self.session.scoring_result.clone();
+        if self.summary.is_none() {
+            self.summary = self.session.summary.clone();
+        }
+        for step in &mut self.commit_steps {
+            step.scoring_result = step.session.scoring_result.clone();
+            if step.summary.is_none() {
+                step.summary = step.session.summary.clone();
+            }
+        }
+        stats
+    }
+
+    /// Take over the review of the revision given with `--since` for the hunks that did not
+    /// change since. Call after `set_scoring_result`.
+    pub fn carry_over(&mut self, previous: &ReviewSession) {
+        self.session.carry_over(previous);
+        self.scoring_result = self.session.scoring_result.clone();
+    }
+
+    /// Move to another commit by `delta`, resetting the review position
+    fn step_commit(&mut self, delta: isize) {
+        if self.commit_steps.is_empty() {
+            return;
+        }
+
+        let target = self
+            .current_commit
+            .saturating_add_signed(delta)
+            .min(self.commit_steps.len() - 1);
+        if target == self.current_commit {
+            return;
+        }
+
+        // Park the current commit's results, then bring in the target's
+        self.swap_commit(self.current_commit);
+        self.swap_commit(target);
+        self.current_commit = target;
+        self.stream_index = StreamIndex::build(&self.diff_result);
+        self.untested_source = untested_paths(&self.diff_result);
+
+        let expanded = self.compute_smart_expanded();
+        if let View::Review {
+            tree_selected,
+            tree_scroll_offset,
+            stream_scroll_offset,
+            expanded_files,
+            selected_highlight,
+            ..
+        } = &mut self.view
+        {
+            *tree_selected = 0;
+            *tree_scroll_offset = 0;
+            *stream_scroll_offset = 0;
+            *selected_highlight = None;
+            *expanded_files = expanded;
+        }
+
+        if let Some(commit) = self.current_commit() {
+            let text = format!(
+                "Commit {}/{}: {} {}",
+                self.current_commit + 1,
+                self.commit_steps.len(),
+                commit.short_sha(),
+                commit.summary
+            );
+            self.set_status(&text, MessageLevel::Info);
         }
     }
 
     pub fn set_scoring_result(&mut self, result: ScoringResult) {
+        self.session.set_scoring_result(result.clone());
         self.scoring_result = Some(result);
     }
 
     pub fn set_summary(&mut self, summary: SummaryResponse) {
+        self.session.set_summary(summary.clone());
         self.summary = Some(summary);
     }
 
@@ -237,6 +466,230 @@ impl App {
         self.status_message = None;
     }
 
+    /// Handle a key press: editing while a note is open, otherwise its action.
+    /// A status message lasts until the next key press.
+    pub fn handle_key(&mut self, key: KeyEvent) -> CraiResult<()> {
+        self.clear_status();
+        if self.note_editor.is_some() {
+            self.handle_note_key(key);
+            return Ok(());
+        }
+        self.handle_action(Action::from_key(key))
+    }
+
+    /// Insert pasted text into the open note
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some(editor) = &mut self.note_editor {
+            editor.text.insert_str(text);
+        }
+    }
+
+    fn handle_note_key(&mut self, key: KeyEvent) {
+        let Some(editor) = &mut self.note_editor else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let moves_line = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Esc => {
+                self.note_editor = None;
+                self.set_status("Note discarded", MessageLevel::Info);
+            }
+            KeyCode::Char('c') if ctrl => {
+                self.note_editor = None;
+            }
+            KeyCode::Char('s') if ctrl => self.save_note(),
+            KeyCode::Char('e') if ctrl => editor.external = true,
+            KeyCode::Char('t') if ctrl => self.cycle_note_target(),
+            KeyCode::Up if moves_line => self.move_note_line(-1),
+            KeyCode::Down if moves_line => self.move_note_line(1),
+            _ => {
+                editor.text.input(key);
+            }
+        }
+    }
+
+    /// Open the editor for a new note on the selected highlight, or the selected file in
+    /// the sidebar, or the chunk at the top of the stream
+    fn open_note_editor(&mut self) {
+        let target = if let Some(score) = self.selected_score() {
+            Some((score.file_index, NoteTarget::Chunk(score.chunk_id)))
+        } else if let View::Review {
+            tree_selected,
+            tree_focused: true,
+            sort_mode: StreamSortMode::ByFile,
+            selected_highlight: None,
+            ..
+        } = &self.view
+        {
+            Some((*tree_selected, NoteTarget::File))
+        } else {
+            self.current_context().and_then(|(file_index, chunk_index)| {
+                let chunk = self.diff_result.files.get(file_index)?.chunks.get(chunk_index)?;
+                Some((file_index, NoteTarget::Chunk(chunk.id)))
+            })
+        };
+
+        let Some((file_index, target)) = target else {
+            self.set_status("Select a highlight or file to add a note to", MessageLevel::Warning);
+            return;
+        };
+        let chunk_id = match target {
+            NoteTarget::Chunk(chunk_id) | NoteTarget::Line(chunk_id, _) => Some(chunk_id),
+            NoteTarget::File => None,
+        };
+        self.note_editor = Some(NoteEditor {
+            file_index,
+            target,
+            chunk_id,
+            editing: None,
+            text: TextArea::default(),
+            external: false,
+        });
+    }
+
+    fn save_note(&mut self) {
+        let Some(editor) = self.note_editor.take() else {
+            return;
+        };
+        let text = editor.text.text().trim().to_string();
+
+        match (editor.editing, text.is_empty()) {
+            (None, true) => self.set_status("Empty note discarded", MessageLevel::Info),
+            (None, false) => {
+                self.session.add_note(editor.file_index, editor.target, text);
+                self.set_status(&format!("Note added to {}", editor.target.label()), MessageLevel::Info);
+            }
+            (Some(note_ref), true) => {
+                self.session.remove_note(note_ref);
+                self.clamp_notes_selection();
+                self.set_status("Note deleted", MessageLevel::Info);
+            }
+            (Some(note_ref), false) => {
+                self.session.update_note(note_ref, text);
+                self.set_status("Note updated", MessageLevel::Info);
+            }
+        }
+    }
+
+    /// Switch a new note between the chunk it was opened on, one of its lines and its file
+    fn cycle_note_target(&mut self) {
+        let Some(editor) = &self.note_editor else {
+            return;
+        };
+        if editor.editing.is_some() {
+            return;
+        }
+        let target = match (editor.target, editor.chunk_id) {
+            (NoteTarget::Chunk(chunk_id), _) => match self.note_lines(editor.file_index, chunk_id).first() {
+                Some(&line) => NoteTarget::Line(chunk_id, line),
+                None => NoteTarget::File,
+            },
+            (NoteTarget::Line(..), _) => NoteTarget::File,
+            (NoteTarget::File, Some(chunk_id)) => NoteTarget::Chunk(chunk_id),
+            (NoteTarget::File, None) => NoteTarget::File,
+        };
+        if let Some(editor) = &mut self.note_editor {
+            editor.target = target;
+        }
+    }
+
+    /// Move a line note to the next or previous line of its chunk
+    fn move_note_line(&mut self, delta: isize) {
+        let Some(NoteEditor {
+            file_index,
+            target: NoteTarget::Line(chunk_id, line),
+            editing: None,
+            ..
+        }) = self.note_editor
+        else {
+            return;
+        };
+        let lines = self.note_lines(file_index, chunk_id);
+        let Some(current) = lines.iter().position(|&l| l == line) else {
+            return;
+        };
+        let next = current.saturating_add_signed(delta).min(lines.len() - 1);
+        if let Some(editor) = &mut self.note_editor {
+            editor.target = NoteTarget::Line(chunk_id, lines[next]);
+        }
+    }
+
+    /// Lines of the new file a note in a chunk can be attached to, added lines first
+    pub fn note_lines(&self, file_index: usize, chunk_id: ChunkId) -> Vec<u32> {
+        let Some(chunk) = self
+            .diff_result
+            .files
+            .get(file_index)
+            .and_then(|file| file.chunks.iter().find(|c| c.id == chunk_id))
+        else {
+            return Vec::new();
+        };
+        let (added, context): (Vec<&DiffLine>, Vec<&DiffLine>) = chunk
+            .lines
+            .iter()
+            .filter(|l| l.new_line_num.is_some())
+            .partition(|l| l.kind == LineKind::Add);
+        let lines = if added.is_empty() { context } else { added };
+        lines.iter().filter_map(|l| l.new_line_num).collect()
+    }
+
+    /// Text of the open note if it asked for the external editor, clearing the request.
+    /// The caller leaves the TUI, runs [`edit_externally`](crate::tui::editor::edit_externally)
+    /// and passes the result to [`App::finish_external_edit`].
+    pub fn take_external_edit(&mut self) -> Option<String> {
+        let editor = self.note_editor.as_mut().filter(|e| e.external)?;
+        editor.external = false;
+        Some(editor.text.text())
+    }
+
+    pub fn finish_external_edit(&mut self, result: CraiResult<String>) {
+        match result {
+            Ok(text) => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.text = TextArea::from_text(&text);
+                }
+            }
+            Err(e) => self.set_status(&format!("External editor failed: {}", e), MessageLevel::Error),
+        }
+    }
+
+    fn edit_selected_note(&mut self) {
+        let View::Notes { selected } = self.view else {
+            return;
+        };
+        let Some(entry) = self.session.notes().get(selected).copied() else {
+            return;
+        };
+        self.note_editor = Some(NoteEditor {
+            file_index: entry.note_ref.file_index,
+            target: entry.target,
+            chunk_id: entry.note_ref.chunk_id,
+            editing: Some(entry.note_ref),
+            text: TextArea::from_text(&entry.note.text),
+            external: false,
+        });
+    }
+
+    fn delete_selected_note(&mut self) {
+        let View::Notes { selected } = self.view else {
+            return;
+        };
+        let Some(note_ref) = self.session.notes().get(selected).map(|e| e.note_ref) else {
+            return;
+        };
+        self.session.remove_note(note_ref);
+        self.clamp_notes_selection();
+        self.set_status("Note deleted", MessageLevel::Info);
+    }
+
+    /// Keep the notes panel's selection on a note after one is removed
+    fn clamp_notes_selection(&mut self) {
+        if let View::Notes { selected } = &mut self.view {
+            *selected = (*selected).min(self.session.notes().len().saturating_sub(1));
+        }
+    }
+
     pub fn handle_action(&mut self, action: Action) -> CraiResult<()> {
         // Handle quit confirmation specially
         if matches!(self.view, View::QuitConfirm) {
@@ -304,9 +757,23 @@ impl App {
             Action::PrevHighlight => {
                 self.navigate_highlight(-1);
             }
-            Action::Approve | Action::Discuss | Action::RequestChanges | Action::AddNote => {
-                // These would modify review state - stub for now
-                self.set_status("Review actions not yet implemented", MessageLevel::Info);
+            Action::Approve => {
+                self.mark_selected(UserChunkStatus::Approved);
+            }
+            Action::Discuss => {
+                self.mark_selected(UserChunkStatus::NeedsDiscussion);
+            }
+            Action::RequestChanges => {
+                self.mark_selected(UserChunkStatus::RequestedChanges);
+            }
+            Action::AddNote => {
+                self.open_note_editor();
+            }
+            Action::Notes => {
+                self.view = View::Notes { selected: 0 };
+            }
+            Action::Delete => {
+                self.delete_selected_note();
             }
             Action::RunSubagent(subagent) => {
                 self.set_status(
@@ -363,6 +830,12 @@ impl App {
                     }
                 }
             }
+            Action::NextCommit => {
+                self.step_commit(1);
+            }
+            Action::PrevCommit => {
+                self.step_commit(-1);
+            }
             Action::ToggleSortMode => {
                 if let View::Review { sort_mode, .. } = &mut self.view {
                     *sort_mode = match sort_mode {
@@ -383,6 +856,7 @@ impl App {
         self.view = match &self.view {
             View::Help => View::Summary,
             View::Stats => View::Summary,
+            View::Notes { .. } => View::Summary,
             View::Review { .. } => View::Summary,
             View::Summary | View::QuitConfirm => {
                 self.view = View::QuitConfirm;
@@ -392,6 +866,21 @@ impl App {
     }
 
     fn handle_navigation(&mut self, dir: Direction) {
+        if let View::Notes { selected } = self.view {
+            let last = self.session.notes().len().saturating_sub(1);
+            let selected = match dir {
+                Direction::Up => selected.saturating_sub(1),
+                Direction::Down => selected + 1,
+                Direction::PageUp => selected.saturating_sub(10),
+                Direction::PageDown => selected + 10,
+                Direction::Home => 0,
+                Direction::End => last,
+                Direction::Left | Direction::Right => selected,
+            };
+            self.view = View::Notes { selected: selected.min(last) };
+            return;
+        }
+
         // Extract state needed for navigation before mutable borrow
         let nav_context = if let View::Review {
             tree_selected,
@@ -677,6 +1166,9 @@ impl App {
             View::Help => {
                 self.view = View::Summary;
             }
+            View::Notes { .. } => {
+                self.edit_selected_note();
+            }
             _ => {}
         }
     }
@@ -780,12 +1272,18 @@ impl App {
                 height += resp.concerns.len();
             }
 
+            if !score.integrity_flags.is_empty() {
+                height += 1; // Blank
+                height += score.integrity_flags.len();
+            }
+
             height += 1; // Blank after analysis
         }
 
         // "Changes:" header + diff lines
         height += 1; // "Changes:" header
-        height += chunk.lines.len();
+        let shown = self.moved_residual(chunk).map_or(chunk, |(_, residual)| residual);
+        height += shown.lines.len();
 
         // Separator: 2 lines
         height += 2;
@@ -855,6 +1353,177 @@ impl App {
         self.calculate_highlight_height(score)
     }
 
+    /// Set the review status of the selected highlight; setting the status it already
+    /// has clears it
+    fn mark_selected(&mut self, status: UserChunkStatus) {
+        let Some(score) = self.selected_score() else {
+            self.set_status("Select a highlight to review", MessageLevel::Warning);
+            return;
+        };
+        let (file_index, chunk_id) = (score.file_index, score.chunk_id);
+
+        let status = if self.session.chunk_status(file_index, chunk_id) == status {
+            UserChunkStatus::Unreviewed
+        } else {
+            status
+        };
+        self.session.mark_chunk_status(file_index, chunk_id, status);
+
+        let progress = self.session.progress();
+        let text = format!(
+            "{}: {} ({}/{} reviewed)",
+            self.diff_result.files[file_index].path.display(),
+            status.label(),
+            progress.reviewed_chunks,
+            progress.total_chunks
+        );
+        self.set_status(&text, MessageLevel::Info);
+
+        if status.is_reviewed() && self.config.tui.auto_advance {
+            self.advance_to_unreviewed(file_index, chunk_id);
+        }
+    }
+
+    /// Select the next reviewable highlight still needing review after the given one, in the
+    /// order the stream shows them
+    fn advance_to_unreviewed(&mut self, file_index: usize, chunk_id: ChunkId) {
+        let View::Review { sort_mode, .. } = &self.view else {
+            return;
+        };
+        let sort_mode = *sort_mode;
+
+        let (highlights, _) = get_sorted_highlights(self, sort_mode);
+        let current = highlights
+            .iter()
+            .position(|s| s.file_index == file_index && s.chunk_id == chunk_id)
+            .unwrap_or(0);
+        let next = (1..=highlights.len())
+            .map(|step| (current + step) % highlights.len())
+            .find(|&idx| {
+                let score = highlights[idx];
+                !score.is_filtered()
+                    && !self.session.chunk_status(score.file_index, score.chunk_id).is_reviewed()
+            });
+
+        match next {
+            Some(idx) => self.select_highlight(idx, sort_mode),
+            None => self.set_status("All highlights reviewed", MessageLevel::Info),
+        }
+    }
+
+    /// Select a highlight by its position in the sorted stream, in both the sidebar and the stream
+    fn select_highlight(&mut self, index: usize, sort_mode: StreamSortMode) {
+        let (highlights, _) = get_sorted_highlights(self, sort_mode);
+        let Some(score) = highlights.get(index) else {
+            return;
+        };
+        let file_index = score.file_index;
+        let file_highlight = self
+            .highlights_for_file(file_index)
+            .iter()
+            .position(|s| s.chunk_index == score.chunk_index);
+        let offset = self.get_scroll_offset_for_highlight(index, sort_mode);
+
+        if let View::Review {
+            tree_selected,
+            stream_scroll_offset,
+            expanded_files,
+            selected_highlight,
+            ..
+        } = &mut self.view
+        {
+            match sort_mode {
+                StreamSortMode::ByScore => *selected_highlight = Some(index),
+                StreamSortMode::ByFile => {
+                    *tree_selected = file_index;
+                    expanded_files.insert(file_index);
+                    *selected_highlight = file_highlight;
+                }
+            }
+            if let Some(offset) = offset {
+                *stream_scroll_offset = offset;
+            }
+        }
+    }
+
+    /// The highlight review actions apply to: the one selected in the sidebar when it has
+    /// focus, otherwise the one at the top of the stream
+    pub fn selected_score(&self) -> Option<&ChunkScore> {
+        let View::Review {
+            tree_selected,
+            tree_focused,
+            stream_scroll_offset,
+            sort_mode,
+            selected_highlight,
+            ..
+        } = &self.view
+        else {
+            return None;
+        };
+
+        if !*tree_focused {
+            return self.highlight_at_offset(*stream_scroll_offset, *sort_mode);
+        }
+        match sort_mode {
+            StreamSortMode::ByScore => {
+                let (highlights, _) = get_sorted_highlights(self, StreamSortMode::ByScore);
+                highlights.get(selected_highlight.unwrap_or(0)).copied()
+            }
+            StreamSortMode::ByFile => self
+                .highlights_for_file(*tree_selected)
+                .get((*selected_highlight)?)
+                .copied(),
+        }
+    }
+
+    /// The highlight whose block contains a stream line
+    fn highlight_at_offset(&self, offset: usize, sort_mode: StreamSortMode) -> Option<&ChunkScore> {
+        let (highlights, divider_index) = get_sorted_highlights(self, sort_mode);
+        let divider_height = 5; // Same as DIVIDER_HEIGHT in stream.rs
+
+        let mut start = 0;
+        let mut found = None;
+        for (idx, score) in highlights.into_iter().enumerate() {
+            if sort_mode == StreamSortMode::ByScore && divider_index == Some(idx) {
+                start += divider_height;
+            }
+            if start > offset {
+                break;
+            }
+            found = Some(score);
+            start += self.calculate_highlight_height(score);
+        }
+        found
+    }
+
+    /// Where a note is attached, e.g. "src/main.rs:42" for a line, or the chunk's line
+    /// range and enclosing symbol
+    pub fn note_location(&self, file_index: usize, target: NoteTarget) -> String {
+        let Some(file) = self.diff_result.files.get(file_index) else {
+            return String::new();
+        };
+        let path = file.path.display();
+        match target {
+            NoteTarget::File => path.to_string(),
+            NoteTarget::Line(_, line) => format!("{}:{}", path, line),
+            NoteTarget::Chunk(chunk_id) => match file.chunks.iter().find(|c| c.id == chunk_id) {
+                Some(chunk) => {
+                    let mut location = format!("{}:{}-{}", path, chunk.new_range.start, chunk.new_range.end());
+                    if let Some(symbol) = &chunk.symbol {
+                        location.push_str(&format!(" ({})", symbol));
+                    }
+                    location
+                }
+                None => path.to_string(),
+            },
+        }
+    }
+
+    /// Review status of a scored chunk
+    pub fn chunk_status(&self, score: &ChunkScore) -> UserChunkStatus {
+        self.session.chunk_status(score.file_index, score.chunk_id)
+    }
+
     // Accessor methods for views
 
     pub fn files(&self) -> &[FileDiff] {
@@ -892,6 +1561,16 @@ impl App {
         self.current_chunk_score().and_then(|cs| cs.response.as_ref())
     }
 
+    /// The move a chunk takes part in, with what is left of the chunk once the move is
+    /// accounted for, when that is what gets reviewed instead of the whole chunk
+    pub fn moved_residual(&self, chunk: &DiffChunk) -> Option<(&MovedCode, &DiffChunk)> {
+        if !self.config.filters.auto_filter_moved {
+            return None;
+        }
+        let moved = self.diff_result.move_of(chunk.id)?;
+        Some((moved, moved.residual_of(chunk.id)?))
+    }
+
     pub fn reviewable_chunks_count(&self) -> usize {
         self.scoring_result
             .as_ref()
@@ -935,9 +1614,10 @@ impl App {
         expanded
     }
 
-    /// Get highlights for a specific file (non-heuristic-filtered chunks with responses)
+    /// Highlights of a file, grouped by enclosing symbol in order of first appearance
     pub fn highlights_for_file(&self, file_index: usize) -> Vec<&ChunkScore> {
-        self.scoring_result
+        let mut highlights: Vec<&ChunkScore> = self
+            .scoring_result
             .as_ref()
             .map(|sr| {
                 sr.scores
@@ -949,7 +1629,31 @@ impl App {
                     })
                     .collect()
             })
-            .unwrap_or_default()
+            .unwrap_or_default();
+
+        let mut order: Vec<Option<&str>> = Vec::new();
+        for score in &highlights {
+            let symbol = self.chunk_symbol(score);
+            if !order.contains(&symbol) {
+                order.push(symbol);
+            }
+        }
+        highlights.sort_by_key(|s| {
+            let symbol = self.chunk_symbol(s);
+            order.iter().position(|o| *o == symbol)
+        });
+        highlights
+    }
+
+    /// Enclosing symbol of a scored chunk, when known
+    pub fn chunk_symbol(&self, score: &ChunkScore) -> Option<&str> {
+        self.diff_result
+            .files
+            .get(score.file_index)?
+            .chunks
+            .get(score.chunk_index)?
+            .symbol
+            .as_deref()
     }
 
     /// Build flat list of tree items for navigation
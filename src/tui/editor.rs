@@ -0,0 +1,216 @@
+use crate::error::{CraiError, CraiResult};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::process::Command;
+
+/// Multi-line text being edited, with a cursor. Columns count characters, not bytes.
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl TextArea {
+    /// Text to edit, with the cursor at its end
+    pub fn from_text(text: &str) -> Self {
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Row and character column of the cursor
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.lines.iter().all(|line| line.trim().is_empty())
+    }
+
+    /// Apply an editing or cursor key; returns false for keys it doesn't handle
+    pub fn input(&mut self, key: KeyEvent) -> bool {
+        let word = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert_char(c),
+            KeyCode::Enter => self.newline(),
+            KeyCode::Tab => self.insert_str("    "),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if word => self.word_left(),
+            KeyCode::Right if word => self.word_right(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Up => self.up(),
+            KeyCode::Down => self.down(),
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Insert text at the cursor, e.g. from a paste; line breaks start new lines
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        for (idx, part) in text.split('\n').enumerate() {
+            if idx > 0 {
+                self.newline();
+            }
+            for c in part.chars() {
+                self.insert_char(c);
+            }
+        }
+    }
+
+    fn insert_char(&mut self, c: char) {
+        let at = self.byte_col();
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+    }
+
+    fn newline(&mut self) {
+        let at = self.byte_col();
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.byte_col();
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let at = self.byte_col();
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+    }
+
+    fn right(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    fn up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.line_len());
+        }
+    }
+
+    fn down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.line_len());
+        }
+    }
+
+    /// To the start of the word before the cursor
+    fn word_left(&mut self) {
+        let chars: Vec<char> = self.lines[self.row].chars().collect();
+        let mut col = self.col;
+        while col > 0 && chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        while col > 0 && !chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        self.col = col;
+    }
+
+    /// To the end of the word after the cursor
+    fn word_right(&mut self) {
+        let chars: Vec<char> = self.lines[self.row].chars().collect();
+        let mut col = self.col;
+        while col < chars.len() && chars[col].is_whitespace() {
+            col += 1;
+        }
+        while col < chars.len() && !chars[col].is_whitespace() {
+            col += 1;
+        }
+        self.col = col;
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn byte_col(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(self.col).map_or(line.len(), |(idx, _)| idx)
+    }
+}
+
+/// Edit text in `$VISUAL` or `$EDITOR` (falling back to `vi`) through a temporary file.
+/// The terminal must be restored to normal mode while the editor runs.
+pub fn edit_externally(text: &str) -> CraiResult<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // The variable may carry arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let path = std::env::temp_dir().join(format!("crai-note-{}.md", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text)?;
+
+    let status = Command::new(program).args(parts).arg(&path).status();
+    let result = match status {
+        Ok(status) if status.success() => std::fs::read_to_string(&path).map_err(CraiError::from),
+        Ok(status) => Err(CraiError::Tui(format!("{} exited with {}", program, status))),
+        Err(e) => Err(CraiError::CliNotFound(format!("{}: {}", program, e))),
+    };
+    let _ = std::fs::remove_file(&path);
+
+    Ok(result?.trim_end().to_string())
+}
@@ -2,9 +2,11 @@ use crate::error::{CraiError, CraiResult};
 use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
 use std::time::Duration;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Event {
     Key(KeyEvent),
+    /// Text pasted into the terminal
+    Paste(String),
     Tick,
     Resize(u16, u16),
 }
@@ -24,6 +26,7 @@ impl EventHandler {
         if event::poll(self.tick_rate).map_err(|e| CraiError::Tui(e.to_string()))? {
             match event::read().map_err(|e| CraiError::Tui(e.to_string()))? {
                 CrosstermEvent::Key(key) => Ok(Event::Key(key)),
+                CrosstermEvent::Paste(text) => Ok(Event::Paste(text)),
                 CrosstermEvent::Resize(w, h) => Ok(Event::Resize(w, h)),
                 _ => Ok(Event::Tick),
             }
@@ -48,6 +51,8 @@ pub enum Action {
     Discuss,
     RequestChanges,
     AddNote,
+    Notes,
+    Delete,
     ToggleFilter,
     RunSubagent(SubagentAction),
     Stats,
@@ -58,6 +63,8 @@ pub enum Action {
     NextHighlight,
     PrevHighlight,
     ToggleSortMode,
+    NextCommit,
+    PrevCommit,
     None,
 }
 
@@ -120,6 +127,7 @@ impl Action {
             KeyCode::Char('a') => Action::Approve,
             KeyCode::Char('d') => Action::Discuss,
             KeyCode::Char('r') => Action::RequestChanges,
+            KeyCode::Char('c') => Action::AddNote,
             KeyCode::Char('s') => Action::Stats,
             KeyCode::Char('S') => Action::RunSubagent(SubagentAction::Security),
             KeyCode::Char('P') => Action::RunSubagent(SubagentAction::Performance),
@@ -128,10 +136,14 @@ impl Action {
             KeyCode::Char('N') => Action::PrevHighlight,
             KeyCode::Char('t') => Action::ToggleFilter,
             KeyCode::Char('o') => Action::ToggleSortMode,
+            KeyCode::Char(']') => Action::NextCommit,
+            KeyCode::Char('[') => Action::PrevCommit,
             KeyCode::Char('y') => Action::ConfirmYes,
             KeyCode::Char('1') => Action::Summary,
             KeyCode::Char('2') => Action::FocusTree,
             KeyCode::Char('3') => Action::FocusStream,
+            KeyCode::Char('4') => Action::Notes,
+            KeyCode::Char('x') | KeyCode::Delete => Action::Delete,
             _ => Action::None,
         }
     }
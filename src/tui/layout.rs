@@ -1,4 +1,5 @@
-use crate::tui::app::{App, MessageLevel, View};
+use crate::review::session::NoteTarget;
+use crate::tui::app::{App, MessageLevel, NoteEditor, View};
 use crate::tui::views;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
@@ -19,13 +20,30 @@ impl LayoutManager {
         Self::render_header(frame, main_layout[0], app);
         Self::render_content(frame, main_layout[1], app);
         Self::render_status_bar(frame, main_layout[2], app);
+
+        if let Some(editor) = &app.note_editor {
+            Self::render_note_editor(frame, main_layout[1], app, editor);
+        }
     }
 
     fn render_header(frame: &mut Frame, area: Rect, app: &App) {
-        let title = format!(
-            " CRAI - Code Review AI | {} -> {} ",
-            app.diff_result.base_branch, app.diff_result.compare_branch
-        );
+        let mut title = match app.current_commit() {
+            Some(commit) => format!(
+                " CRAI - Code Review AI | Commit {}/{} {} {} ",
+                app.current_commit + 1,
+                app.commit_steps.len(),
+                commit.short_sha(),
+                commit.summary
+            ),
+            None => format!(
+                " CRAI - Code Review AI | {} -> {} ",
+                app.diff_result.base_label(),
+                app.diff_result.compare_branch
+            ),
+        };
+        if let Some(profile) = &app.profile {
+            title.push_str(&format!("| profile {} ", profile));
+        }
 
         let header = Paragraph::new(title)
             .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
@@ -63,6 +81,7 @@ impl LayoutManager {
             }
             View::Stats => views::stats::render(frame, area, app),
             View::Help => views::help::render(frame, area),
+            View::Notes { selected } => views::notes::render(frame, area, app, *selected),
             View::QuitConfirm => {
                 // Render summary in background
                 views::summary::render(frame, area, app);
@@ -137,6 +156,70 @@ impl LayoutManager {
         frame.render_widget(dialog, dialog_area);
     }
 
+    fn render_note_editor(frame: &mut Frame, area: Rect, app: &App, editor: &NoteEditor) {
+        let dialog_width = area.width.saturating_sub(8).min(90);
+        let dialog_height = area.height.saturating_sub(4).min(16);
+        let x = area.x + (area.width.saturating_sub(dialog_width)) / 2;
+        let y = area.y + (area.height.saturating_sub(dialog_height)) / 2;
+        let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+        frame.render_widget(Clear, dialog_area);
+
+        let location = app.note_location(editor.file_index, editor.target);
+        let title = match editor.editing {
+            Some(_) => format!(" Edit note on {} ", location),
+            None => format!(" New {} note on {} ", editor.target.label(), location),
+        };
+        let mut keys = String::from(" [Ctrl+S] Save ");
+        if editor.editing.is_none() {
+            keys.push_str("[Ctrl+T] Attach to ");
+            if matches!(editor.target, NoteTarget::Line(..)) {
+                keys.push_str("[Alt+Up/Down] Line ");
+            }
+        }
+        keys.push_str("[Ctrl+E] $EDITOR [Esc] Cancel ");
+
+        let block = Block::default()
+            .title(title)
+            .title_bottom(Line::from(keys).right_aligned())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .style(Style::default().bg(Color::Black));
+        let mut inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        // The line a line note is about, as a reminder while writing
+        if let NoteTarget::Line(chunk_id, line) = editor.target {
+            let content = app
+                .files()
+                .get(editor.file_index)
+                .and_then(|file| file.chunks.iter().find(|c| c.id == chunk_id))
+                .and_then(|chunk| chunk.lines.iter().find(|l| l.new_line_num == Some(line)))
+                .map(|l| format!("{} {}", l.kind.prefix(), l.content))
+                .unwrap_or_default();
+            let quoted = Paragraph::new(format!("{:>5} | {}", line, content))
+                .style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(quoted, Rect { height: 1, ..inner });
+            inner.y += 1;
+            inner.height = inner.height.saturating_sub(1);
+        }
+
+        // Scroll so the cursor stays in view
+        let (row, col) = editor.text.cursor();
+        let scroll_rows = row.saturating_sub(inner.height.saturating_sub(1) as usize);
+        let scroll_cols = col.saturating_sub(inner.width.saturating_sub(1) as usize);
+        let body = Paragraph::new(editor.text.lines().join("\n"))
+            .scroll((scroll_rows as u16, scroll_cols as u16));
+        frame.render_widget(body, inner);
+
+        if inner.height > 0 {
+            frame.set_cursor_position((
+                inner.x + (col - scroll_cols) as u16,
+                inner.y + (row - scroll_rows) as u16,
+            ));
+        }
+    }
+
     fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
         let (text, style) = if let Some(ref progress) = app.progress {
             (
@@ -162,23 +245,39 @@ impl LayoutManager {
                 View::Summary => "[Enter] Review [1] Summary [s] Stats [?] Help [q] Quit",
                 View::Review { tree_focused, .. } => {
                     if *tree_focused {
-                        "[j/k] Navigate [Enter] Expand [3/Tab] Stream [1] Summary [Esc] Back"
+                        "[j/k] Navigate [Enter] Expand [c] Note [3/Tab] Stream [1] Summary [Esc] Back"
                     } else {
-                        "[j/k] Scroll [n/N] Highlights [o] Sort [2/Tab] Files [1] Summary"
+                        "[j/k] Scroll [n/N] Highlights [a/d/r] Mark [c] Note [4] Notes [o] Sort [2/Tab] Files"
                     }
                 }
                 View::Stats => "[1] Summary [Esc] Back [q] Quit",
                 View::Help => "[1] Summary [Esc] Back [q] Quit",
+                View::Notes { .. } => "[j/k] Navigate [Enter] Edit [x] Delete [Esc] Back",
                 View::QuitConfirm => "[q/y/Enter] Confirm quit [any key] Cancel",
             };
 
-            let stats = format!(
+            let keybinds = if app.commit_steps.len() > 1 {
+                format!("{} [[/]] Commit", keybinds)
+            } else {
+                keybinds.to_string()
+            };
+
+            let mut stats = format!(
                 "Files: {} | Chunks: {}/{} | Filtered: {} lines ",
                 app.diff_result.files.len(),
                 app.reviewable_chunks_count(),
                 app.total_chunks_count(),
                 app.filtered_lines_count(),
             );
+            if app.scoring_result.is_some() {
+                let progress = app.session.progress();
+                stats.push_str(&format!(
+                    "| Reviewed: {}/{} ({:.0}%) ",
+                    progress.reviewed_chunks,
+                    progress.total_chunks,
+                    progress.chunk_percentage()
+                ));
+            }
 
             (
                 format!(" {} | {}", keybinds, stats),
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod editor;
 pub mod event;
 pub mod layout;
 pub mod views;
@@ -8,6 +9,7 @@ pub use event::{Event, EventHandler};
 
 use crate::error::{CraiError, CraiResult};
 use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -19,7 +21,7 @@ pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 pub fn init_terminal() -> CraiResult<Tui> {
     enable_raw_mode().map_err(|e| CraiError::Terminal(e.to_string()))?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen).map_err(|e| CraiError::Terminal(e.to_string()))?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste).map_err(|e| CraiError::Terminal(e.to_string()))?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend).map_err(|e| CraiError::Terminal(e.to_string()))?;
     Ok(terminal)
@@ -27,6 +29,6 @@ pub fn init_terminal() -> CraiResult<Tui> {
 
 pub fn restore_terminal() -> CraiResult<()> {
     disable_raw_mode().map_err(|e| CraiError::Terminal(e.to_string()))?;
-    execute!(io::stdout(), LeaveAlternateScreen).map_err(|e| CraiError::Terminal(e.to_string()))?;
+    execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen).map_err(|e| CraiError::Terminal(e.to_string()))?;
     Ok(())
 }
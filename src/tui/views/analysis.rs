@@ -1,4 +1,6 @@
 use crate::ai::schema::{ChangeClassification, Severity};
+use crate::diff::chunk::ChunkId;
+use crate::diff::filter::FilterReason;
 use crate::tui::app::App;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
@@ -50,7 +52,7 @@ pub fn render_compact(frame: &mut Frame, area: Rect, app: &App, file_index: usiz
     } else if let Some(s) = score {
         if let Some(ref filter) = s.filter_result {
             if filter.is_filtered {
-                vec![
+                let mut lines = vec![
                     Line::from(Span::styled(
                         "Filtered",
                         Style::default().fg(Color::DarkGray),
@@ -63,7 +65,15 @@ pub fn render_compact(frame: &mut Frame, area: Rect, app: &App, file_index: usiz
                             .map(|r| r.description())
                             .unwrap_or("Unknown")
                     )),
-                ]
+                ];
+                if let Some(FilterReason::Moved { source, destination }) = filter.reason {
+                    lines.push(Line::from(format!(
+                        "Moved: {} -> {}",
+                        chunk_location(app, source, false),
+                        chunk_location(app, destination, true)
+                    )));
+                }
+                lines
             } else {
                 vec![Line::from("Analysis pending...")]
             }
@@ -169,3 +179,20 @@ fn score_style(score: f64) -> Style {
         Style::default().fg(Color::DarkGray)
     }
 }
+
+/// "path:line" of a chunk, on the new side or the old one
+fn chunk_location(app: &App, chunk_id: ChunkId, new_side: bool) -> String {
+    app.diff_result
+        .files
+        .iter()
+        .find_map(|file| {
+            let chunk = file.chunks.iter().find(|c| c.id == chunk_id)?;
+            let range = if new_side { chunk.new_range } else { chunk.old_range };
+            let path = match (&file.old_path, new_side) {
+                (Some(old_path), false) => old_path,
+                _ => &file.path,
+            };
+            Some(format!("{}:{}", path.display(), range.start))
+        })
+        .unwrap_or_else(|| "?".to_string())
+}
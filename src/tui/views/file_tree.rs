@@ -1,5 +1,6 @@
 use crate::ai::scoring::ChunkScore;
-use crate::diff::chunk::FileStatus;
+use crate::diff::chunk::{format_bytes, ContentKind, FileStatus};
+use crate::review::session::UserChunkStatus;
 use crate::tui::app::App;
 use crate::tui::event::StreamSortMode;
 use crate::tui::views::stream::get_sorted_highlights;
@@ -7,10 +8,12 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 use std::collections::HashSet;
 
-/// Tree item - either a file or a highlight
+/// Tree item - a file, the symbol heading a group of highlights, or a highlight
 #[derive(Debug, Clone)]
 enum TreeItem {
     File { index: usize },
+    /// Not selectable; labelled with the symbol of the highlight that follows it
+    Symbol { file_index: usize, highlight_index: usize },
     Highlight { file_index: usize, highlight_index: usize },
 }
 
@@ -124,9 +127,11 @@ fn render_highlight_item<'a>(
         Style::default().fg(Color::Green)
     };
 
-    // Format: [XX%] filename: reasoning preview...
+    // Format: S [XX%] filename: reasoning preview..., S being the review status
+    let review_status = app.chunk_status(score);
+    let status_prefix = format!("{} ", review_status.symbol());
     let prefix = format!("[{:>2.0}%] ", score_val * 100.0);
-    let prefix_len = prefix.chars().count();
+    let prefix_len = status_prefix.len() + prefix.chars().count();
 
     // Calculate space for filename and reasoning
     let remaining_width = sidebar_width.saturating_sub(prefix_len);
@@ -145,6 +150,7 @@ fn render_highlight_item<'a>(
     };
 
     let line = Line::from(vec![
+        Span::styled(status_prefix, review_status_style(review_status)),
         Span::styled(prefix, score_style),
         Span::styled(truncated_filename, Style::default().fg(Color::Blue)),
         Span::styled(": ", Style::default().fg(Color::DarkGray)),
@@ -188,10 +194,19 @@ fn render_file_tree(
     for (file_idx, _file) in app.diff_result.files.iter().enumerate() {
         tree_items.push(TreeItem::File { index: file_idx });
 
-        // If expanded, add highlight children
+        // If expanded, add highlight children under a heading per symbol
         if expanded_files.contains(&file_idx) {
             let highlights = app.highlights_for_file(file_idx);
-            for (h_idx, _) in highlights.iter().enumerate() {
+            let mut current_symbol = None;
+            for (h_idx, score) in highlights.iter().enumerate() {
+                let symbol = app.chunk_symbol(score);
+                if symbol.is_some() && symbol != current_symbol {
+                    tree_items.push(TreeItem::Symbol {
+                        file_index: file_idx,
+                        highlight_index: h_idx,
+                    });
+                }
+                current_symbol = symbol;
                 tree_items.push(TreeItem::Highlight {
                     file_index: file_idx,
                     highlight_index: h_idx,
@@ -206,6 +221,7 @@ fn render_file_tree(
         .enumerate()
         .find(|(_, item)| match item {
             TreeItem::File { index } => *index == selected_file && selected_highlight.is_none(),
+            TreeItem::Symbol { .. } => false,
             TreeItem::Highlight { file_index, highlight_index } => {
                 *file_index == selected_file && Some(*highlight_index) == selected_highlight
             }
@@ -231,7 +247,7 @@ fn render_file_tree(
                         FileStatus::Deleted => 'D',
                         FileStatus::Modified => 'M',
                         FileStatus::Renamed { .. } => 'R',
-                        FileStatus::Copied => 'C',
+                        FileStatus::Copied { .. } => 'C',
                     };
 
                     let status_style = match file.status {
@@ -239,7 +255,7 @@ fn render_file_tree(
                         FileStatus::Deleted => Style::default().fg(Color::Red),
                         FileStatus::Modified => Style::default().fg(Color::Yellow),
                         FileStatus::Renamed { .. } => Style::default().fg(Color::Blue),
-                        FileStatus::Copied => Style::default().fg(Color::Cyan),
+                        FileStatus::Copied { .. } => Style::default().fg(Color::Cyan),
                     };
 
                     let expand_char = if highlights.is_empty() {
@@ -262,13 +278,41 @@ fn render_file_tree(
                         format!(" ({})", highlights.len())
                     };
 
-                    let max_name_chars = sidebar_width.saturating_sub(6 + highlight_count.len());
+                    let mode_label = file
+                        .mode_change
+                        .map(|m| format!(" {}", m.short_label()))
+                        .unwrap_or_default();
+
+                    let kind_label = if file.oversized.is_some() {
+                        " [oversized]".to_string()
+                    } else if file.content_kind.is_text() {
+                        String::new()
+                    } else {
+                        format!(" [{}]", file.content_kind.label())
+                    };
+
+                    // First owner only; the file list shows them all
+                    let owner_label = match file.owners.as_slice() {
+                        [] => String::new(),
+                        [owner] => format!(" {}", owner),
+                        [owner, rest @ ..] => format!(" {}+{}", owner, rest.len()),
+                    };
+
+                    let review_status = app.session.file_status(*index);
+
+                    let max_name_chars = sidebar_width.saturating_sub(
+                        8 + highlight_count.len() + mode_label.len() + kind_label.len() + owner_label.chars().count(),
+                    );
                     let truncated = truncate_str(filename, max_name_chars);
 
                     let line = Line::from(vec![
                         Span::raw(format!("{} ", expand_char)),
+                        Span::styled(format!("{} ", review_status.symbol()), Style::default().fg(Color::Green)),
                         Span::styled(format!("{} ", status_char), status_style),
                         Span::raw(truncated),
+                        Span::styled(kind_label, Style::default().fg(Color::Cyan)),
+                        Span::styled(mode_label, Style::default().fg(Color::Magenta)),
+                        Span::styled(owner_label, Style::default().fg(Color::Yellow)),
                         Span::styled(highlight_count, Style::default().fg(Color::DarkGray)),
                     ]);
 
@@ -283,6 +327,18 @@ fn render_file_tree(
 
                     ListItem::new(line).style(item_style)
                 }
+                TreeItem::Symbol { file_index, highlight_index } => {
+                    let highlights = app.highlights_for_file(*file_index);
+                    let symbol = highlights
+                        .get(*highlight_index)
+                        .and_then(|score| app.chunk_symbol(score))
+                        .unwrap_or_default();
+                    let truncated = truncate_str(symbol, sidebar_width.saturating_sub(4));
+                    ListItem::new(Line::from(vec![
+                        Span::raw("    "),
+                        Span::styled(truncated, Style::default().fg(Color::Blue)),
+                    ]))
+                }
                 TreeItem::Highlight { file_index, highlight_index } => {
                     let highlights = app.highlights_for_file(*file_index);
                     let chunk_score = highlights.get(*highlight_index);
@@ -305,11 +361,21 @@ fn render_file_tree(
                         Style::default().fg(Color::Green)
                     };
 
-                    let max_reason_chars = sidebar_width.saturating_sub(12);
+                    // Highlights under a symbol heading sit one level deeper
+                    let indent = if chunk_score.and_then(|s| app.chunk_symbol(s)).is_some() {
+                        "      "
+                    } else {
+                        "    "
+                    };
+
+                    let review_status = chunk_score.map(|s| app.chunk_status(s)).unwrap_or_default();
+
+                    let max_reason_chars = sidebar_width.saturating_sub(10 + indent.len());
                     let truncated_reason = truncate_str(&reasoning, max_reason_chars);
 
                     let line = Line::from(vec![
-                        Span::raw("    "),
+                        Span::raw(indent),
+                        Span::styled(format!("{} ", review_status.symbol()), review_status_style(review_status)),
                         Span::styled(format!("[{:>2.0}%] ", score * 100.0), score_style),
                         Span::raw(truncated_reason),
                     ]);
@@ -346,6 +412,16 @@ fn render_file_tree(
     frame.render_stateful_widget(list, area, &mut state);
 }
 
+pub fn review_status_style(status: UserChunkStatus) -> Style {
+    match status {
+        UserChunkStatus::Unreviewed | UserChunkStatus::Viewed => Style::default().fg(Color::DarkGray),
+        UserChunkStatus::Approved => Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        UserChunkStatus::NeedsDiscussion => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        UserChunkStatus::RequestedChanges => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        UserChunkStatus::NeedsReReview => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+    }
+}
+
 fn truncate_str(s: &str, max_chars: usize) -> String {
     let char_count = s.chars().count();
     if char_count <= max_chars {
@@ -370,7 +446,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, selected: usize, _scroll
                 FileStatus::Deleted => 'D',
                 FileStatus::Modified => 'M',
                 FileStatus::Renamed { .. } => 'R',
-                FileStatus::Copied => 'C',
+                FileStatus::Copied { .. } => 'C',
             };
 
             let status_style = match file.status {
@@ -378,7 +454,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, selected: usize, _scroll
                 FileStatus::Deleted => Style::default().fg(Color::Red),
                 FileStatus::Modified => Style::default().fg(Color::Yellow),
                 FileStatus::Renamed { .. } => Style::default().fg(Color::Blue),
-                FileStatus::Copied => Style::default().fg(Color::Cyan),
+                FileStatus::Copied { .. } => Style::default().fg(Color::Cyan),
             };
 
             let chunk_count = file.chunks.len();
@@ -401,16 +477,57 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, selected: usize, _scroll
                 }
             });
 
-            let line = Line::from(vec![
+            let status_detail = match file.status {
+                FileStatus::Renamed { similarity_percent }
+                | FileStatus::Copied { similarity_percent } => {
+                    format!(", {}% similar", similarity_percent)
+                }
+                _ => String::new(),
+            };
+
+            let mode_info = file
+                .mode_change
+                .map(|m| format!(", mode {}", m.short_label()))
+                .unwrap_or_default();
+
+            // Oversized, binary, submodule and symlink entries have no chunks; describe them instead
+            let content_info = if let Some(size) = file.oversized {
+                format!("oversized, {}, skipped", format_bytes(size))
+            } else if file.content_kind.is_text() {
+                format!("{} chunks, {} changes", chunk_count, changes)
+            } else {
+                file.content_kind.describe()
+            };
+
+            let mut spans = vec![
                 Span::styled(format!(" {} ", status_char), status_style),
                 Span::raw(format!(
-                    "{} ({} chunks, {} changes){}",
-                    file.path.display(),
-                    chunk_count,
-                    changes,
+                    "{} ({}{}{}){}",
+                    file.path_label(),
+                    content_info,
+                    status_detail,
+                    mode_info,
                     score_info.unwrap_or_default(),
                 )),
-            ]);
+            ];
+
+            if !file.owners.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", file.owners.join(" ")),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+
+            if let ContentKind::Submodule { commits, .. } = &file.content_kind {
+                if let Some(latest) = commits.first() {
+                    spans.push(Span::styled(
+                        format!("  latest: {}", latest),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+
+            let line = Line::from(spans);
 
             let item_style = if idx == selected {
                 Style::default()
@@ -9,6 +9,7 @@ QUICK ACCESS
 1                Summary view
 2                Focus file tree
 3                Focus highlights stream
+4                Notes panel
 s                Statistics view
 ?                This help screen
 
@@ -24,14 +25,35 @@ g                Jump to start
 Ctrl+F, PgDn     Page down
 Ctrl+B, PgUp     Page up
 Esc              Go back to summary
+[ / ]            Previous/Next commit (--commit, --per-commit)
 
 REVIEW ACTIONS
 ──────────────
 a                Approve current highlight
 d                Mark for discussion
 r                Request changes
+                 (repeat the key to clear the mark)
+c                Add a note to the current highlight, or to
+                 the file selected in the file tree
 t                Toggle filtered chunks
 
+The current highlight is the one selected in the file
+tree, or the one at the top of the stream. Marks show as
++ approved, ? discussion, ! changes in the tree; files
+show + once every highlight is marked, ~ while some are.
+Set tui.auto_advance to jump to the next unmarked one.
+Marks and notes are saved on quit. * marks a highlight
+reviewed in a resumed session whose content has changed.
+
+NOTES
+─────
+Ctrl+S           Save the note being written
+Ctrl+T           Attach it to the chunk, a line or the file
+Alt+Up/Down      Pick the line, for a line note
+Ctrl+E           Continue in $VISUAL or $EDITOR
+Esc              Discard the note
+Enter, x         In the notes panel: edit, delete
+
 SPECIALIZED REVIEWS
 ───────────────────
 Shift+S          Run security review
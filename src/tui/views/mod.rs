@@ -2,6 +2,7 @@ pub mod analysis;
 pub mod diff;
 pub mod file_tree;
 pub mod help;
+pub mod notes;
 pub mod stats;
 pub mod stream;
 pub mod summary;
@@ -0,0 +1,78 @@
+use crate::review::session::NoteTarget;
+use crate::tui::app::App;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::time::SystemTime;
+
+/// Lines of a note shown in the list before it is cut short
+const PREVIEW_LINES: usize = 3;
+
+pub fn render(frame: &mut Frame, area: Rect, app: &App, selected: usize) {
+    let notes = app.session.notes();
+    let block = Block::default()
+        .title(format!(" (4) Notes ({}) ", notes.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if notes.is_empty() {
+        let empty = Paragraph::new("No notes yet. Press c on a highlight, or on a file in the file tree, to add one.")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = notes
+        .iter()
+        .map(|entry| {
+            let kind_style = match entry.target {
+                NoteTarget::File => Style::default().fg(Color::Magenta),
+                NoteTarget::Chunk(_) => Style::default().fg(Color::Cyan),
+                NoteTarget::Line(..) => Style::default().fg(Color::Yellow),
+            };
+            let mut lines = vec![Line::from(vec![
+                Span::styled(format!("[{}] ", entry.target.label()), kind_style),
+                Span::styled(
+                    app.note_location(entry.note_ref.file_index, entry.target),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {}", format_age(entry.note.created_at)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])];
+
+            let text_lines: Vec<&str> = entry.note.text.lines().collect();
+            for line in text_lines.iter().take(PREVIEW_LINES) {
+                lines.push(Line::from(format!("    {}", line)));
+            }
+            if text_lines.len() > PREVIEW_LINES {
+                lines.push(Line::styled(
+                    format!("    ... {} more lines", text_lines.len() - PREVIEW_LINES),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(""));
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block.title_bottom(Line::from(format!(" {}/{} ", selected + 1, notes.len())).right_aligned()))
+        .highlight_style(Style::default().bg(Color::DarkGray));
+
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// How long ago a wall-clock time was, e.g. "3h ago"
+pub fn format_age(time: SystemTime) -> String {
+    let secs = time.elapsed().unwrap_or_default().as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
@@ -38,6 +38,14 @@ fn render_filter_breakdown(frame: &mut Frame, area: Rect, app: &App) {
             .value(stats.whitespace_lines as u64)
             .label("Whitespace".into())
             .style(Style::default().fg(Color::Gray)),
+        Bar::default()
+            .value(stats.formatting_lines as u64)
+            .label("Formatting".into())
+            .style(Style::default().fg(Color::Gray)),
+        Bar::default()
+            .value(stats.comment_lines as u64)
+            .label("Comments".into())
+            .style(Style::default().fg(Color::DarkGray)),
         Bar::default()
             .value(stats.import_lines as u64)
             .label("Imports".into())
@@ -46,14 +54,34 @@ fn render_filter_breakdown(frame: &mut Frame, area: Rect, app: &App) {
             .value(stats.rename_lines as u64)
             .label("Renames".into())
             .style(Style::default().fg(Color::Cyan)),
+        Bar::default()
+            .value(stats.moved_lines as u64)
+            .label("Moved".into())
+            .style(Style::default().fg(Color::LightBlue)),
         Bar::default()
             .value(stats.generated_lines as u64)
             .label("Generated".into())
             .style(Style::default().fg(Color::Magenta)),
+        Bar::default()
+            .value(stats.test_lines as u64)
+            .label("Tests".into())
+            .style(Style::default().fg(Color::LightGreen)),
+        Bar::default()
+            .value(stats.docs_lines as u64)
+            .label("Docs".into())
+            .style(Style::default().fg(Color::LightYellow)),
+        Bar::default()
+            .value(stats.config_lines as u64)
+            .label("Config".into())
+            .style(Style::default().fg(Color::LightMagenta)),
         Bar::default()
             .value(stats.below_threshold_lines as u64)
             .label("Low Score".into())
             .style(Style::default().fg(Color::Green)),
+        Bar::default()
+            .value(stats.unchanged_lines as u64)
+            .label("Unchanged".into())
+            .style(Style::default().fg(Color::DarkGray)),
     ];
 
     let bar_chart = BarChart::default()
@@ -1,8 +1,11 @@
 use crate::ai::schema::{ChangeClassification, Severity};
 use crate::ai::scoring::ChunkScore;
-use crate::diff::chunk::{FileStatus, LineKind};
+use crate::diff::chunk::{ChunkId, FileStatus, LineKind};
+use crate::diff::interdiff::HunkChange;
+use crate::review::session::UserChunkStatus;
 use crate::tui::app::App;
 use crate::tui::event::StreamSortMode;
+use crate::tui::views::file_tree::review_status_style;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
@@ -278,12 +281,18 @@ fn calculate_highlight_height(app: &App, score: &ChunkScore, content_width: usiz
             }
         }
 
+        if !score.integrity_flags.is_empty() {
+            height += 1; // Blank
+            height += score.integrity_flags.len();
+        }
+
         height += 1; // Blank after analysis
     }
 
     // "Changes:" header + diff lines
     height += 1; // "Changes:" header
-    height += chunk.lines.len();
+    let shown = app.moved_residual(chunk).map_or(chunk, |(_, residual)| residual);
+    height += shown.lines.len();
 
     // Separator: 2 lines
     height += 2;
@@ -321,7 +330,7 @@ fn render_highlight_block<'a>(
         FileStatus::Deleted => 'D',
         FileStatus::Modified => 'M',
         FileStatus::Renamed { .. } => 'R',
-        FileStatus::Copied => 'C',
+        FileStatus::Copied { .. } => 'C',
     };
 
     let status_style = match file.status {
@@ -329,7 +338,7 @@ fn render_highlight_block<'a>(
         FileStatus::Deleted => Style::default().fg(Color::Red),
         FileStatus::Modified => Style::default().fg(Color::Yellow),
         FileStatus::Renamed { .. } => Style::default().fg(Color::Blue),
-        FileStatus::Copied => Style::default().fg(Color::Cyan),
+        FileStatus::Copied { .. } => Style::default().fg(Color::Cyan),
     };
 
     let score_style = if resp.score >= 0.7 {
@@ -347,7 +356,7 @@ fn render_highlight_block<'a>(
         ),
         Span::styled(format!("{} ", status_char), status_style),
         Span::styled(
-            file.path.display().to_string(),
+            file.path_label(),
             Style::default().add_modifier(Modifier::BOLD),
         ),
         Span::raw(format!(
@@ -355,12 +364,28 @@ fn render_highlight_block<'a>(
             chunk.new_range.start,
             chunk.new_range.start + chunk.new_range.count
         )),
+        Span::styled(
+            chunk.symbol.as_deref().map(|s| format!("{} ", s)).unwrap_or_default(),
+            Style::default().fg(Color::Blue),
+        ),
         Span::styled(format!("[{:.0}%]", resp.score * 100.0), score_style),
+        Span::styled(
+            score
+                .unweighted_score
+                .map(|s| format!(" (weighted, AI {:.0}%)", s * 100.0))
+                .unwrap_or_default(),
+            Style::default().fg(Color::DarkGray),
+        ),
         Span::raw(" "),
         Span::styled(
             format!("{}", resp.classification),
             classification_style(&resp.classification),
         ),
+        Span::styled(
+            interdiff_tag(app, chunk.id),
+            Style::default().fg(Color::Magenta),
+        ),
+        Span::styled(review_tag(app, score), review_status_style(app.chunk_status(score))),
     ]));
 
     lines.push(Line::from(Span::styled(
@@ -433,16 +458,47 @@ fn render_highlight_block<'a>(
         }
     }
 
+    // Integrity warnings (prompt injection, contradictory responses)
+    if !score.integrity_flags.is_empty() {
+        lines.push(Line::from(""));
+        for flag in &score.integrity_flags {
+            lines.push(Line::from(Span::styled(
+                truncate_line(&format!("  ⚠ {}", flag.description()), content_width),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+        }
+    }
+
     lines.push(Line::from(""));
 
     // === SIDE-BY-SIDE DIFF (shown after analysis) ===
+    // Code moved with edits shows only the edits, against where it came from; a chunk that
+    // code moved out of shows what else it changes
+    let (changes_title, shown) = match app.moved_residual(chunk) {
+        Some((moved, residual)) => {
+            let file_path = |file_idx: usize| {
+                app.diff_result
+                    .files
+                    .get(file_idx)
+                    .map(|f| f.path.display().to_string())
+                    .unwrap_or_default()
+            };
+            let title = if moved.destination == chunk.id {
+                format!("Changes while moving from {}:", file_path(moved.source_file))
+            } else {
+                format!("Changes besides the code moved to {}:", file_path(moved.destination_file))
+            };
+            (title, residual)
+        }
+        None => ("Changes:".to_string(), chunk),
+    };
     lines.push(Line::from(Span::styled(
-        "Changes:",
+        changes_title,
         Style::default()
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD),
     )));
-    lines.extend(render_side_by_side_diff(chunk, app.config.tui.show_line_numbers, content_width));
+    lines.extend(render_side_by_side_diff(shown, app.config.tui.show_line_numbers, content_width));
 
     // === SEPARATOR ===
     lines.push(Line::from(""));
@@ -533,6 +589,39 @@ fn render_side_by_side_diff<'a>(
     lines
 }
 
+/// The reviewer's status and note count, e.g. " [approved, 2 notes]"; empty before either is set
+fn review_tag(app: &App, score: &ChunkScore) -> String {
+    let status = app.chunk_status(score);
+    let notes = app
+        .session
+        .chunk_state(score.file_index, score.chunk_id)
+        .map_or(0, |state| state.notes.len());
+
+    let mut parts = Vec::new();
+    if status != UserChunkStatus::Unreviewed {
+        parts.push(status.label().to_string());
+    }
+    match notes {
+        0 => {}
+        1 => parts.push("1 note".to_string()),
+        n => parts.push(format!("{} notes", n)),
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", parts.join(", "))
+    }
+}
+
+/// " [new]" or " [changed]" when reviewing the changes since an earlier revision
+fn interdiff_tag(app: &App, chunk_id: ChunkId) -> &'static str {
+    match app.diff_result.interdiff.as_ref().map(|i| i.change(chunk_id)) {
+        Some(HunkChange::New) => " [new]",
+        Some(HunkChange::Changed { .. }) => " [changed]",
+        _ => "",
+    }
+}
+
 fn truncate_line(line: &str, max_chars: usize) -> String {
     let char_count = line.chars().count();
     if char_count <= max_chars {
@@ -1,3 +1,4 @@
+use crate::diff::chunk::{format_bytes, ContentKind};
 use crate::tui::app::App;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
@@ -5,12 +6,14 @@ use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
 pub fn render(frame: &mut Frame, area: Rect, app: &App) {
     // Adjust layout based on whether we have AI summary
     let has_summary = app.summary.is_some();
+    // Leave room for the commit message when stepping through commits
+    let overview_height = if app.current_commit().is_some() { 12 } else { 8 };
 
     let layout = if has_summary {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(8),  // Overview
+                Constraint::Length(overview_height), // Overview
                 Constraint::Length(8),  // Key changes (from AI)
                 Constraint::Length(8),  // Key concerns (from scoring)
                 Constraint::Min(0),     // Statistics
@@ -21,7 +24,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(8),  // Overview
+                Constraint::Length(overview_height), // Overview
                 Constraint::Length(10), // Key concerns
                 Constraint::Min(0),     // Statistics
             ])
@@ -42,6 +45,20 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn render_overview(frame: &mut Frame, area: Rect, app: &App) {
+    let commit_text = app
+        .current_commit()
+        .map(|commit| {
+            format!(
+                "Commit {}/{} {} by {}\n{}\n\n",
+                app.current_commit + 1,
+                app.commit_steps.len(),
+                commit.short_sha(),
+                commit.author,
+                commit.message
+            )
+        })
+        .unwrap_or_default();
+
     let overview_text = if let Some(ref summary) = app.summary {
         // Include risk assessment in the overview
         let risk_info = format!(
@@ -56,13 +73,15 @@ fn render_overview(frame: &mut Frame, area: Rect, app: &App) {
             "Comparing {} -> {}\n\n\
              {} files changed with {} reviewable chunks.\n\n\
              Press [Enter] to start reviewing or [s] for detailed statistics.",
-            app.diff_result.base_branch,
+            app.diff_result.base_label(),
             app.diff_result.compare_branch,
             app.diff_result.files.len(),
             app.reviewable_chunks_count(),
         )
     };
 
+    let overview_text = format!("{}{}", commit_text, overview_text);
+
     let paragraph = Paragraph::new(overview_text)
         .block(
             Block::default()
@@ -139,7 +158,17 @@ fn truncate_path(path: &str) -> String {
 }
 
 fn render_concerns(frame: &mut Frame, area: Rect, app: &App) {
-    let items: Vec<ListItem> = if let Some(ref scoring) = app.scoring_result {
+    // Raised from the paths alone, so it shows before any AI analysis
+    let missing_tests = (!app.untested_source.is_empty()).then(|| {
+        let text = format!(
+            "Source changed but no tests changed ({} file{})",
+            app.untested_source.len(),
+            if app.untested_source.len() == 1 { "" } else { "s" }
+        );
+        ListItem::new(text).style(Style::default().fg(Color::Yellow))
+    });
+
+    let mut items: Vec<ListItem> = if let Some(ref scoring) = app.scoring_result {
         // Find high-scoring chunks
         let mut concerns: Vec<_> = scoring
             .scores
@@ -189,6 +218,22 @@ fn render_concerns(frame: &mut Frame, area: Rect, app: &App) {
     } else {
         vec![ListItem::new("Run AI analysis to identify key concerns...")]
     };
+    let flagged_dependencies = app
+        .diff_result
+        .dependencies
+        .as_ref()
+        .map_or(0, |deps| deps.flagged().count());
+    if flagged_dependencies > 0 {
+        let text = format!(
+            "{} dependency change{} with a major bump or new registry",
+            flagged_dependencies,
+            if flagged_dependencies == 1 { "" } else { "s" }
+        );
+        items.insert(0, ListItem::new(text).style(Style::default().fg(Color::Yellow)));
+    }
+    if let Some(item) = missing_tests {
+        items.insert(0, item);
+    }
 
     let list = List::new(items).block(
         Block::default()
@@ -211,7 +256,11 @@ fn render_statistics(frame: &mut Frame, area: Rect, app: &App) {
              Lines filtered: {} ({:.1}%)\n\n\
              Filter breakdown:\n\
                Whitespace only:    {:<8} Import changes: {}\n\
-               Auto-generated:     {:<8} Below threshold: {}\n\n\
+               Formatting only:    {:<8} Comment only: {}\n\
+               Moved:              {:<8} Unchanged: {}\n\
+               Auto-generated:     {:<8} Below threshold: {}\n\
+               Tests:              {:<8} Docs: {}\n\
+               Configuration:      {}\n\n\
              Average score: {:.2}    Max score: {:.2}",
             app.diff_result.files.len(),
             scoring.reviewable_count(),
@@ -221,8 +270,15 @@ fn render_statistics(frame: &mut Frame, area: Rect, app: &App) {
             filter_pct,
             stats.whitespace_lines,
             stats.import_lines,
+            stats.formatting_lines,
+            stats.comment_lines,
+            stats.moved_lines,
+            stats.unchanged_lines,
             stats.generated_lines,
             stats.below_threshold_lines,
+            stats.test_lines,
+            stats.docs_lines,
+            stats.config_lines,
             scoring.average_score().unwrap_or(0.0),
             scoring.max_score().unwrap_or(0.0),
         )
@@ -236,6 +292,15 @@ fn render_statistics(frame: &mut Frame, area: Rect, app: &App) {
         )
     };
 
+    let stats_text = format!(
+        "{}{}{}{}{}",
+        stats_text,
+        interdiff_changes(app),
+        dependency_changes(app),
+        non_text_changes(app),
+        oversized_files(app)
+    );
+
     let paragraph = Paragraph::new(stats_text)
         .block(
             Block::default()
@@ -248,6 +313,104 @@ fn render_statistics(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
+/// Hunk counts against the `--since` revision, and the hunks that disappeared since
+fn interdiff_changes(app: &App) -> String {
+    let Some(interdiff) = &app.diff_result.interdiff else {
+        return String::new();
+    };
+
+    let mut text = format!(
+        "\n\nSince {}: {} new, {} changed, {} unchanged (hidden), {} disappeared",
+        interdiff.since,
+        interdiff.new_count(),
+        interdiff.changed_count(),
+        interdiff.unchanged_count(),
+        interdiff.removed.len()
+    );
+    for hunk in &interdiff.removed {
+        text.push_str(&format!(
+            "\n  - {}:{} {}",
+            hunk.path.display(),
+            hunk.chunk.new_range.start,
+            hunk.chunk.header
+        ));
+    }
+    text
+}
+
+/// Dependency change counts, listing the major bumps, new registries and unparsed manifests
+fn dependency_changes(app: &App) -> String {
+    let Some(deps) = &app.diff_result.dependencies else {
+        return String::new();
+    };
+
+    let mut text = format!("\n\nDependencies: {}", deps.describe());
+    for change in deps.flagged() {
+        text.push_str(&format!(
+            "\n  {} [{}] in {}",
+            change.describe(),
+            change.flags(),
+            change.manifest.display()
+        ));
+    }
+    for error in &deps.errors {
+        text.push_str(&format!("\n  {}", error.describe()));
+    }
+    text
+}
+
+/// Binary, submodule and symlink changes, which have no chunks to review
+fn non_text_changes(app: &App) -> String {
+    const MAX_LISTED_COMMITS: usize = 5;
+
+    let mut text = String::new();
+    for file in app.diff_result.files.iter().filter(|f| !f.content_kind.is_text()) {
+        if text.is_empty() {
+            text.push_str("\n\nNon-text changes:");
+        }
+        text.push_str(&format!(
+            "\n  {} {}: {}",
+            file.status,
+            file.path.display(),
+            file.content_kind.describe()
+        ));
+
+        if let ContentKind::Submodule { commits, .. } = &file.content_kind {
+            for commit in commits.iter().take(MAX_LISTED_COMMITS) {
+                text.push_str(&format!("\n      {}", commit));
+            }
+            if commits.len() > MAX_LISTED_COMMITS {
+                text.push_str(&format!(
+                    "\n      ... and {} more",
+                    commits.len() - MAX_LISTED_COMMITS
+                ));
+            }
+        }
+    }
+    text
+}
+
+/// Files skipped because they exceed `max_file_size_bytes`
+fn oversized_files(app: &App) -> String {
+    let mut text = String::new();
+    for file in &app.diff_result.files {
+        let Some(size) = file.oversized else { continue };
+        if text.is_empty() {
+            text.push_str(&format!(
+                "\n\nSkipped (over {}):",
+                format_bytes(app.config.diff.max_file_size_bytes)
+            ));
+        }
+        text.push_str(&format!(
+            "\n  {} {}: oversized, {}",
+            file.status,
+            file.path.display(),
+            format_bytes(size)
+        ));
+    }
+    text
+}
+
 fn count_additions(app: &App) -> usize {
     app.diff_result
         .files
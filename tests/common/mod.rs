@@ -0,0 +1,49 @@
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// A scratch repository built with the git CLI
+pub struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let fixture = Self {
+            dir: TempDir::new().expect("temp dir"),
+        };
+        fixture.git(&["init", "-q"]);
+        fixture.git(&["config", "user.name", "crai"]);
+        fixture.git(&["config", "user.email", "crai@example.com"]);
+        fixture.git(&["config", "core.autocrlf", "false"]);
+        fixture
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn git(&self, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(self.path())
+            .args(args)
+            .output()
+            .expect("git runs");
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    pub fn write(&self, path: &str, contents: &[u8]) {
+        let full_path = self.path().join(path);
+        if let Some(dir) = full_path.parent() {
+            std::fs::create_dir_all(dir).expect("create dir");
+        }
+        std::fs::write(full_path, contents).expect("write file");
+    }
+
+    pub fn commit(&self, message: &str) {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "-m", message]);
+    }
+}
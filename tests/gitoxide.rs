@@ -0,0 +1,135 @@
+#![cfg(feature = "gitoxide")]
+
+use crai::diff::git::GitOperations;
+use crai::diff::gitoxide::Gitoxide;
+use crai::diff::vcs::Vcs;
+
+mod common;
+
+use common::Fixture;
+
+fn backends(fixture: &Fixture) -> (GitOperations, Gitoxide) {
+    (
+        GitOperations::new(fixture.path().to_path_buf()),
+        Gitoxide::open(fixture.path()).expect("open with gitoxide"),
+    )
+}
+
+/// Both backends must produce the same diff text for the last commit
+async fn assert_same_commit_diff(fixture: &Fixture) {
+    let (git, gix) = backends(fixture);
+    for ignore_whitespace in [false, true] {
+        let expected = git
+            .get_unified_diff("HEAD~1", "HEAD", 3, ignore_whitespace)
+            .await
+            .expect("git diff");
+        assert!(!expected.is_empty(), "fixture commit has no diff");
+        let actual = gix
+            .get_unified_diff("HEAD~1", "HEAD", 3, ignore_whitespace)
+            .await
+            .expect("gitoxide diff");
+        assert_eq!(actual, expected);
+    }
+}
+
+/// Both backends must produce the same staged and unstaged diff text
+async fn assert_same_local_diffs(fixture: &Fixture) {
+    let (git, gix) = backends(fixture);
+    assert_eq!(
+        gix.get_staged_unified_diff(3, false).await.expect("gitoxide staged diff"),
+        git.get_staged_unified_diff(3, false).await.expect("git staged diff"),
+    );
+    assert_eq!(
+        gix.get_unstaged_unified_diff(3, false).await.expect("gitoxide unstaged diff"),
+        git.get_unstaged_unified_diff(3, false).await.expect("git unstaged diff"),
+    );
+}
+
+#[cfg(unix)]
+fn set_executable(fixture: &Fixture, path: &str, executable: bool) {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = if executable { 0o755 } else { 0o644 };
+    std::fs::set_permissions(fixture.path().join(path), std::fs::Permissions::from_mode(mode))
+        .expect("set permissions");
+}
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|n| format!("line {}\n", n)).collect()
+}
+
+#[tokio::test]
+async fn renames_match_git() {
+    let fixture = Fixture::new();
+    fixture.write("src/moved.rs", numbered_lines(40).as_bytes());
+    fixture.write("src/edited.rs", numbered_lines(30).as_bytes());
+    fixture.write("keep.txt", b"unchanged\n");
+    fixture.commit("initial");
+
+    std::fs::create_dir_all(fixture.path().join("lib")).expect("create dir");
+    std::fs::rename(fixture.path().join("src/moved.rs"), fixture.path().join("lib/moved.rs"))
+        .expect("move file");
+    let edited = numbered_lines(30).replace("line 7\n", "line seven\n");
+    std::fs::remove_file(fixture.path().join("src/edited.rs")).expect("remove file");
+    fixture.write("src/renamed.rs", edited.as_bytes());
+    fixture.commit("rename");
+
+    assert_same_commit_diff(&fixture).await;
+}
+
+#[tokio::test]
+async fn binary_files_match_git() {
+    let fixture = Fixture::new();
+    fixture.write("image.bin", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+    fixture.write("text.txt", b"plain\n");
+    fixture.commit("initial");
+
+    fixture.write("image.bin", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01");
+    fixture.write("added.bin", b"\0\x01\x02\x03");
+    fixture.write("text.txt", b"now\0binary\n");
+    fixture.commit("binary");
+
+    assert_same_commit_diff(&fixture).await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn mode_changes_match_git() {
+    let fixture = Fixture::new();
+    fixture.write("run.sh", b"#!/bin/sh\necho hi\n");
+    fixture.write("tool.sh", b"#!/bin/sh\necho tool\n");
+    fixture.commit("initial");
+
+    set_executable(&fixture, "run.sh", true);
+    fixture.write("tool.sh", b"#!/bin/sh\necho changed\n");
+    set_executable(&fixture, "tool.sh", true);
+    fixture.commit("modes");
+    assert_same_commit_diff(&fixture).await;
+
+    // Flipped back in the working tree, and staged for one of them
+    set_executable(&fixture, "run.sh", false);
+    set_executable(&fixture, "tool.sh", false);
+    fixture.git(&["add", "tool.sh"]);
+    assert_same_local_diffs(&fixture).await;
+}
+
+#[tokio::test]
+async fn missing_final_newline_matches_git() {
+    let fixture = Fixture::new();
+    fixture.write("gains.txt", b"one\ntwo");
+    fixture.write("loses.txt", b"one\ntwo\n");
+    fixture.write("edited.txt", b"one\ntwo\nthree");
+    fixture.write("short.txt", b"only");
+    fixture.commit("initial");
+
+    fixture.write("gains.txt", b"one\ntwo\n");
+    fixture.write("loses.txt", b"one\ntwo");
+    fixture.write("edited.txt", b"one\n2\nthree");
+    fixture.write("short.txt", b"changed");
+    fixture.commit("newlines");
+    assert_same_commit_diff(&fixture).await;
+
+    fixture.write("edited.txt", b"one\n2\n3");
+    fixture.write("short.txt", b"changed\n");
+    fixture.git(&["add", "short.txt"]);
+    assert_same_local_diffs(&fixture).await;
+}
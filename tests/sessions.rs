@@ -0,0 +1,89 @@
+mod common;
+
+use common::Fixture;
+use crai::config::DiffConfig;
+use crai::diff::interdiff::Interdiff;
+use crai::diff::{DiffParser, DiffResult};
+use crai::review::session::UserChunkStatus;
+use crai::review::{ReviewSession, SessionStore};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|n| format!("line {}\n", n)).collect()
+}
+
+fn chunk_status(session: &ReviewSession, path: &str) -> UserChunkStatus {
+    let (file_idx, file) = session
+        .diff_result
+        .files
+        .iter()
+        .enumerate()
+        .find(|(_, f)| f.path == Path::new(path))
+        .expect("file in diff");
+    session.chunk_status(file_idx, file.chunks[0].id)
+}
+
+fn mark(session: &mut ReviewSession, path: &str, status: UserChunkStatus) {
+    let file_idx = session
+        .diff_result
+        .files
+        .iter()
+        .position(|f| f.path == Path::new(path))
+        .expect("file in diff");
+    let chunk_id = session.diff_result.files[file_idx].chunks[0].id;
+    session
+        .file_states
+        .entry(file_idx)
+        .or_default()
+        .chunk_states
+        .entry(chunk_id)
+        .or_default()
+        .user_status = status;
+}
+
+async fn branch_diff(parser: &DiffParser, compare: &str) -> DiffResult {
+    parser.parse_merge_base("main", compare).await.expect("diff parses")
+}
+
+#[tokio::test]
+async fn since_carries_over_review_saved_at_earlier_tip() {
+    let fixture = Fixture::new();
+    fixture.git(&["checkout", "-q", "-b", "main"]);
+    fixture.write("kept.txt", numbered_lines(20).as_bytes());
+    fixture.write("changed.txt", numbered_lines(20).as_bytes());
+    fixture.commit("initial");
+
+    fixture.git(&["checkout", "-q", "-b", "feature"]);
+    fixture.write("kept.txt", numbered_lines(20).replace("line 3\n", "line three\n").as_bytes());
+    fixture.write("changed.txt", numbered_lines(20).replace("line 3\n", "line three\n").as_bytes());
+    fixture.commit("first round");
+    let reviewed_tip = fixture.git(&["rev-parse", "HEAD"]);
+
+    let cache = TempDir::new().expect("temp dir");
+    let store = SessionStore::new(cache.path(), fixture.path());
+    let parser = DiffParser::new(fixture.path().to_path_buf(), &DiffConfig::default());
+
+    // Reviewed as the branch checked out, the way a plain run sees it
+    let mut first = ReviewSession::new(branch_diff(&parser, "HEAD").await);
+    mark(&mut first, "kept.txt", UserChunkStatus::Approved);
+    mark(&mut first, "changed.txt", UserChunkStatus::RequestedChanges);
+    store.save(&first).expect("session saves");
+
+    fixture.write("changed.txt", numbered_lines(20).replace("line 3\n", "line 3 again\n").as_bytes());
+    fixture.commit("second round");
+
+    let previous = branch_diff(&parser, &reviewed_tip).await;
+    let mut current = branch_diff(&parser, "HEAD").await;
+    current.interdiff = Some(Interdiff::compute(&reviewed_tip, &previous, &current));
+
+    let mut session = ReviewSession::new(current);
+    let previous = store
+        .load_previous(&previous)
+        .expect("session loads")
+        .expect("review saved at the earlier tip");
+    session.carry_over(&previous);
+
+    assert_eq!(chunk_status(&session, "kept.txt"), UserChunkStatus::Approved);
+    assert_eq!(chunk_status(&session, "changed.txt"), UserChunkStatus::Unreviewed);
+}